@@ -0,0 +1,37 @@
+-- Add up migration script here
+-- Persisted scores on questions and answers
+ALTER TABLE questions
+ADD COLUMN IF NOT EXISTS score INT NOT NULL DEFAULT 0;
+ALTER TABLE answers
+ADD COLUMN IF NOT EXISTS score INT NOT NULL DEFAULT 0;
+-- Only bump `updated_at` when the post itself changes, not its score
+CREATE OR REPLACE FUNCTION update_questions_timestamp() RETURNS TRIGGER AS $$ BEGIN IF ROW(NEW.title, NEW.slug, NEW.content, NEW.raw_content) IS DISTINCT
+FROM ROW(OLD.title, OLD.slug, OLD.content, OLD.raw_content) THEN NEW.updated_at = NOW();
+END IF;
+RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+CREATE OR REPLACE FUNCTION update_answers_timestamp() RETURNS TRIGGER AS $$ BEGIN IF ROW(NEW.content, NEW.raw_content) IS DISTINCT
+FROM ROW(OLD.content, OLD.raw_content) THEN NEW.updated_at = NOW();
+END IF;
+RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+-- Question votes table
+CREATE TABLE IF NOT EXISTS question_votes (
+    question UUID NOT NULL REFERENCES questions(id) ON DELETE CASCADE,
+    voter UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    value SMALLINT NOT NULL CHECK (value IN (-1, 1)),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (question, voter)
+);
+CREATE INDEX IF NOT EXISTS question_votes_index_voter ON question_votes (voter);
+-- Answer votes table
+CREATE TABLE IF NOT EXISTS answer_votes (
+    answer UUID NOT NULL REFERENCES answers(id) ON DELETE CASCADE,
+    voter UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    value SMALLINT NOT NULL CHECK (value IN (-1, 1)),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (answer, voter)
+);
+CREATE INDEX IF NOT EXISTS answer_votes_index_voter ON answer_votes (voter);
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS questions_index_accepted_answer;
+ALTER TABLE questions DROP COLUMN IF EXISTS accepted_answer;
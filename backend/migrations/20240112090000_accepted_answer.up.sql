@@ -0,0 +1,6 @@
+-- Add up migration script here
+-- At most one accepted answer per question, cleared if that answer is deleted
+ALTER TABLE questions
+ADD COLUMN IF NOT EXISTS accepted_answer UUID NULL REFERENCES answers(id) ON DELETE
+SET NULL;
+CREATE INDEX IF NOT EXISTS questions_index_accepted_answer ON questions (accepted_answer);
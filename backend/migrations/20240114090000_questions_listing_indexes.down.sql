@@ -0,0 +1,7 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS question_tags_index_tag;
+DROP INDEX IF EXISTS answers_index_question;
+DROP INDEX IF EXISTS questions_index_author;
+DROP INDEX IF EXISTS questions_index_score;
+DROP INDEX IF EXISTS questions_index_updated_at;
+DROP INDEX IF EXISTS questions_index_created_at;
@@ -0,0 +1,7 @@
+-- Add up migration script here
+CREATE INDEX IF NOT EXISTS questions_index_created_at ON questions (created_at DESC);
+CREATE INDEX IF NOT EXISTS questions_index_updated_at ON questions (updated_at DESC);
+CREATE INDEX IF NOT EXISTS questions_index_score ON questions (score DESC);
+CREATE INDEX IF NOT EXISTS questions_index_author ON questions (author);
+CREATE INDEX IF NOT EXISTS answers_index_question ON answers (question);
+CREATE INDEX IF NOT EXISTS question_tags_index_tag ON question_tags (tag);
@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS answers_index_search_vector;
+ALTER TABLE answers DROP COLUMN IF EXISTS search_vector;
+DROP INDEX IF EXISTS questions_index_search_vector;
+ALTER TABLE questions DROP COLUMN IF EXISTS search_vector;
@@ -0,0 +1,15 @@
+-- Add up migration script here
+-- Titles outrank bodies when searching questions
+ALTER TABLE questions
+ADD COLUMN IF NOT EXISTS search_vector tsvector GENERATED ALWAYS AS (
+        setweight(to_tsvector('english', coalesce(title, '')), 'A') || setweight(
+            to_tsvector('english', coalesce(raw_content, '')),
+            'B'
+        )
+    ) STORED;
+CREATE INDEX IF NOT EXISTS questions_index_search_vector ON questions USING GIN (search_vector);
+ALTER TABLE answers
+ADD COLUMN IF NOT EXISTS search_vector tsvector GENERATED ALWAYS AS (
+        to_tsvector('english', coalesce(raw_content, ''))
+    ) STORED;
+CREATE INDEX IF NOT EXISTS answers_index_search_vector ON answers USING GIN (search_vector);
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS comments;
+DROP FUNCTION IF EXISTS update_comments_timestamp();
@@ -0,0 +1,27 @@
+-- Add up migration script here
+-- Trigger function to update the timestamp on the 'comments' table
+CREATE OR REPLACE FUNCTION update_comments_timestamp() RETURNS TRIGGER AS $$ BEGIN IF ROW(NEW.content, NEW.raw_content) IS DISTINCT
+FROM ROW(OLD.content, OLD.raw_content) THEN NEW.updated_at = NOW();
+END IF;
+RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+-- Comments table. A comment belongs to exactly one question or answer and
+-- may reply to another comment on the same post.
+CREATE TABLE IF NOT EXISTS comments (
+    id UUID NOT NULL PRIMARY KEY DEFAULT gen_random_uuid(),
+    content TEXT NOT NULL,
+    raw_content TEXT NOT NULL CHECK (char_length(raw_content) <= 600),
+    author UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    question UUID NULL REFERENCES questions(id) ON DELETE CASCADE,
+    answer UUID NULL REFERENCES answers(id) ON DELETE CASCADE,
+    parent UUID NULL REFERENCES comments(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT comments_single_target CHECK ((question IS NULL) <> (answer IS NULL))
+);
+CREATE INDEX IF NOT EXISTS comments_index_question ON comments (question, created_at);
+CREATE INDEX IF NOT EXISTS comments_index_answer ON comments (answer, created_at);
+CREATE INDEX IF NOT EXISTS comments_index_parent ON comments (parent);
+CREATE TRIGGER update_comments_timestamp BEFORE
+UPDATE ON comments FOR EACH ROW EXECUTE PROCEDURE update_comments_timestamp();
@@ -0,0 +1,47 @@
+-- Add up migration script here
+-- Question revisions table. Every create or edit stores a full snapshot.
+CREATE TABLE IF NOT EXISTS question_revisions (
+    id UUID NOT NULL PRIMARY KEY DEFAULT gen_random_uuid(),
+    question UUID NOT NULL REFERENCES questions(id) ON DELETE CASCADE,
+    revision INT NOT NULL,
+    editor UUID NULL REFERENCES users(id) ON DELETE SET NULL,
+    title TEXT NOT NULL,
+    tags TEXT [] NOT NULL DEFAULT '{}',
+    raw_content TEXT NOT NULL,
+    edit_summary TEXT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (question, revision)
+);
+-- Answer revisions table
+CREATE TABLE IF NOT EXISTS answer_revisions (
+    id UUID NOT NULL PRIMARY KEY DEFAULT gen_random_uuid(),
+    answer UUID NOT NULL REFERENCES answers(id) ON DELETE CASCADE,
+    revision INT NOT NULL,
+    editor UUID NULL REFERENCES users(id) ON DELETE SET NULL,
+    raw_content TEXT NOT NULL,
+    edit_summary TEXT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (answer, revision)
+);
+-- Existing posts start with their current state as the first revision
+INSERT INTO question_revisions (question, revision, editor, title, tags, raw_content, created_at)
+SELECT q.id,
+    1,
+    q.author,
+    q.title,
+    ARRAY(
+        SELECT qt.tag
+        FROM question_tags qt
+        WHERE qt.question = q.id
+        ORDER BY qt.tag
+    ),
+    q.raw_content,
+    q.updated_at
+FROM questions q ON CONFLICT DO NOTHING;
+INSERT INTO answer_revisions (answer, revision, editor, raw_content, created_at)
+SELECT a.id,
+    1,
+    a.author,
+    a.raw_content,
+    a.updated_at
+FROM answers a ON CONFLICT DO NOTHING;
@@ -0,0 +1,19 @@
+-- Add down migration script here
+CREATE OR REPLACE FUNCTION update_questions_timestamp() RETURNS TRIGGER AS $$ BEGIN IF ROW(NEW.title, NEW.slug, NEW.content, NEW.raw_content) IS DISTINCT
+FROM ROW(OLD.title, OLD.slug, OLD.content, OLD.raw_content) THEN NEW.updated_at = NOW();
+END IF;
+RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+CREATE OR REPLACE FUNCTION update_answers_timestamp() RETURNS TRIGGER AS $$ BEGIN IF ROW(NEW.content, NEW.raw_content) IS DISTINCT
+FROM ROW(OLD.content, OLD.raw_content) THEN NEW.updated_at = NOW();
+END IF;
+RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+CREATE OR REPLACE FUNCTION update_comments_timestamp() RETURNS TRIGGER AS $$ BEGIN IF ROW(NEW.content, NEW.raw_content) IS DISTINCT
+FROM ROW(OLD.content, OLD.raw_content) THEN NEW.updated_at = NOW();
+END IF;
+RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
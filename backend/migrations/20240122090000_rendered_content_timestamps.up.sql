@@ -0,0 +1,21 @@
+-- Add up migration script here
+-- `content` is derived from `raw_content`, so re-rendering it (e.g. after the
+-- sanitizer changes) must not count as an edit of the post.
+CREATE OR REPLACE FUNCTION update_questions_timestamp() RETURNS TRIGGER AS $$ BEGIN IF ROW(NEW.title, NEW.slug, NEW.raw_content) IS DISTINCT
+FROM ROW(OLD.title, OLD.slug, OLD.raw_content) THEN NEW.updated_at = NOW();
+END IF;
+RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+CREATE OR REPLACE FUNCTION update_answers_timestamp() RETURNS TRIGGER AS $$ BEGIN IF NEW.raw_content IS DISTINCT
+FROM OLD.raw_content THEN NEW.updated_at = NOW();
+END IF;
+RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+CREATE OR REPLACE FUNCTION update_comments_timestamp() RETURNS TRIGGER AS $$ BEGIN IF NEW.raw_content IS DISTINCT
+FROM OLD.raw_content THEN NEW.updated_at = NOW();
+END IF;
+RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
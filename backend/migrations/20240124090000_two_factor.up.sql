@@ -0,0 +1,13 @@
+-- Add up migration script here
+-- Base32 TOTP secret, only set once enrollment has been confirmed
+ALTER TABLE users
+ADD COLUMN IF NOT EXISTS totp_secret TEXT NULL;
+-- Single-use recovery codes, stored hashed
+CREATE TABLE IF NOT EXISTS user_recovery_codes (
+    id UUID NOT NULL PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    code_hash TEXT NOT NULL,
+    used_at TIMESTAMPTZ NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, code_hash)
+);
@@ -0,0 +1,9 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS moderation_actions;
+ALTER TABLE answers DROP COLUMN IF EXISTS locked_by,
+    DROP COLUMN IF EXISTS locked_at;
+ALTER TABLE questions DROP COLUMN IF EXISTS locked_by,
+    DROP COLUMN IF EXISTS locked_at,
+    DROP COLUMN IF EXISTS close_reason,
+    DROP COLUMN IF EXISTS closed_by,
+    DROP COLUMN IF EXISTS closed_at;
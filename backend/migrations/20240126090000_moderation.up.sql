@@ -0,0 +1,27 @@
+-- Add up migration script here
+-- Closed questions take no new answers; locked posts are frozen for everyone
+-- but moderators.
+ALTER TABLE questions
+ADD COLUMN IF NOT EXISTS closed_at TIMESTAMPTZ NULL,
+    ADD COLUMN IF NOT EXISTS closed_by UUID NULL REFERENCES users(id) ON DELETE
+SET NULL,
+    ADD COLUMN IF NOT EXISTS close_reason TEXT NULL,
+    ADD COLUMN IF NOT EXISTS locked_at TIMESTAMPTZ NULL,
+    ADD COLUMN IF NOT EXISTS locked_by UUID NULL REFERENCES users(id) ON DELETE
+SET NULL;
+ALTER TABLE answers
+ADD COLUMN IF NOT EXISTS locked_at TIMESTAMPTZ NULL,
+    ADD COLUMN IF NOT EXISTS locked_by UUID NULL REFERENCES users(id) ON DELETE
+SET NULL;
+-- Moderation log. Targets are not foreign keys so deletions stay on record.
+CREATE TABLE IF NOT EXISTS moderation_actions (
+    id UUID NOT NULL PRIMARY KEY DEFAULT gen_random_uuid(),
+    moderator UUID NULL REFERENCES users(id) ON DELETE SET NULL,
+    action TEXT NOT NULL,
+    question UUID NULL,
+    answer UUID NULL,
+    reason TEXT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS moderation_actions_created_at_indx ON moderation_actions (created_at DESC);
+CREATE INDEX IF NOT EXISTS moderation_actions_moderator_indx ON moderation_actions (moderator);
@@ -0,0 +1,6 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS users_date_joined_indx;
+ALTER TABLE users DROP COLUMN IF EXISTS deactivated_at,
+    DROP COLUMN IF EXISTS suspended_by,
+    DROP COLUMN IF EXISTS suspension_reason,
+    DROP COLUMN IF EXISTS suspended_until;
@@ -0,0 +1,12 @@
+-- Add up migration script here
+-- A suspension ends on its own once `suspended_until` has passed
+ALTER TABLE users
+ADD COLUMN IF NOT EXISTS suspended_until TIMESTAMPTZ NULL,
+    ADD COLUMN IF NOT EXISTS suspension_reason TEXT NULL,
+    ADD COLUMN IF NOT EXISTS suspended_by UUID NULL REFERENCES users(id) ON DELETE
+SET NULL;
+-- Set when an administrator deactivates the account, so that it cannot be
+-- re-activated with an activation code
+ALTER TABLE users
+ADD COLUMN IF NOT EXISTS deactivated_at TIMESTAMPTZ NULL;
+CREATE INDEX IF NOT EXISTS users_date_joined_indx ON users (date_joined DESC, id);
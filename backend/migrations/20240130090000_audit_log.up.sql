@@ -0,0 +1,26 @@
+-- Add up migration script here
+-- Append-only trail of security-sensitive and moderation events. Users are
+-- referenced without foreign keys so entries outlive the accounts they name.
+CREATE TABLE IF NOT EXISTS audit_log (
+    id UUID NOT NULL PRIMARY KEY DEFAULT gen_random_uuid(),
+    event TEXT NOT NULL,
+    actor UUID NULL,
+    target_type TEXT NULL,
+    target_id UUID NULL,
+    ip_address TEXT NULL,
+    user_agent TEXT NULL,
+    payload JSONB NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS audit_log_created_at_indx ON audit_log (created_at DESC, id);
+CREATE INDEX IF NOT EXISTS audit_log_event_indx ON audit_log (event, created_at DESC);
+CREATE INDEX IF NOT EXISTS audit_log_actor_indx ON audit_log (actor, created_at DESC);
+CREATE INDEX IF NOT EXISTS audit_log_target_indx ON audit_log (target_id, created_at DESC);
+-- Entries can be added but never changed or removed
+CREATE OR REPLACE FUNCTION prevent_audit_log_changes() RETURNS TRIGGER AS $$ BEGIN RAISE EXCEPTION 'audit_log is append-only';
+END;
+$$ LANGUAGE plpgsql;
+CREATE TRIGGER audit_log_append_only BEFORE
+UPDATE
+    OR DELETE ON audit_log FOR EACH ROW EXECUTE PROCEDURE prevent_audit_log_changes();
+CREATE TRIGGER audit_log_no_truncate BEFORE TRUNCATE ON audit_log FOR EACH STATEMENT EXECUTE PROCEDURE prevent_audit_log_changes();
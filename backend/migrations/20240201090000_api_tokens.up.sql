@@ -0,0 +1,15 @@
+-- Add up migration script here
+-- Personal access tokens. Only a SHA-256 hash of the token is kept; the
+-- prefix lets users tell their tokens apart.
+CREATE TABLE IF NOT EXISTS api_tokens(
+    id UUID NOT NULL PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    token_prefix TEXT NOT NULL,
+    scope TEXT NOT NULL CHECK (scope IN ('read', 'write')),
+    expires_at TIMESTAMPTZ NOT NULL,
+    last_used_at TIMESTAMPTZ NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS api_tokens_user_id_indx ON api_tokens (user_id, created_at DESC);
@@ -0,0 +1,9 @@
+-- Add down migration script here
+ALTER TABLE questions DROP CONSTRAINT IF EXISTS questions_author_fkey,
+    ADD CONSTRAINT questions_author_fkey FOREIGN KEY (author) REFERENCES users(id) ON DELETE CASCADE;
+ALTER TABLE answers DROP CONSTRAINT IF EXISTS answers_author_fkey,
+    ADD CONSTRAINT answers_author_fkey FOREIGN KEY (author) REFERENCES users(id) ON DELETE CASCADE;
+ALTER TABLE comments DROP CONSTRAINT IF EXISTS comments_author_fkey,
+    ADD CONSTRAINT comments_author_fkey FOREIGN KEY (author) REFERENCES users(id) ON DELETE CASCADE;
+DELETE FROM users
+WHERE id = '00000000-0000-0000-0000-000000000000';
@@ -0,0 +1,29 @@
+-- Add up migration script here
+-- Placeholder that takes over the posts of anonymized accounts. It cannot log
+-- in, and being deactivated it cannot be activated either.
+INSERT INTO users (
+        id,
+        email,
+        password,
+        first_name,
+        last_name,
+        is_active,
+        deactivated_at
+    )
+VALUES (
+        '00000000-0000-0000-0000-000000000000',
+        'deleted-user@cryptoflow.invalid',
+        '!',
+        'Deleted',
+        'User',
+        FALSE,
+        NOW()
+    ) ON CONFLICT (id) DO NOTHING;
+-- Authored content is dealt with explicitly when an account is deleted.
+-- Cascading took other people's answers down with the author's questions.
+ALTER TABLE questions DROP CONSTRAINT IF EXISTS questions_author_fkey,
+    ADD CONSTRAINT questions_author_fkey FOREIGN KEY (author) REFERENCES users(id);
+ALTER TABLE answers DROP CONSTRAINT IF EXISTS answers_author_fkey,
+    ADD CONSTRAINT answers_author_fkey FOREIGN KEY (author) REFERENCES users(id);
+ALTER TABLE comments DROP CONSTRAINT IF EXISTS comments_author_fkey,
+    ADD CONSTRAINT comments_author_fkey FOREIGN KEY (author) REFERENCES users(id);
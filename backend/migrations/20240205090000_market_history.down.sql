@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS daily_candles;
+DROP TABLE IF EXISTS price_snapshots;
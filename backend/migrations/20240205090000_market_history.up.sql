@@ -0,0 +1,24 @@
+-- Add up migration script here
+-- Prices recorded by the market history job, and backfilled from market charts.
+CREATE TABLE IF NOT EXISTS price_snapshots (
+    coin VARCHAR(255) NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+    vs_currency VARCHAR(16) NOT NULL,
+    captured_at TIMESTAMPTZ NOT NULL,
+    price DOUBLE PRECISION NOT NULL,
+    market_cap DOUBLE PRECISION NULL,
+    total_volume DOUBLE PRECISION NULL,
+    PRIMARY KEY (coin, vs_currency, captured_at)
+);
+-- One candle per coin, currency and UTC day, rebuilt from market charts.
+CREATE TABLE IF NOT EXISTS daily_candles (
+    coin VARCHAR(255) NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+    vs_currency VARCHAR(16) NOT NULL,
+    day DATE NOT NULL,
+    open DOUBLE PRECISION NOT NULL,
+    high DOUBLE PRECISION NOT NULL,
+    low DOUBLE PRECISION NOT NULL,
+    close DOUBLE PRECISION NOT NULL,
+    volume DOUBLE PRECISION NOT NULL DEFAULT 0,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (coin, vs_currency, day)
+);
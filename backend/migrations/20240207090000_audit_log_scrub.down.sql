@@ -0,0 +1,4 @@
+-- Add down migration script here
+CREATE OR REPLACE FUNCTION prevent_audit_log_changes() RETURNS TRIGGER AS $$ BEGIN RAISE EXCEPTION 'audit_log is append-only';
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,27 @@
+-- Add up migration script here
+-- Entries stay append-only, except that the personal data of a deleted
+-- account may be taken out of them: addresses, user agents and emails can
+-- be cleared, while what happened, to whom and when stays as it was.
+CREATE OR REPLACE FUNCTION prevent_audit_log_changes() RETURNS TRIGGER AS $$ BEGIN IF TG_OP = 'UPDATE'
+    AND NEW.id = OLD.id
+    AND NEW.event = OLD.event
+    AND NEW.actor IS NOT DISTINCT FROM OLD.actor
+    AND NEW.target_type IS NOT DISTINCT FROM OLD.target_type
+    AND NEW.target_id IS NOT DISTINCT FROM OLD.target_id
+    AND NEW.created_at = OLD.created_at
+    AND (
+        NEW.ip_address IS NULL
+        OR NEW.ip_address = OLD.ip_address
+    )
+    AND (
+        NEW.user_agent IS NULL
+        OR NEW.user_agent = OLD.user_agent
+    )
+    AND (
+        NEW.payload = OLD.payload
+        OR NEW.payload = OLD.payload - ARRAY ['email', 'old_email', 'new_email']
+    ) THEN RETURN NEW;
+END IF;
+RAISE EXCEPTION 'audit_log is append-only';
+END;
+$$ LANGUAGE plpgsql;
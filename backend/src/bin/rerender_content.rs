@@ -0,0 +1,31 @@
+//! One-off job re-rendering the stored HTML of all questions, answers and
+//! comments from their raw markdown. Run it after changing how markdown is
+//! rendered or sanitized, e.g. `cargo run --release --bin rerender_content`.
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+#[tokio::main]
+async fn main() -> Result<(), sqlx::Error> {
+    dotenv::dotenv().ok();
+
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| "backend=info,rerender_content=info".into()),
+        )
+        .with(tracing_subscriber::fmt::layer())
+        .init();
+
+    let db_url = std::env::var("DATABASE_URL").expect("Failed to get DATABASE_URL.");
+    let store = backend::store::Store::new(&db_url).await;
+
+    sqlx::migrate!()
+        .run(&store.connection)
+        .await
+        .expect("Failed to migrate");
+
+    for (table, updated) in store.rerender_content_in_db().await? {
+        tracing::info!("{}: {} rows re-rendered", table, updated);
+    }
+
+    Ok(())
+}
@@ -1,3 +1,4 @@
+pub mod market_data;
 pub mod models;
 pub mod routes;
 pub mod settings;
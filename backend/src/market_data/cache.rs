@@ -0,0 +1,498 @@
+use super::{
+    CoinMarketData, MarketDataError, MarketDataProvider, PriceOptions, Quote, SimplePrices,
+};
+use bb8_redis::bb8;
+use std::sync::Arc;
+
+/// Tells clients whether market data came from the cache.
+pub const CACHE_STATUS_HEADER: &str = "x-cache";
+
+/// How long a background refresh holds its key, so that concurrent requests
+/// for the same stale entry start a single refresh.
+const REFRESH_LOCK_SECONDS: u64 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CacheStatus {
+    Hit,
+    /// Served from the cache past its TTL while a refresh runs.
+    Stale,
+    Miss,
+}
+
+impl CacheStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CacheStatus::Hit => "HIT",
+            CacheStatus::Stale => "STALE",
+            CacheStatus::Miss => "MISS",
+        }
+    }
+
+    /// The status of a response built from several entries is the worst one.
+    pub fn combine(self, other: CacheStatus) -> CacheStatus {
+        self.max(other)
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct CachedEntry<T> {
+    fetched_at: i64,
+    data: T,
+}
+
+fn is_fresh<T>(entry: &CachedEntry<T>, ttl_seconds: u64) -> bool {
+    chrono::Utc::now().timestamp() - entry.fetched_at < ttl_seconds as i64
+}
+
+fn price_key(id: &str, currency: &str) -> String {
+    format!("market_data:price:{}:{}", id, currency)
+}
+
+/// `USD` and `usd` share an entry.
+fn chart_key(id: &str, currency: &str, days: i32) -> String {
+    format!(
+        "market_data:chart:{}:{}:{}",
+        id,
+        currency.trim().to_lowercase(),
+        days
+    )
+}
+
+const CURRENCIES_KEY: &str = "market_data:supported_vs_currencies";
+
+/// Caches provider responses in Redis. Entries are served as they are for
+/// their TTL, then for `stale_seconds` more while they are refreshed in the
+/// background. Redis failures only cost the cache, never the request.
+#[derive(Clone)]
+pub struct MarketDataCache {
+    provider: Arc<dyn MarketDataProvider>,
+    redis_store: bb8::Pool<bb8_redis::RedisConnectionManager>,
+    settings: crate::settings::MarketDataCacheSettings,
+}
+
+impl MarketDataCache {
+    pub fn new(
+        provider: Arc<dyn MarketDataProvider>,
+        redis_store: bb8::Pool<bb8_redis::RedisConnectionManager>,
+        settings: crate::settings::MarketDataCacheSettings,
+    ) -> Self {
+        MarketDataCache {
+            provider,
+            redis_store,
+            settings,
+        }
+    }
+
+    /// The provider itself, for calls that must not be cached.
+    pub fn provider(&self) -> &dyn MarketDataProvider {
+        self.provider.as_ref()
+    }
+
+    #[tracing::instrument(name = "cached_supported_vs_currencies", skip(self))]
+    pub async fn supported_vs_currencies(
+        &self,
+    ) -> Result<(Vec<String>, CacheStatus), MarketDataError> {
+        self.get_or_fetch(
+            CURRENCIES_KEY.to_string(),
+            self.settings.currencies_ttl_seconds,
+            |provider| async move { provider.supported_vs_currencies().await },
+        )
+        .await
+    }
+
+    #[tracing::instrument(name = "cached_market_chart", skip(self))]
+    pub async fn market_chart(
+        &self,
+        id: &str,
+        vs_currency: &str,
+        days: i32,
+    ) -> Result<(CoinMarketData, CacheStatus), MarketDataError> {
+        let vs_currency = vs_currency.trim().to_lowercase();
+        let key = chart_key(id, &vs_currency, days);
+        let id = id.to_string();
+
+        self.get_or_fetch(key, self.settings.chart_ttl_seconds, move |provider| {
+            let (id, vs_currency) = (id.clone(), vs_currency.clone());
+            async move { provider.market_chart(&id, &vs_currency, days).await }
+        })
+        .await
+    }
+
+    /// Quotes are cached per coin and currency, with all figures, so that any
+    /// combination of coins, currencies and options shares the entries.
+    #[tracing::instrument(name = "cached_simple_prices", skip(self))]
+    pub async fn simple_prices(
+        &self,
+        ids: &[String],
+        vs_currencies: &[String],
+        options: PriceOptions,
+    ) -> Result<(SimplePrices, CacheStatus), MarketDataError> {
+        let pairs: Vec<(&String, &String)> = ids
+            .iter()
+            .flat_map(|id| vs_currencies.iter().map(move |currency| (id, currency)))
+            .collect();
+        let keys: Vec<String> = pairs
+            .iter()
+            .map(|(id, currency)| price_key(id, currency))
+            .collect();
+        // A cached `None` means the provider returned nothing for that pair
+        let entries = read_entries::<Option<Quote>>(&self.redis_store, &keys).await;
+
+        let mut prices = SimplePrices::new();
+        let mut missing_ids: Vec<String> = Vec::new();
+        let mut stale_ids: Vec<String> = Vec::new();
+        for ((id, currency), entry) in pairs.into_iter().zip(entries) {
+            match entry {
+                Some(entry) => {
+                    let fresh = is_fresh(&entry, self.settings.prices_ttl_seconds);
+                    // Unknown pairs simply expire, there is nothing to refresh
+                    let Some(quote) = entry.data else {
+                        continue;
+                    };
+                    if !fresh && !stale_ids.contains(id) {
+                        stale_ids.push(id.clone());
+                    }
+                    prices
+                        .entry(id.clone())
+                        .or_default()
+                        .insert(currency.clone(), quote);
+                }
+                None => {
+                    if !missing_ids.contains(id) {
+                        missing_ids.push(id.clone());
+                    }
+                }
+            }
+        }
+        // Coins refetched anyway need no background refresh
+        stale_ids.retain(|id| !missing_ids.contains(id));
+
+        let mut status = CacheStatus::Hit;
+        if !missing_ids.is_empty() {
+            status = CacheStatus::Miss;
+            let fetched = fetch_and_store_prices(
+                self.provider.as_ref(),
+                &self.redis_store,
+                &missing_ids,
+                vs_currencies,
+                self.entry_lifetime(self.settings.prices_ttl_seconds),
+                self.settings.unknown_ttl_seconds,
+            )
+            .await?;
+            for (id, quotes) in fetched {
+                prices.entry(id).or_default().extend(quotes);
+            }
+        }
+
+        if !stale_ids.is_empty() {
+            status = status.combine(CacheStatus::Stale);
+            self.refresh_prices_in_background(stale_ids, vs_currencies.to_vec());
+        }
+
+        for quotes in prices.values_mut() {
+            for quote in quotes.values_mut() {
+                if !options.include_market_cap {
+                    quote.market_cap = None;
+                }
+                if !options.include_24hr_change {
+                    quote.change_24h = None;
+                }
+            }
+        }
+
+        Ok((prices, status))
+    }
+
+    /// How long Redis keeps an entry: its TTL plus the time it may be served
+    /// stale.
+    fn entry_lifetime(&self, ttl_seconds: u64) -> u64 {
+        ttl_seconds + self.settings.stale_seconds
+    }
+
+    async fn get_or_fetch<T, F, Fut>(
+        &self,
+        key: String,
+        ttl_seconds: u64,
+        fetch: F,
+    ) -> Result<(T, CacheStatus), MarketDataError>
+    where
+        T: serde::Serialize + serde::de::DeserializeOwned + Send + Sync + 'static,
+        F: Fn(Arc<dyn MarketDataProvider>) -> Fut + Send + 'static,
+        Fut: std::future::Future<Output = Result<T, MarketDataError>> + Send + 'static,
+    {
+        let lifetime = self.entry_lifetime(ttl_seconds);
+        let entry = read_entries::<T>(&self.redis_store, std::slice::from_ref(&key))
+            .await
+            .pop()
+            .flatten();
+
+        if let Some(entry) = entry {
+            if is_fresh(&entry, ttl_seconds) {
+                return Ok((entry.data, CacheStatus::Hit));
+            }
+
+            let provider = self.provider.clone();
+            let redis_store = self.redis_store.clone();
+            tokio::spawn(async move {
+                if !claim_refresh(&redis_store, &key).await {
+                    return;
+                }
+                match fetch(provider).await {
+                    Ok(data) => store_entries(&redis_store, &[(key.clone(), data)], lifetime).await,
+                    Err(e) => tracing::warn!("Failed to refresh {}: {}", key, e),
+                }
+                release_refresh(&redis_store, &[key]).await;
+            });
+            return Ok((entry.data, CacheStatus::Stale));
+        }
+
+        let data = fetch(self.provider.clone()).await?;
+        let entries = [(key, data)];
+        store_entries(&self.redis_store, &entries, lifetime).await;
+        let [(_, data)] = entries;
+
+        Ok((data, CacheStatus::Miss))
+    }
+
+    fn refresh_prices_in_background(&self, ids: Vec<String>, vs_currencies: Vec<String>) {
+        let provider = self.provider.clone();
+        let redis_store = self.redis_store.clone();
+        let lifetime = self.entry_lifetime(self.settings.prices_ttl_seconds);
+        let unknown_lifetime = self.settings.unknown_ttl_seconds;
+
+        tokio::spawn(async move {
+            // Quotes are refreshed per coin, in all the requested currencies
+            let mut claimed = Vec::new();
+            let mut claimed_keys = Vec::new();
+            for id in ids {
+                let key = format!("market_data:price:{}", id);
+                if claim_refresh(&redis_store, &key).await {
+                    claimed.push(id);
+                    claimed_keys.push(key);
+                }
+            }
+            if claimed.is_empty() {
+                return;
+            }
+
+            if let Err(e) = fetch_and_store_prices(
+                provider.as_ref(),
+                &redis_store,
+                &claimed,
+                &vs_currencies,
+                lifetime,
+                unknown_lifetime,
+            )
+            .await
+            {
+                tracing::warn!("Failed to refresh prices of {:?}: {}", claimed, e);
+            }
+            release_refresh(&redis_store, &claimed_keys).await;
+        });
+    }
+}
+
+/// Fetches every figure of `ids` in `vs_currencies` and caches each quote.
+/// Pairs the provider has no quote for are cached as `None` for
+/// `unknown_lifetime`, so that unknown coins are not asked for every time.
+async fn fetch_and_store_prices(
+    provider: &dyn MarketDataProvider,
+    redis_store: &bb8::Pool<bb8_redis::RedisConnectionManager>,
+    ids: &[String],
+    vs_currencies: &[String],
+    lifetime: u64,
+    unknown_lifetime: u64,
+) -> Result<SimplePrices, MarketDataError> {
+    let all_figures = PriceOptions {
+        include_market_cap: true,
+        include_24hr_change: true,
+    };
+    let fetched = provider
+        .simple_prices(ids, vs_currencies, all_figures)
+        .await?;
+
+    let mut entries: Vec<(String, Option<&Quote>)> = Vec::new();
+    let mut unknown: Vec<(String, Option<&Quote>)> = Vec::new();
+    for id in ids {
+        for currency in vs_currencies {
+            match fetched.get(id).and_then(|quotes| quotes.get(currency)) {
+                Some(quote) => entries.push((price_key(id, currency), Some(quote))),
+                None => unknown.push((price_key(id, currency), None)),
+            }
+        }
+    }
+    store_entries(redis_store, &entries, lifetime).await;
+    store_entries(redis_store, &unknown, unknown_lifetime).await;
+
+    Ok(fetched)
+}
+
+/// Reads `keys` at once. Entries that are missing or unreadable, or all of
+/// them if Redis is unavailable, come back as `None`.
+async fn read_entries<T: serde::de::DeserializeOwned>(
+    redis_store: &bb8::Pool<bb8_redis::RedisConnectionManager>,
+    keys: &[String],
+) -> Vec<Option<CachedEntry<T>>> {
+    let values: Vec<Option<String>> = match redis_store.get().await {
+        Ok(mut redis_con) => bb8_redis::redis::cmd("MGET")
+            .arg(keys)
+            .query_async(&mut *redis_con)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!("Failed to read market data cache: {}", e);
+                vec![None; keys.len()]
+            }),
+        Err(e) => {
+            tracing::warn!("Failed to get redis connection: {}", e);
+            vec![None; keys.len()]
+        }
+    };
+
+    values
+        .into_iter()
+        .map(|value| value.and_then(|value| serde_json::from_str(&value).ok()))
+        .collect()
+}
+
+async fn store_entries<T: serde::Serialize>(
+    redis_store: &bb8::Pool<bb8_redis::RedisConnectionManager>,
+    entries: &[(String, T)],
+    lifetime: u64,
+) {
+    if entries.is_empty() {
+        return;
+    }
+
+    let fetched_at = chrono::Utc::now().timestamp();
+    let mut pipe = bb8_redis::redis::pipe();
+    for (key, data) in entries {
+        match serde_json::to_string(&CachedEntry { fetched_at, data }) {
+            Ok(payload) => {
+                pipe.cmd("SET")
+                    .arg(key)
+                    .arg(payload)
+                    .arg("EX")
+                    .arg(lifetime)
+                    .ignore();
+            }
+            Err(e) => tracing::warn!("Failed to serialize {}: {}", key, e),
+        }
+    }
+
+    let result = match redis_store.get().await {
+        Ok(mut redis_con) => pipe.query_async::<_, ()>(&mut *redis_con).await,
+        Err(e) => {
+            tracing::warn!("Failed to get redis connection: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = result {
+        tracing::warn!("Failed to write market data cache: {}", e);
+    }
+}
+
+fn refresh_lock_key(key: &str) -> String {
+    format!("{}:refreshing", key)
+}
+
+/// Whether this process should refresh `key`, i.e. no other refresh of it is
+/// running.
+async fn claim_refresh(
+    redis_store: &bb8::Pool<bb8_redis::RedisConnectionManager>,
+    key: &str,
+) -> bool {
+    let Ok(mut redis_con) = redis_store.get().await else {
+        return false;
+    };
+
+    let claimed: Option<String> = bb8_redis::redis::cmd("SET")
+        .arg(refresh_lock_key(key))
+        .arg(1)
+        .arg("NX")
+        .arg("EX")
+        .arg(REFRESH_LOCK_SECONDS)
+        .query_async(&mut *redis_con)
+        .await
+        .unwrap_or(None);
+
+    claimed.is_some()
+}
+
+async fn release_refresh(
+    redis_store: &bb8::Pool<bb8_redis::RedisConnectionManager>,
+    keys: &[String],
+) {
+    let Ok(mut redis_con) = redis_store.get().await else {
+        return;
+    };
+
+    let lock_keys: Vec<String> = keys.iter().map(|key| refresh_lock_key(key)).collect();
+    if let Err(e) = bb8_redis::redis::cmd("DEL")
+        .arg(lock_keys)
+        .query_async::<_, i64>(&mut *redis_con)
+        .await
+    {
+        tracing::warn!("Failed to release market data refresh: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chart_keys_ignore_currency_case_and_spacing() {
+        assert_eq!(
+            chart_key("bitcoin", "USD", 7),
+            "market_data:chart:bitcoin:usd:7"
+        );
+        assert_eq!(
+            chart_key("bitcoin", " Usd ", 7),
+            chart_key("bitcoin", "usd", 7)
+        );
+    }
+
+    #[test]
+    fn chart_keys_differ_per_coin_currency_and_range() {
+        let key = chart_key("bitcoin", "usd", 7);
+        assert_ne!(key, chart_key("ethereum", "usd", 7));
+        assert_ne!(key, chart_key("bitcoin", "eur", 7));
+        assert_ne!(key, chart_key("bitcoin", "usd", 30));
+    }
+
+    #[test]
+    fn entries_are_fresh_for_their_ttl() {
+        let now = chrono::Utc::now().timestamp();
+        let entry = |age: i64| CachedEntry {
+            fetched_at: now - age,
+            data: (),
+        };
+        assert!(is_fresh(&entry(0), 60));
+        assert!(is_fresh(&entry(59), 60));
+        assert!(!is_fresh(&entry(61), 60));
+    }
+
+    #[test]
+    fn combined_status_is_the_worst() {
+        assert_eq!(CacheStatus::Hit.combine(CacheStatus::Hit), CacheStatus::Hit);
+        assert_eq!(
+            CacheStatus::Hit.combine(CacheStatus::Stale),
+            CacheStatus::Stale
+        );
+        assert_eq!(
+            CacheStatus::Miss.combine(CacheStatus::Stale),
+            CacheStatus::Miss
+        );
+    }
+
+    #[test]
+    fn unknown_pairs_are_told_apart_from_quotes() {
+        let unknown: CachedEntry<Option<Quote>> =
+            serde_json::from_str(r#"{"fetched_at":0,"data":null}"#).unwrap();
+        assert!(unknown.data.is_none());
+
+        let known: CachedEntry<Option<Quote>> =
+            serde_json::from_str(r#"{"fetched_at":0,"data":{"price":1.5}}"#).unwrap();
+        assert_eq!(known.data.map(|quote| quote.price), Some(1.5));
+    }
+}
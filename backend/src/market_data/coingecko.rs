@@ -0,0 +1,130 @@
+use super::{CoinDetail, CoinMarketData, MarketDataError, PriceOptions, SimplePrices};
+
+/// Talks to the CoinGecko API at `coingecko.api_url`.
+pub struct CoinGeckoProvider {
+    client: reqwest::Client,
+    api_url: String,
+    api_key: String,
+}
+
+impl CoinGeckoProvider {
+    pub fn new(settings: &crate::settings::CoinGeckoSettings) -> Self {
+        CoinGeckoProvider {
+            client: reqwest::Client::new(),
+            api_url: settings.api_url.trim_end_matches('/').to_string(),
+            api_key: settings.api_key.clone(),
+        }
+    }
+
+    async fn get<T: serde::de::DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> Result<T, MarketDataError> {
+        let mut request = self
+            .client
+            .get(format!("{}{}", self.api_url, path))
+            .query(query);
+        // Keyless requests fall back to the public rate limit
+        if !self.api_key.is_empty() {
+            request = request.header("x-cg-demo-api-key", &self.api_key);
+        }
+
+        let response = request.send().await?.error_for_status()?;
+        Ok(response.json::<T>().await?)
+    }
+}
+
+#[axum::async_trait]
+impl super::MarketDataProvider for CoinGeckoProvider {
+    #[tracing::instrument(name = "coingecko_list_coins", skip(self))]
+    async fn list_coins(&self, vs_currency: &str) -> Result<Vec<super::Coin>, MarketDataError> {
+        self.get(
+            "/coins/markets",
+            &[("vs_currency", vs_currency.to_lowercase())],
+        )
+        .await
+    }
+
+    #[tracing::instrument(name = "coingecko_supported_vs_currencies", skip(self))]
+    async fn supported_vs_currencies(&self) -> Result<Vec<String>, MarketDataError> {
+        self.get("/simple/supported_vs_currencies", &[]).await
+    }
+
+    #[tracing::instrument(name = "coingecko_simple_prices", skip(self))]
+    async fn simple_prices(
+        &self,
+        ids: &[String],
+        vs_currencies: &[String],
+        options: PriceOptions,
+    ) -> Result<SimplePrices, MarketDataError> {
+        let raw = self
+            .get(
+                "/simple/price",
+                &[
+                    ("ids", ids.join(",")),
+                    ("vs_currencies", vs_currencies.join(",")),
+                    ("include_market_cap", options.include_market_cap.to_string()),
+                    (
+                        "include_24hr_change",
+                        options.include_24hr_change.to_string(),
+                    ),
+                ],
+            )
+            .await?;
+
+        Ok(super::quotes_from_raw(raw, ids, vs_currencies, options))
+    }
+
+    #[tracing::instrument(name = "coingecko_market_chart", skip(self))]
+    async fn market_chart(
+        &self,
+        id: &str,
+        vs_currency: &str,
+        days: i32,
+    ) -> Result<CoinMarketData, MarketDataError> {
+        // Anything else could point the request, and our key, elsewhere
+        if !super::is_valid_coin_id(id) {
+            return Err(MarketDataError::UnknownCoin(id.to_string()));
+        }
+
+        self.get(
+            &format!("/coins/{}/market_chart", id),
+            &[
+                ("vs_currency", vs_currency.to_lowercase()),
+                ("days", days.to_string()),
+            ],
+        )
+        .await
+        .map_err(|e| unknown_coin_on_404(e, id))
+    }
+
+    #[tracing::instrument(name = "coingecko_coin_detail", skip(self))]
+    async fn coin_detail(&self, id: &str) -> Result<CoinDetail, MarketDataError> {
+        if !super::is_valid_coin_id(id) {
+            return Err(MarketDataError::UnknownCoin(id.to_string()));
+        }
+
+        self.get(
+            &format!("/coins/{}", id),
+            &[
+                ("localization", "false".to_string()),
+                ("tickers", "false".to_string()),
+                ("market_data", "false".to_string()),
+                ("community_data", "false".to_string()),
+                ("developer_data", "false".to_string()),
+            ],
+        )
+        .await
+        .map_err(|e| unknown_coin_on_404(e, id))
+    }
+}
+
+fn unknown_coin_on_404(error: MarketDataError, id: &str) -> MarketDataError {
+    match error {
+        MarketDataError::Request(e) if e.status() == Some(reqwest::StatusCode::NOT_FOUND) => {
+            MarketDataError::UnknownCoin(id.to_string())
+        }
+        e => e,
+    }
+}
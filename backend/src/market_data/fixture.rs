@@ -0,0 +1,110 @@
+use super::{Coin, CoinDetail, CoinMarketData, MarketDataError, PriceOptions, SimplePrices};
+
+/// Serves recorded CoinGecko responses from `market_data.fixtures_dir`:
+///
+/// - `coins_markets.json`: the `/coins/markets` listing
+/// - `supported_vs_currencies.json`: `/simple/supported_vs_currencies`
+/// - `simple_price.json`: `/simple/price` for every coin and currency, with
+///   market caps and 24h changes
+/// - `market_chart/<id>.json`: `/coins/<id>/market_chart`, the longest range
+/// - `coins/<id>.json`: `/coins/<id>`
+///
+/// Listings and charts are recorded in a single currency and served as is
+/// whatever currency is asked for.
+pub struct FixtureProvider {
+    dir: std::path::PathBuf,
+}
+
+impl FixtureProvider {
+    pub fn new(dir: &str) -> Self {
+        FixtureProvider {
+            dir: std::path::PathBuf::from(dir),
+        }
+    }
+
+    async fn read<T: serde::de::DeserializeOwned>(
+        &self,
+        relative_path: &str,
+    ) -> Result<T, MarketDataError> {
+        let path = self.dir.join(relative_path);
+        let contents = tokio::fs::read(&path)
+            .await
+            .map_err(|e| MarketDataError::Fixture(format!("{}: {}", path.display(), e)))?;
+
+        serde_json::from_slice(&contents)
+            .map_err(|e| MarketDataError::Fixture(format!("{}: {}", path.display(), e)))
+    }
+
+    /// Reads the fixture of a single coin, which is missing for unknown coins.
+    async fn read_coin<T: serde::de::DeserializeOwned>(
+        &self,
+        folder: &str,
+        id: &str,
+    ) -> Result<T, MarketDataError> {
+        if !super::is_valid_coin_id(id)
+            || !self.dir.join(folder).join(format!("{}.json", id)).is_file()
+        {
+            return Err(MarketDataError::UnknownCoin(id.to_string()));
+        }
+
+        self.read(&format!("{}/{}.json", folder, id)).await
+    }
+}
+
+#[axum::async_trait]
+impl super::MarketDataProvider for FixtureProvider {
+    #[tracing::instrument(name = "fixture_list_coins", skip(self))]
+    async fn list_coins(&self, _vs_currency: &str) -> Result<Vec<Coin>, MarketDataError> {
+        self.read("coins_markets.json").await
+    }
+
+    #[tracing::instrument(name = "fixture_supported_vs_currencies", skip(self))]
+    async fn supported_vs_currencies(&self) -> Result<Vec<String>, MarketDataError> {
+        self.read("supported_vs_currencies.json").await
+    }
+
+    #[tracing::instrument(name = "fixture_simple_prices", skip(self))]
+    async fn simple_prices(
+        &self,
+        ids: &[String],
+        vs_currencies: &[String],
+        options: PriceOptions,
+    ) -> Result<SimplePrices, MarketDataError> {
+        let raw = self.read("simple_price.json").await?;
+
+        Ok(super::quotes_from_raw(raw, ids, vs_currencies, options))
+    }
+
+    #[tracing::instrument(name = "fixture_market_chart", skip(self))]
+    async fn market_chart(
+        &self,
+        id: &str,
+        _vs_currency: &str,
+        days: i32,
+    ) -> Result<CoinMarketData, MarketDataError> {
+        let mut chart: CoinMarketData = self.read_coin("market_chart", id).await?;
+
+        // Keep the last `days` days of the recording
+        let latest = chart
+            .prices
+            .last()
+            .and_then(|point| point.first())
+            .copied()
+            .unwrap_or_default();
+        let since = latest - f64::from(days.max(1)) * 86_400_000.0;
+        for points in [
+            &mut chart.prices,
+            &mut chart.market_caps,
+            &mut chart.total_volumes,
+        ] {
+            points.retain(|point| point.first().is_some_and(|timestamp| *timestamp >= since));
+        }
+
+        Ok(chart)
+    }
+
+    #[tracing::instrument(name = "fixture_coin_detail", skip(self))]
+    async fn coin_detail(&self, id: &str) -> Result<CoinDetail, MarketDataError> {
+        self.read_coin("coins", id).await
+    }
+}
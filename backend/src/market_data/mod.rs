@@ -0,0 +1,247 @@
+//! Where coin listings, prices and charts come from. Everything in the crypto
+//! subsystem goes through a `MarketDataProvider`, picked by
+//! `Settings.market_data.provider`, so it can run against CoinGecko or
+//! against fixture files without network access.
+
+mod cache;
+mod coingecko;
+mod fixture;
+
+pub use cache::{CacheStatus, MarketDataCache, CACHE_STATUS_HEADER};
+pub use coingecko::CoinGeckoProvider;
+pub use fixture::FixtureProvider;
+
+use std::collections::HashMap;
+
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+pub struct Coin {
+    pub id: String,
+    pub symbol: String,
+    pub name: String,
+    pub image: String,
+    pub current_price: f64,
+    pub market_cap: f64,
+    pub market_cap_rank: Option<i32>,
+    pub fully_diluted_valuation: Option<f64>,
+    pub total_volume: f64,
+    pub high_24h: f64,
+    pub low_24h: f64,
+    pub price_change_24h: f64,
+    pub price_change_percentage_24h: f64,
+    pub market_cap_change_24h: f64,
+    pub market_cap_change_percentage_24h: f64,
+    pub circulating_supply: f64,
+    pub total_supply: Option<f64>,
+    pub max_supply: Option<f64>,
+    pub ath: f64,
+    pub ath_change_percentage: f64,
+    pub ath_date: String,
+    pub atl: f64,
+    pub atl_change_percentage: f64,
+    pub atl_date: String,
+    pub roi: Option<Roi>,
+    pub last_updated: String,
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+pub struct Roi {
+    pub times: f64,
+    pub currency: String,
+    pub percentage: f64,
+}
+
+/// Points are `[timestamp in milliseconds, value]` pairs, oldest first.
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+pub struct CoinMarketData {
+    pub prices: Vec<Vec<f64>>,
+    pub market_caps: Vec<Vec<f64>>,
+    pub total_volumes: Vec<Vec<f64>>,
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+pub struct CoinDetail {
+    pub id: String,
+    pub symbol: String,
+    pub name: String,
+    pub market_cap_rank: Option<i32>,
+    pub description: CoinDescription,
+    pub image: CoinImage,
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+pub struct CoinDescription {
+    pub en: String,
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+pub struct CoinImage {
+    pub thumb: String,
+    pub small: String,
+    pub large: String,
+}
+
+/// Extra figures `simple_prices` may return next to the price.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PriceOptions {
+    pub include_market_cap: bool,
+    pub include_24hr_change: bool,
+}
+
+/// A coin's price in one currency. The other figures are only filled in
+/// when asked for through `PriceOptions`.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct Quote {
+    pub price: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub market_cap: Option<f64>,
+    /// Percentage change over the last 24 hours.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub change_24h: Option<f64>,
+}
+
+/// Quotes keyed by coin id, then by lowercase currency code.
+pub type SimplePrices = HashMap<String, HashMap<String, Quote>>;
+
+/// CoinGecko's `/simple/price` body, e.g.
+/// `{"bitcoin": {"usd": 43000, "usd_market_cap": ..., "usd_24h_change": ...}}`.
+type RawSimplePrices = HashMap<String, HashMap<String, Option<f64>>>;
+
+/// Picks the requested coins, currencies and figures out of a raw
+/// `/simple/price` body. Coins and currencies without a price are left out.
+fn quotes_from_raw(
+    mut raw: RawSimplePrices,
+    ids: &[String],
+    vs_currencies: &[String],
+    options: PriceOptions,
+) -> SimplePrices {
+    let mut prices = SimplePrices::new();
+    for id in ids {
+        let Some(figures) = raw.remove(id) else {
+            continue;
+        };
+
+        let figure = |key: String| figures.get(&key).copied().flatten();
+        let quotes: HashMap<String, Quote> = vs_currencies
+            .iter()
+            .filter_map(|currency| {
+                let price = figure(currency.clone())?;
+                Some((
+                    currency.clone(),
+                    Quote {
+                        price,
+                        market_cap: options
+                            .include_market_cap
+                            .then(|| figure(format!("{}_market_cap", currency)))
+                            .flatten(),
+                        change_24h: options
+                            .include_24hr_change
+                            .then(|| figure(format!("{}_24h_change", currency)))
+                            .flatten(),
+                    },
+                ))
+            })
+            .collect();
+
+        if !quotes.is_empty() {
+            prices.insert(id.clone(), quotes);
+        }
+    }
+
+    prices
+}
+
+/// Longest history a chart may cover, which is also all the CoinGecko public
+/// API serves.
+pub const MAX_CHART_DAYS: i32 = 365;
+
+/// Whether `id` looks like a CoinGecko coin id, e.g. `bitcoin` or
+/// `usd-coin`. Ids end up in URLs and paths, so nothing else is let through.
+pub fn is_valid_coin_id(id: &str) -> bool {
+    !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+#[derive(Debug)]
+pub enum MarketDataError {
+    Request(reqwest::Error),
+    /// The provider has no data for this coin.
+    UnknownCoin(String),
+    /// A fixture file is missing or malformed.
+    Fixture(String),
+}
+
+impl std::fmt::Display for MarketDataError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MarketDataError::Request(error) => write!(f, "market data request failed: {}", error),
+            MarketDataError::UnknownCoin(id) => write!(f, "unknown coin: {}", id),
+            MarketDataError::Fixture(message) => write!(f, "market data fixture: {}", message),
+        }
+    }
+}
+
+impl From<reqwest::Error> for MarketDataError {
+    fn from(error: reqwest::Error) -> Self {
+        Self::Request(error)
+    }
+}
+
+impl From<MarketDataError> for crate::utils::CustomAppError {
+    fn from(error: MarketDataError) -> Self {
+        match error {
+            MarketDataError::Request(error) => error.into(),
+            MarketDataError::UnknownCoin(id) => crate::utils::CustomAppError::from((
+                format!("No market data for coin {}", id),
+                crate::utils::ErrorContext::NotFound,
+            )),
+            MarketDataError::Fixture(message) => crate::utils::CustomAppError::from((
+                message,
+                crate::utils::ErrorContext::InternalServerError,
+            )),
+        }
+    }
+}
+
+#[axum::async_trait]
+pub trait MarketDataProvider: Send + Sync {
+    /// Coins by market cap, as used to refresh the tags.
+    async fn list_coins(&self, vs_currency: &str) -> Result<Vec<Coin>, MarketDataError>;
+
+    /// Lowercase codes of the currencies prices can be quoted in.
+    async fn supported_vs_currencies(&self) -> Result<Vec<String>, MarketDataError>;
+
+    /// Current price of every coin in `ids` in every currency of
+    /// `vs_currencies`, which are lowercase. Unknown coins are left out.
+    async fn simple_prices(
+        &self,
+        ids: &[String],
+        vs_currencies: &[String],
+        options: PriceOptions,
+    ) -> Result<SimplePrices, MarketDataError>;
+
+    /// Price, market cap and volume history over the last `days` days.
+    async fn market_chart(
+        &self,
+        id: &str,
+        vs_currency: &str,
+        days: i32,
+    ) -> Result<CoinMarketData, MarketDataError>;
+
+    async fn coin_detail(&self, id: &str) -> Result<CoinDetail, MarketDataError>;
+}
+
+/// Builds the provider selected in the settings.
+pub fn build_provider(
+    settings: &crate::settings::Settings,
+) -> std::sync::Arc<dyn MarketDataProvider> {
+    match settings.market_data.provider {
+        crate::settings::MarketDataBackend::CoinGecko => {
+            std::sync::Arc::new(CoinGeckoProvider::new(&settings.coingecko))
+        }
+        crate::settings::MarketDataBackend::Fixture => {
+            std::sync::Arc::new(FixtureProvider::new(&settings.market_data.fixtures_dir))
+        }
+    }
+}
@@ -0,0 +1,54 @@
+/// A recorded price, or the average of the prices in a downsampled bucket
+/// starting at `captured_at`.
+#[derive(serde::Serialize, Debug, sqlx::FromRow)]
+pub struct PriceSnapshot {
+    pub captured_at: chrono::DateTime<chrono::Utc>,
+    pub price: f64,
+    pub market_cap: Option<f64>,
+    pub total_volume: Option<f64>,
+}
+
+/// Open, high, low and close over a UTC day, or over the `interval_days`
+/// days starting at `day` when downsampled. `volume` is the traded volume
+/// over the period.
+#[derive(serde::Serialize, Debug, sqlx::FromRow)]
+pub struct DailyCandle {
+    pub day: chrono::NaiveDate,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+}
+
+/// `from` and `to` are RFC 3339 timestamps; `from` is inclusive and `to`
+/// exclusive.
+#[derive(serde::Deserialize, Debug)]
+pub struct MarketHistoryQuery {
+    pub currency: Option<String>,
+    pub from: Option<chrono::DateTime<chrono::Utc>>,
+    pub to: Option<chrono::DateTime<chrono::Utc>>,
+    pub max_points: Option<i64>,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct PriceHistory {
+    pub coin: String,
+    pub currency: String,
+    pub from: chrono::DateTime<chrono::Utc>,
+    pub to: chrono::DateTime<chrono::Utc>,
+    /// Width of the buckets points were averaged over, if downsampled.
+    pub interval_seconds: Option<i64>,
+    pub points: Vec<PriceSnapshot>,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct CandleHistory {
+    pub coin: String,
+    pub currency: String,
+    pub from: chrono::NaiveDate,
+    pub to: chrono::NaiveDate,
+    /// Days each candle covers; more than one when downsampled.
+    pub interval_days: i32,
+    pub candles: Vec<DailyCandle>,
+}
@@ -1,9 +1,25 @@
+mod market;
 mod qa;
 mod users;
 
+pub use market::{CandleHistory, DailyCandle, MarketHistoryQuery, PriceHistory, PriceSnapshot};
 pub use qa::{
-    Answer, AnswerAuthor, AnswerAuthorQueryResult, CreateAnswer, CreateQuestion, NewAnswer,
-    NewQuestion, Question, QuestionAuthorWithTags, QuestionAuthorWithTagsQueryResult, Tag,
-    UpdateAnswer, UpdateQuestion,
+    Answer, AnswerAuthor, AnswerAuthorQueryResult, AnswerRevision, AnswerWithQuestion,
+    AnswerWithQuestionQueryResult, CommentAuthor, CommentAuthorQueryResult, CreateAnswer,
+    CreateComment, CreateQuestion, DiffChange, ModerationAction, ModerationActionKind,
+    ModerationLogQuery, ModerationReason, NewAnswer, NewComment, NewQuestion, NewVote, Question,
+    QuestionAuthorWithTags, QuestionAuthorWithTagsQueryResult, QuestionListQuery, QuestionRevision,
+    QuestionSort, RevisionDiff, RevisionDiffQuery, SearchHit, SearchMatchQueryResult, SearchQuery,
+    SearchRequest, Tag, UpdateAnswer, UpdateComment, UpdateQuestion, VoteSummary,
+};
+pub use users::{
+    AccountExport, ActivateUser, AdminUser, AdminUserDetails, AdminUserListQuery, ApiToken,
+    ApiTokenOwner, ApiTokenScope, AuditEvent, AuditLogEntry, AuditLogQuery, AuditTarget,
+    ChangePassword, ConfirmEmailChange, ConfirmPasswordReset, CreatedApiToken, CsrfToken,
+    DeleteAccount, ExportedAnswer, ExportedComment, ExportedQuestion, ExportedVote, LoggedInUser,
+    LoginUser, NewApiToken, NewAuditEntry, NewUser, PendingEmailChange, PublicProfile, PublicUser,
+    RecoveryCodes, RequestEmailChange, RequestPasswordReset, ResendActivation, SessionInfo, TopTag,
+    TwoFactorChallenge, TwoFactorCode, TwoFactorEnrollment, TwoFactorLogin,
+    TwoFactorReauthentication, TwoFactorStatus, UpdateProfile, User, UserActivity, UserPostsQuery,
+    UserSuspension, UserVisible, DELETED_USER_ID,
 };
-pub use users::{ActivateUser, LoggedInUser, LoginUser, NewUser, User, UserVisible};
@@ -36,6 +36,7 @@ pub struct UpdateQuestion {
     pub title: String,
     pub tags: String,
     pub content: String,
+    pub edit_summary: Option<String>,
 }
 
 #[derive(serde::Serialize, Debug)]
@@ -51,6 +52,7 @@ pub struct Answer {
 #[derive(serde::Deserialize, Debug)]
 pub struct NewAnswer {
     pub content: String,
+    pub edit_summary: Option<String>,
 }
 
 #[derive(serde::Serialize, Debug)]
@@ -67,6 +69,51 @@ pub struct UpdateAnswer {
     pub raw_content: String,
     pub author: Uuid,
     pub answer_id: Uuid,
+    pub edit_summary: Option<String>,
+}
+
+#[derive(serde::Serialize, Debug, FromRow)]
+pub struct QuestionRevision {
+    pub id: Uuid,
+    pub question: Uuid,
+    pub revision: i32,
+    pub editor: Option<Uuid>,
+    pub title: String,
+    pub tags: Vec<String>,
+    pub raw_content: String,
+    pub edit_summary: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(serde::Serialize, Debug, FromRow)]
+pub struct AnswerRevision {
+    pub id: Uuid,
+    pub answer: Uuid,
+    pub revision: i32,
+    pub editor: Option<Uuid>,
+    pub raw_content: String,
+    pub edit_summary: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct RevisionDiffQuery {
+    pub from: i32,
+    pub to: i32,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct DiffChange {
+    pub tag: String,
+    pub value: String,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct RevisionDiff {
+    pub from: i32,
+    pub to: i32,
+    pub unified_diff: String,
+    pub changes: Vec<DiffChange>,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, FromRow)]
@@ -78,6 +125,63 @@ pub struct Tag {
     pub market_cap_rank: Option<i32>,
 }
 
+/// Orderings supported by the questions listing.
+#[derive(serde::Deserialize, Debug, Default, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum QuestionSort {
+    #[default]
+    Newest,
+    RecentlyUpdated,
+    MostAnswered,
+    HighestScore,
+}
+
+#[derive(serde::Deserialize, Debug, Default)]
+pub struct QuestionListQuery {
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+    #[serde(default)]
+    pub sort: QuestionSort,
+    pub tag: Option<String>,
+    pub author: Option<Uuid>,
+    pub unanswered: Option<bool>,
+    pub resolved: Option<bool>,
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct SearchRequest {
+    pub q: String,
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+}
+
+/// A search request broken into its full-text part and its filters, e.g.
+/// `[bitcoin] author:<uuid> "cold storage"`.
+#[derive(Debug, Default)]
+pub struct SearchQuery {
+    pub text: String,
+    pub tags: Vec<String>,
+    pub author: Option<Uuid>,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct SearchHit {
+    pub question: QuestionAuthorWithTags,
+    pub rank: f32,
+    pub title_highlight: String,
+    pub snippet: String,
+    pub answer_snippet: Option<String>,
+}
+
+#[derive(FromRow, Debug)]
+pub struct SearchMatchQueryResult {
+    pub id: Uuid,
+    pub rank: f32,
+    pub title_highlight: String,
+    pub snippet: String,
+    pub answer_snippet: Option<String>,
+}
+
 #[derive(serde::Serialize, Debug)]
 pub struct QuestionAuthorWithTags {
     pub id: Uuid,
@@ -85,10 +189,18 @@ pub struct QuestionAuthorWithTags {
     pub slug: String,
     pub content: String,
     pub raw_content: String,
-    pub author: crate::models::UserVisible,
+    pub author: crate::models::PublicUser,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
     pub tags: Vec<Tag>,
+    pub score: i32,
+    pub accepted_answer: Option<Uuid>,
+    pub is_resolved: bool,
+    pub closed_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub close_reason: Option<String>,
+    pub locked_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub answers_count: i64,
+    pub comments_count: i64,
 }
 
 #[derive(serde::Serialize, Debug)]
@@ -96,9 +208,132 @@ pub struct AnswerAuthor {
     pub id: Uuid,
     pub content: String,
     pub raw_content: String,
-    pub author: crate::models::UserVisible,
+    pub author: crate::models::PublicUser,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
+    pub score: i32,
+    pub is_accepted: bool,
+    pub locked_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub comments_count: i64,
+}
+
+/// An answer as listed on its author's profile, next to what it answers.
+#[derive(serde::Serialize, Debug)]
+pub struct AnswerWithQuestion {
+    #[serde(flatten)]
+    pub answer: AnswerAuthor,
+    pub question: Uuid,
+    pub question_title: String,
+    pub question_slug: String,
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct NewComment {
+    pub content: String,
+    pub parent: Option<Uuid>,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct CreateComment {
+    pub content: String,
+    pub raw_content: String,
+    pub author: Uuid,
+    pub question: Option<Uuid>,
+    pub answer: Option<Uuid>,
+    pub parent: Option<Uuid>,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct UpdateComment {
+    pub content: String,
+    pub raw_content: String,
+    pub author: Uuid,
+    pub comment_id: Uuid,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct CommentAuthor {
+    pub id: Uuid,
+    pub content: String,
+    pub raw_content: String,
+    pub author: crate::models::PublicUser,
+    pub question: Option<Uuid>,
+    pub answer: Option<Uuid>,
+    pub parent: Option<Uuid>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct NewVote {
+    pub value: i16,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct VoteSummary {
+    pub id: Uuid,
+    pub score: i32,
+    pub vote: Option<i16>,
+}
+
+/// Things moderators do to posts that are not theirs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModerationActionKind {
+    EditQuestion,
+    DeleteQuestion,
+    CloseQuestion,
+    ReopenQuestion,
+    LockQuestion,
+    UnlockQuestion,
+    EditAnswer,
+    DeleteAnswer,
+    LockAnswer,
+    UnlockAnswer,
+    EditComment,
+    DeleteComment,
+}
+
+impl ModerationActionKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ModerationActionKind::EditQuestion => "edit_question",
+            ModerationActionKind::DeleteQuestion => "delete_question",
+            ModerationActionKind::CloseQuestion => "close_question",
+            ModerationActionKind::ReopenQuestion => "reopen_question",
+            ModerationActionKind::LockQuestion => "lock_question",
+            ModerationActionKind::UnlockQuestion => "unlock_question",
+            ModerationActionKind::EditAnswer => "edit_answer",
+            ModerationActionKind::DeleteAnswer => "delete_answer",
+            ModerationActionKind::LockAnswer => "lock_answer",
+            ModerationActionKind::UnlockAnswer => "unlock_answer",
+            ModerationActionKind::EditComment => "edit_comment",
+            ModerationActionKind::DeleteComment => "delete_comment",
+        }
+    }
+}
+
+#[derive(serde::Serialize, Debug, FromRow)]
+pub struct ModerationAction {
+    pub id: Uuid,
+    pub moderator: Option<Uuid>,
+    pub action: String,
+    pub question: Option<Uuid>,
+    pub answer: Option<Uuid>,
+    pub reason: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(serde::Deserialize, Debug, Default)]
+pub struct ModerationReason {
+    pub reason: Option<String>,
+}
+
+#[derive(serde::Deserialize, Debug, Default)]
+pub struct ModerationLogQuery {
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+    pub moderator: Option<Uuid>,
+    pub question: Option<Uuid>,
 }
 
 #[derive(FromRow, Debug)]
@@ -111,16 +346,19 @@ pub struct QuestionAuthorWithTagsQueryResult {
     pub raw_content: String,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
+    pub score: i32,
+    pub accepted_answer: Option<Uuid>,
+    pub closed_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub close_reason: Option<String>,
+    pub locked_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub answers_count: i64,
+    pub comments_count: i64,
     // JSON aggregation of tags
     pub tags_json: JsonValue,
     // Fields from `users`
     pub user_id: Uuid,
-    pub user_email: String,
     pub user_first_name: String,
     pub user_last_name: String,
-    pub user_is_active: Option<bool>,
-    pub user_is_staff: Option<bool>,
-    pub user_is_superuser: Option<bool>,
     pub user_thumbnail: Option<String>,
     pub user_date_joined: chrono::DateTime<chrono::Utc>,
 }
@@ -133,18 +371,50 @@ pub struct AnswerAuthorQueryResult {
     pub raw_content: String,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
+    pub score: i32,
+    pub is_accepted: bool,
+    pub locked_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub comments_count: i64,
     // Fields from `users`
     pub user_id: Uuid,
-    pub user_email: String,
     pub user_first_name: String,
     pub user_last_name: String,
-    pub user_is_active: Option<bool>,
-    pub user_is_staff: Option<bool>,
-    pub user_is_superuser: Option<bool>,
     pub user_thumbnail: Option<String>,
     pub user_date_joined: chrono::DateTime<chrono::Utc>,
 }
 
+impl TryFrom<QuestionAuthorWithTagsQueryResult> for QuestionAuthorWithTags {
+    type Error = serde_json::Error;
+
+    fn try_from(query_result: QuestionAuthorWithTagsQueryResult) -> Result<Self, Self::Error> {
+        Ok(QuestionAuthorWithTags {
+            id: query_result.id,
+            title: query_result.title,
+            slug: query_result.slug,
+            content: query_result.content,
+            raw_content: query_result.raw_content,
+            author: crate::models::PublicUser {
+                id: query_result.user_id,
+                first_name: query_result.user_first_name,
+                last_name: query_result.user_last_name,
+                thumbnail: query_result.user_thumbnail,
+                date_joined: query_result.user_date_joined,
+            },
+            created_at: query_result.created_at,
+            updated_at: query_result.updated_at,
+            tags: serde_json::from_value(query_result.tags_json)?,
+            score: query_result.score,
+            is_resolved: query_result.accepted_answer.is_some(),
+            accepted_answer: query_result.accepted_answer,
+            closed_at: query_result.closed_at,
+            close_reason: query_result.close_reason,
+            locked_at: query_result.locked_at,
+            answers_count: query_result.answers_count,
+            comments_count: query_result.comments_count,
+        })
+    }
+}
+
 impl From<AnswerAuthorQueryResult> for AnswerAuthor {
     fn from(query_result: AnswerAuthorQueryResult) -> Self {
         AnswerAuthor {
@@ -153,14 +423,75 @@ impl From<AnswerAuthorQueryResult> for AnswerAuthor {
             raw_content: query_result.raw_content,
             created_at: query_result.created_at,
             updated_at: query_result.updated_at,
-            author: crate::models::UserVisible {
+            score: query_result.score,
+            is_accepted: query_result.is_accepted,
+            locked_at: query_result.locked_at,
+            comments_count: query_result.comments_count,
+            author: crate::models::PublicUser {
+                id: query_result.user_id,
+                first_name: query_result.user_first_name,
+                last_name: query_result.user_last_name,
+                thumbnail: query_result.user_thumbnail,
+                date_joined: query_result.user_date_joined,
+            },
+        }
+    }
+}
+
+#[derive(FromRow, Debug)]
+pub struct AnswerWithQuestionQueryResult {
+    #[sqlx(flatten)]
+    pub answer: AnswerAuthorQueryResult,
+    pub question: Uuid,
+    pub question_title: String,
+    pub question_slug: String,
+}
+
+impl From<AnswerWithQuestionQueryResult> for AnswerWithQuestion {
+    fn from(query_result: AnswerWithQuestionQueryResult) -> Self {
+        AnswerWithQuestion {
+            answer: query_result.answer.into(),
+            question: query_result.question,
+            question_title: query_result.question_title,
+            question_slug: query_result.question_slug,
+        }
+    }
+}
+
+#[derive(FromRow, Debug)]
+pub struct CommentAuthorQueryResult {
+    // Fields from `comments`
+    pub id: Uuid,
+    pub content: String,
+    pub raw_content: String,
+    pub question: Option<Uuid>,
+    pub answer: Option<Uuid>,
+    pub parent: Option<Uuid>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+    // Fields from `users`
+    pub user_id: Uuid,
+    pub user_first_name: String,
+    pub user_last_name: String,
+    pub user_thumbnail: Option<String>,
+    pub user_date_joined: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<CommentAuthorQueryResult> for CommentAuthor {
+    fn from(query_result: CommentAuthorQueryResult) -> Self {
+        CommentAuthor {
+            id: query_result.id,
+            content: query_result.content,
+            raw_content: query_result.raw_content,
+            question: query_result.question,
+            answer: query_result.answer,
+            parent: query_result.parent,
+            created_at: query_result.created_at,
+            updated_at: query_result.updated_at,
+            author: crate::models::PublicUser {
                 id: query_result.user_id,
-                email: query_result.user_email,
                 first_name: query_result.user_first_name,
                 last_name: query_result.user_last_name,
-                is_active: query_result.user_is_active,
-                is_staff: query_result.user_is_staff,
-                is_superuser: query_result.user_is_superuser,
                 thumbnail: query_result.user_thumbnail,
                 date_joined: query_result.user_date_joined,
             },
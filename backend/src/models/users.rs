@@ -41,6 +41,53 @@ impl From<User> for UserVisible {
     }
 }
 
+/// What anybody may see about a user: no email address and no staff flags.
+#[derive(serde::Serialize, Debug, sqlx::FromRow, Clone)]
+pub struct PublicUser {
+    pub id: uuid::Uuid,
+    pub first_name: String,
+    pub last_name: String,
+    pub thumbnail: Option<String>,
+    pub date_joined: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<User> for PublicUser {
+    fn from(user: User) -> Self {
+        PublicUser {
+            id: user.id,
+            first_name: user.first_name,
+            last_name: user.last_name,
+            thumbnail: user.thumbnail,
+            date_joined: user.date_joined,
+        }
+    }
+}
+
+/// A tag the user writes about, with the number of their posts under it.
+#[derive(serde::Serialize, Debug, sqlx::FromRow)]
+pub struct TopTag {
+    pub id: String,
+    pub name: String,
+    pub image: Option<String>,
+    pub posts: i64,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct PublicProfile {
+    #[serde(flatten)]
+    pub user: PublicUser,
+    pub questions: i64,
+    pub answers: i64,
+    pub accepted_answers: i64,
+    pub top_tags: Vec<TopTag>,
+}
+
+#[derive(serde::Deserialize, Debug, Default)]
+pub struct UserPostsQuery {
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+}
+
 #[derive(serde::Serialize)]
 pub struct LoggedInUser {
     pub id: uuid::Uuid,
@@ -68,3 +115,407 @@ pub struct ActivateUser {
     pub id: uuid::Uuid,
     pub token: String,
 }
+
+#[derive(serde::Deserialize, Debug)]
+pub struct RequestPasswordReset {
+    pub email: String,
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct ConfirmPasswordReset {
+    pub id: uuid::Uuid,
+    pub token: String,
+    pub password: String,
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct UpdateProfile {
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    /// An empty string removes the thumbnail.
+    pub thumbnail: Option<String>,
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct ChangePassword {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct RequestEmailChange {
+    pub email: String,
+    pub password: String,
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct ConfirmEmailChange {
+    pub token: String,
+}
+
+/// A pending email change as kept in Redis until it is confirmed.
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct PendingEmailChange {
+    pub email: String,
+    pub hashed_token: String,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct SessionInfo {
+    /// Public handle of the session; the session id itself never leaves the cookie.
+    pub id: String,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_seen: Option<chrono::DateTime<chrono::Utc>>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub is_current: bool,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct TwoFactorStatus {
+    pub enabled: bool,
+    pub recovery_codes_remaining: i64,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct TwoFactorEnrollment {
+    /// Base32 secret for authenticator apps that cannot scan the URI.
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct RecoveryCodes {
+    pub recovery_codes: Vec<String>,
+}
+
+/// A TOTP code or, where accepted, a recovery code.
+#[derive(serde::Deserialize, Debug)]
+pub struct TwoFactorCode {
+    pub code: String,
+}
+
+/// The current password and a second factor, asked for before 2FA is turned
+/// off or the recovery codes are replaced.
+#[derive(serde::Deserialize, Debug)]
+pub struct TwoFactorReauthentication {
+    pub password: String,
+    pub code: String,
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct TwoFactorLogin {
+    pub token: String,
+    pub code: String,
+}
+
+/// Returned by `login_user` instead of a session when the account has 2FA on.
+#[derive(serde::Serialize, Debug)]
+pub struct TwoFactorChallenge {
+    pub message: String,
+    pub status_code: u16,
+    pub two_factor_required: bool,
+    pub two_factor_token: String,
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct ResendActivation {
+    pub email: String,
+}
+
+/// A time-boxed ban. Also the body of a suspension request.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct UserSuspension {
+    pub until: chrono::DateTime<chrono::Utc>,
+    pub reason: String,
+}
+
+/// A user as administrators see it.
+#[derive(serde::Serialize, Debug, sqlx::FromRow)]
+pub struct AdminUser {
+    pub id: uuid::Uuid,
+    pub email: String,
+    pub first_name: String,
+    pub last_name: String,
+    pub is_active: Option<bool>,
+    pub is_staff: Option<bool>,
+    pub is_superuser: Option<bool>,
+    pub thumbnail: Option<String>,
+    pub date_joined: chrono::DateTime<chrono::Utc>,
+    pub two_factor_enabled: bool,
+    pub suspended_until: Option<chrono::DateTime<chrono::Utc>>,
+    pub suspension_reason: Option<String>,
+}
+
+#[derive(serde::Serialize, Debug, sqlx::FromRow)]
+pub struct UserActivity {
+    pub questions: i64,
+    pub answers: i64,
+    pub accepted_answers: i64,
+    pub comments: i64,
+    pub votes: i64,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct AdminUserDetails {
+    #[serde(flatten)]
+    pub user: AdminUser,
+    pub activity: UserActivity,
+    pub active_sessions: usize,
+}
+
+#[derive(serde::Deserialize, Debug, Default)]
+pub struct AdminUserListQuery {
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+    /// Matched against email, first and last name.
+    pub q: Option<String>,
+    pub is_active: Option<bool>,
+    pub is_staff: Option<bool>,
+    pub suspended: Option<bool>,
+}
+
+/// Kinds of events kept in the audit log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditEvent {
+    Login,
+    LoginFailed,
+    Logout,
+    PasswordChanged,
+    PasswordReset,
+    EmailChanged,
+    AccountActivated,
+    AccountDeactivated,
+    AccountDeleted,
+    SessionsRevoked,
+    RoleChanged,
+    UserSuspended,
+    UserUnsuspended,
+    ModeratorEdit,
+    ModeratorDelete,
+    ModeratorAction,
+}
+
+impl AuditEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditEvent::Login => "login",
+            AuditEvent::LoginFailed => "login_failed",
+            AuditEvent::Logout => "logout",
+            AuditEvent::PasswordChanged => "password_changed",
+            AuditEvent::PasswordReset => "password_reset",
+            AuditEvent::EmailChanged => "email_changed",
+            AuditEvent::AccountActivated => "account_activated",
+            AuditEvent::AccountDeactivated => "account_deactivated",
+            AuditEvent::AccountDeleted => "account_deleted",
+            AuditEvent::SessionsRevoked => "sessions_revoked",
+            AuditEvent::RoleChanged => "role_changed",
+            AuditEvent::UserSuspended => "user_suspended",
+            AuditEvent::UserUnsuspended => "user_unsuspended",
+            AuditEvent::ModeratorEdit => "moderator_edit",
+            AuditEvent::ModeratorDelete => "moderator_delete",
+            AuditEvent::ModeratorAction => "moderator_action",
+        }
+    }
+}
+
+impl From<crate::models::ModerationActionKind> for AuditEvent {
+    fn from(kind: crate::models::ModerationActionKind) -> Self {
+        use crate::models::ModerationActionKind::*;
+        match kind {
+            EditQuestion | EditAnswer | EditComment => AuditEvent::ModeratorEdit,
+            DeleteQuestion | DeleteAnswer | DeleteComment => AuditEvent::ModeratorDelete,
+            CloseQuestion | ReopenQuestion | LockQuestion | UnlockQuestion | LockAnswer
+            | UnlockAnswer => AuditEvent::ModeratorAction,
+        }
+    }
+}
+
+/// What an audited event was done to.
+#[derive(Debug, Clone, Copy)]
+pub enum AuditTarget {
+    User(uuid::Uuid),
+    Question(uuid::Uuid),
+    Answer(uuid::Uuid),
+    Comment(uuid::Uuid),
+}
+
+impl AuditTarget {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AuditTarget::User(_) => "user",
+            AuditTarget::Question(_) => "question",
+            AuditTarget::Answer(_) => "answer",
+            AuditTarget::Comment(_) => "comment",
+        }
+    }
+
+    pub fn id(&self) -> uuid::Uuid {
+        match self {
+            AuditTarget::User(id)
+            | AuditTarget::Question(id)
+            | AuditTarget::Answer(id)
+            | AuditTarget::Comment(id) => *id,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct NewAuditEntry {
+    pub event: AuditEvent,
+    pub actor: Option<uuid::Uuid>,
+    pub target: Option<AuditTarget>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub payload: serde_json::Value,
+}
+
+#[derive(serde::Serialize, Debug, sqlx::FromRow)]
+pub struct AuditLogEntry {
+    pub id: uuid::Uuid,
+    pub event: String,
+    pub actor: Option<uuid::Uuid>,
+    pub target_type: Option<String>,
+    pub target_id: Option<uuid::Uuid>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub payload: serde_json::Value,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(serde::Deserialize, Debug, Default)]
+pub struct AuditLogQuery {
+    pub page: Option<i64>,
+    pub page_size: Option<i64>,
+    pub event: Option<String>,
+    pub actor: Option<uuid::Uuid>,
+    /// Matches the target of the event, whatever its type.
+    pub target: Option<uuid::Uuid>,
+    pub ip_address: Option<String>,
+    pub from: Option<chrono::DateTime<chrono::Utc>>,
+    pub to: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// What a personal API token may do. Read tokens are limited to safe
+/// methods such as `GET`.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiTokenScope {
+    Read,
+    Write,
+}
+
+impl ApiTokenScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiTokenScope::Read => "read",
+            ApiTokenScope::Write => "write",
+        }
+    }
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct NewApiToken {
+    pub name: String,
+    pub scope: ApiTokenScope,
+    pub expires_in_days: Option<i64>,
+}
+
+#[derive(serde::Serialize, Debug, sqlx::FromRow)]
+pub struct ApiToken {
+    pub id: uuid::Uuid,
+    pub name: String,
+    pub token_prefix: String,
+    pub scope: String,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    pub last_used_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Returned once, when the token is created. The token itself cannot be
+/// retrieved afterwards.
+#[derive(serde::Serialize, Debug)]
+pub struct CreatedApiToken {
+    #[serde(flatten)]
+    pub api_token: ApiToken,
+    pub token: String,
+}
+
+/// The owner of a valid token, found while authenticating a request.
+#[derive(Debug, sqlx::FromRow)]
+pub struct ApiTokenOwner {
+    pub id: uuid::Uuid,
+    pub user_id: uuid::Uuid,
+    pub scope: String,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct CsrfToken {
+    pub csrf_token: String,
+}
+
+/// Takes over the posts of accounts deleted with anonymization.
+pub const DELETED_USER_ID: uuid::Uuid = uuid::Uuid::nil();
+
+#[derive(serde::Deserialize, Debug)]
+pub struct DeleteAccount {
+    pub password: String,
+    /// A TOTP or recovery code, required when 2FA is on.
+    pub code: Option<String>,
+}
+
+#[derive(serde::Serialize, Debug, sqlx::FromRow)]
+pub struct ExportedQuestion {
+    pub id: uuid::Uuid,
+    pub title: String,
+    pub slug: String,
+    pub raw_content: String,
+    pub tags: Vec<String>,
+    pub score: i32,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(serde::Serialize, Debug, sqlx::FromRow)]
+pub struct ExportedAnswer {
+    pub id: uuid::Uuid,
+    pub question: uuid::Uuid,
+    pub raw_content: String,
+    pub score: i32,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(serde::Serialize, Debug, sqlx::FromRow)]
+pub struct ExportedComment {
+    pub id: uuid::Uuid,
+    pub question: Option<uuid::Uuid>,
+    pub answer: Option<uuid::Uuid>,
+    pub parent: Option<uuid::Uuid>,
+    pub raw_content: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(serde::Serialize, Debug, sqlx::FromRow)]
+pub struct ExportedVote {
+    /// Either `question` or `answer`.
+    pub target_type: String,
+    pub target_id: uuid::Uuid,
+    pub value: i16,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Everything stored about a user, as handed to them on request.
+#[derive(serde::Serialize, Debug)]
+pub struct AccountExport {
+    pub exported_at: chrono::DateTime<chrono::Utc>,
+    pub profile: UserVisible,
+    pub two_factor_enabled: bool,
+    pub questions: Vec<ExportedQuestion>,
+    pub answers: Vec<ExportedAnswer>,
+    pub comments: Vec<ExportedComment>,
+    pub votes: Vec<ExportedVote>,
+    pub sessions: Vec<SessionInfo>,
+    pub api_tokens: Vec<ApiToken>,
+}
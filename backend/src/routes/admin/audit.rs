@@ -0,0 +1,28 @@
+use crate::{
+    models::AuditLogQuery,
+    startup::AppState,
+    utils::{AuthenticatedUser, CustomAppError, Paginated, Pagination, Role},
+};
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+};
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "audit_log", skip(state, admin))]
+pub async fn audit_log(
+    Query(log_query): Query<AuditLogQuery>,
+    State(state): State<AppState>,
+    admin: AuthenticatedUser,
+) -> Result<impl IntoResponse, CustomAppError> {
+    admin.require_role(Role::Admin)?;
+
+    let pagination = Pagination::new(log_query.page, log_query.page_size);
+
+    let (entries, total) = state
+        .db_store
+        .get_audit_log_from_db(&log_query, pagination)
+        .await?;
+
+    Ok(axum::Json(Paginated::new(entries, total, pagination)).into_response())
+}
@@ -0,0 +1,31 @@
+use crate::utils::validate_authentication_session;
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+mod audit;
+mod users;
+
+pub(crate) use users::{audit_admin_action, manageable_user};
+
+pub fn admin_routes(state: crate::startup::AppState) -> Router<crate::startup::AppState> {
+    Router::new()
+        .route("/users", get(users::list_users))
+        .route("/users/:user_id", get(users::get_user_details))
+        .route("/users/:user_id/activate", post(users::activate_user))
+        .route("/users/:user_id/deactivate", post(users::deactivate_user))
+        .route(
+            "/users/:user_id/staff",
+            post(users::grant_staff).delete(users::revoke_staff),
+        )
+        .route(
+            "/users/:user_id/suspension",
+            post(users::suspend_user).delete(users::unsuspend_user),
+        )
+        .route("/audit-log", get(audit::audit_log))
+        .route_layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            validate_authentication_session,
+        ))
+}
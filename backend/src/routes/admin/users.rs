@@ -0,0 +1,285 @@
+use crate::{
+    models::{
+        AdminUser, AdminUserDetails, AdminUserListQuery, AuditEvent, AuditTarget, UserSuspension,
+    },
+    startup::AppState,
+    utils::{
+        AuthenticatedUser, ClientInfo, CustomAppError, CustomAppJson, ErrorContext, Paginated,
+        Pagination, Role,
+    },
+};
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+};
+
+/// Administrators manage other people's accounts, and superusers are managed
+/// through the settings only.
+fn ensure_manageable(admin: &AuthenticatedUser, user: &AdminUser) -> Result<(), CustomAppError> {
+    if admin.id() == user.id {
+        return Err(CustomAppError::from((
+            "You cannot change your own account from here".to_string(),
+            ErrorContext::BadRequest,
+        )));
+    }
+
+    if user.id == crate::models::DELETED_USER_ID {
+        return Err(CustomAppError::from((
+            "The placeholder for deleted accounts cannot be changed".to_string(),
+            ErrorContext::Forbidden,
+        )));
+    }
+
+    if user.is_superuser.unwrap_or(false) {
+        return Err(CustomAppError::from((
+            "Superuser accounts cannot be changed from here".to_string(),
+            ErrorContext::Forbidden,
+        )));
+    }
+
+    Ok(())
+}
+
+/// Loads the user an administrator is about to change.
+pub(crate) async fn manageable_user(
+    state: &AppState,
+    admin: &AuthenticatedUser,
+    user_id: uuid::Uuid,
+) -> Result<AdminUser, CustomAppError> {
+    admin.require_role(Role::Admin)?;
+    let user = state.db_store.get_admin_user_from_db(user_id).await?;
+    ensure_manageable(admin, &user)?;
+    Ok(user)
+}
+
+/// Records what an administrator did to `user_id`.
+pub(crate) async fn audit_admin_action(
+    state: &AppState,
+    admin: &AuthenticatedUser,
+    event: AuditEvent,
+    user_id: uuid::Uuid,
+    client: &ClientInfo,
+    payload: serde_json::Value,
+) {
+    crate::utils::record_audit_event(
+        &state.db_store,
+        event,
+        Some(admin.id()),
+        Some(AuditTarget::User(user_id)),
+        client,
+        payload,
+    )
+    .await;
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "list_users", skip(state, admin))]
+pub async fn list_users(
+    Query(list_query): Query<AdminUserListQuery>,
+    State(state): State<AppState>,
+    admin: AuthenticatedUser,
+) -> Result<impl IntoResponse, CustomAppError> {
+    admin.require_role(Role::Admin)?;
+
+    let pagination = Pagination::new(list_query.page, list_query.page_size);
+
+    let (users, total) = state
+        .db_store
+        .get_users_from_db(&list_query, pagination)
+        .await?;
+
+    Ok(axum::Json(Paginated::new(users, total, pagination)).into_response())
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "get_user_details", skip(state, admin))]
+pub async fn get_user_details(
+    Path(user_id): Path<uuid::Uuid>,
+    State(state): State<AppState>,
+    admin: AuthenticatedUser,
+) -> Result<impl IntoResponse, CustomAppError> {
+    admin.require_role(Role::Admin)?;
+
+    let user = state.db_store.get_admin_user_from_db(user_id).await?;
+    let activity = state.db_store.get_user_activity_from_db(user_id).await?;
+    let active_sessions = crate::utils::list_user_sessions(&state.redis_store, &user_id, None)
+        .await?
+        .len();
+
+    Ok(CustomAppJson(AdminUserDetails {
+        user,
+        activity,
+        active_sessions,
+    })
+    .into_response())
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "admin_activate_user", skip(state, admin, client))]
+pub async fn activate_user(
+    Path(user_id): Path<uuid::Uuid>,
+    State(state): State<AppState>,
+    admin: AuthenticatedUser,
+    client: ClientInfo,
+) -> Result<impl IntoResponse, CustomAppError> {
+    manageable_user(&state, &admin, user_id).await?;
+
+    let user = state.db_store.set_user_active_in_db(user_id, true).await?;
+    audit_admin_action(
+        &state,
+        &admin,
+        AuditEvent::AccountActivated,
+        user_id,
+        &client,
+        serde_json::json!({}),
+    )
+    .await;
+
+    Ok(CustomAppJson(user).into_response())
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "admin_deactivate_user", skip(state, admin, client))]
+pub async fn deactivate_user(
+    Path(user_id): Path<uuid::Uuid>,
+    State(state): State<AppState>,
+    admin: AuthenticatedUser,
+    client: ClientInfo,
+) -> Result<impl IntoResponse, CustomAppError> {
+    manageable_user(&state, &admin, user_id).await?;
+
+    let user = state.db_store.set_user_active_in_db(user_id, false).await?;
+    crate::utils::revoke_all_user_sessions(&state.redis_store, &user_id, None).await?;
+    audit_admin_action(
+        &state,
+        &admin,
+        AuditEvent::AccountDeactivated,
+        user_id,
+        &client,
+        serde_json::json!({}),
+    )
+    .await;
+
+    Ok(CustomAppJson(user).into_response())
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "grant_staff", skip(state, admin, client))]
+pub async fn grant_staff(
+    Path(user_id): Path<uuid::Uuid>,
+    State(state): State<AppState>,
+    admin: AuthenticatedUser,
+    client: ClientInfo,
+) -> Result<impl IntoResponse, CustomAppError> {
+    manageable_user(&state, &admin, user_id).await?;
+
+    let user = state.db_store.set_user_staff_in_db(user_id, true).await?;
+    audit_admin_action(
+        &state,
+        &admin,
+        AuditEvent::RoleChanged,
+        user_id,
+        &client,
+        serde_json::json!({ "role": Role::Moderator, "granted": true }),
+    )
+    .await;
+
+    Ok(CustomAppJson(user).into_response())
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "revoke_staff", skip(state, admin, client))]
+pub async fn revoke_staff(
+    Path(user_id): Path<uuid::Uuid>,
+    State(state): State<AppState>,
+    admin: AuthenticatedUser,
+    client: ClientInfo,
+) -> Result<impl IntoResponse, CustomAppError> {
+    manageable_user(&state, &admin, user_id).await?;
+
+    let user = state.db_store.set_user_staff_in_db(user_id, false).await?;
+    audit_admin_action(
+        &state,
+        &admin,
+        AuditEvent::RoleChanged,
+        user_id,
+        &client,
+        serde_json::json!({ "role": Role::Moderator, "granted": false }),
+    )
+    .await;
+
+    Ok(CustomAppJson(user).into_response())
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "suspend_user", skip(state, admin, client))]
+pub async fn suspend_user(
+    Path(user_id): Path<uuid::Uuid>,
+    State(state): State<AppState>,
+    admin: AuthenticatedUser,
+    client: ClientInfo,
+    CustomAppJson(suspension): CustomAppJson<UserSuspension>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    manageable_user(&state, &admin, user_id).await?;
+
+    if suspension.until <= chrono::Utc::now() {
+        return Err(CustomAppError::from((
+            "A suspension must end in the future".to_string(),
+            ErrorContext::BadRequest,
+        )));
+    }
+
+    let suspension = UserSuspension {
+        until: suspension.until,
+        reason: suspension.reason.trim().to_string(),
+    };
+    if suspension.reason.is_empty() {
+        return Err(CustomAppError::from((
+            "A reason is required to suspend a user".to_string(),
+            ErrorContext::BadRequest,
+        )));
+    }
+
+    let user = state
+        .db_store
+        .suspend_user_in_db(user_id, admin.id(), &suspension)
+        .await?;
+    // Open sessions are refused from the next request on
+    crate::utils::save_user_suspension(&state.redis_store, &user_id, &suspension).await?;
+    audit_admin_action(
+        &state,
+        &admin,
+        AuditEvent::UserSuspended,
+        user_id,
+        &client,
+        serde_json::json!({ "until": suspension.until, "reason": suspension.reason }),
+    )
+    .await;
+
+    Ok(CustomAppJson(user).into_response())
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "unsuspend_user", skip(state, admin, client))]
+pub async fn unsuspend_user(
+    Path(user_id): Path<uuid::Uuid>,
+    State(state): State<AppState>,
+    admin: AuthenticatedUser,
+    client: ClientInfo,
+) -> Result<impl IntoResponse, CustomAppError> {
+    manageable_user(&state, &admin, user_id).await?;
+
+    let user = state.db_store.unsuspend_user_in_db(user_id).await?;
+    crate::utils::lift_user_suspension(&state.redis_store, &user_id).await?;
+    audit_admin_action(
+        &state,
+        &admin,
+        AuditEvent::UserUnsuspended,
+        user_id,
+        &client,
+        serde_json::json!({}),
+    )
+    .await;
+
+    Ok(CustomAppJson(user).into_response())
+}
@@ -0,0 +1,136 @@
+use crate::{
+    models::{CandleHistory, MarketHistoryQuery, PriceHistory},
+    settings::MarketHistorySettings,
+    startup::AppState,
+    utils::{CustomAppError, CustomAppJson, ErrorContext},
+};
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+};
+
+/// Range served when `from` is left out.
+const DEFAULT_PRICE_HISTORY_DAYS: i64 = 30;
+const DEFAULT_CANDLE_HISTORY_DAYS: i64 = 365;
+
+/// The currency and range asked for, with the defaults filled in.
+struct HistoryRange {
+    currency: String,
+    from: chrono::DateTime<chrono::Utc>,
+    to: chrono::DateTime<chrono::Utc>,
+    max_points: i64,
+}
+
+async fn history_range(
+    state: &AppState,
+    coin: &str,
+    query: MarketHistoryQuery,
+    default_days: i64,
+) -> Result<HistoryRange, CustomAppError> {
+    let settings: MarketHistorySettings = crate::settings::get_settings()
+        .map_err(|_| {
+            CustomAppError::from((
+                "Failed to read settings".to_string(),
+                ErrorContext::InternalServerError,
+            ))
+        })?
+        .market_history;
+
+    let currency = match query.currency {
+        Some(currency) => currency.trim().to_lowercase(),
+        None => settings.currencies.first().cloned().unwrap_or_default(),
+    };
+    if !settings.currencies.contains(&currency) {
+        return Err(CustomAppError::from((
+            format!(
+                "No history is recorded in {}. Use one of: {}",
+                currency,
+                settings.currencies.join(", ")
+            ),
+            ErrorContext::BadRequest,
+        )));
+    }
+
+    let to = query.to.unwrap_or_else(chrono::Utc::now);
+    let from = query
+        .from
+        .unwrap_or_else(|| to - chrono::Duration::days(default_days));
+    if from >= to {
+        return Err(CustomAppError::from((
+            "`from` must be before `to`".to_string(),
+            ErrorContext::BadRequest,
+        )));
+    }
+
+    state.db_store.validate_tags(&[coin.to_string()]).await?;
+
+    Ok(HistoryRange {
+        currency,
+        from,
+        to,
+        max_points: query
+            .max_points
+            .unwrap_or(settings.max_points)
+            .clamp(1, settings.max_points),
+    })
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "get_price_history", skip(state))]
+pub async fn get_price_history(
+    Path(coin): Path<String>,
+    Query(query): Query<MarketHistoryQuery>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    let range = history_range(&state, &coin, query, DEFAULT_PRICE_HISTORY_DAYS).await?;
+
+    let (points, interval_seconds) = state
+        .db_store
+        .get_price_history_from_db(
+            &coin,
+            &range.currency,
+            range.from,
+            range.to,
+            range.max_points,
+        )
+        .await?;
+
+    Ok(CustomAppJson(PriceHistory {
+        coin,
+        currency: range.currency,
+        from: range.from,
+        to: range.to,
+        interval_seconds,
+        points,
+    }))
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "get_candle_history", skip(state))]
+pub async fn get_candle_history(
+    Path(coin): Path<String>,
+    Query(query): Query<MarketHistoryQuery>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    let range = history_range(&state, &coin, query, DEFAULT_CANDLE_HISTORY_DAYS).await?;
+
+    let from = range.from.date_naive();
+    let to = range.to.date_naive();
+    // Rounded up so that the candles never outnumber `max_points`
+    let days = (to - from).num_days() + 1;
+    let interval_days = ((days + range.max_points - 1) / range.max_points) as i32;
+
+    let candles = state
+        .db_store
+        .get_candle_history_from_db(&coin, &range.currency, from, to, interval_days)
+        .await?;
+
+    Ok(CustomAppJson(CandleHistory {
+        coin,
+        currency: range.currency,
+        from,
+        to,
+        interval_days,
+        candles,
+    }))
+}
@@ -1,6 +1,7 @@
 use axum::{routing::get, Router};
 
 mod coins;
+mod history;
 mod price;
 mod prices;
 
@@ -9,4 +10,6 @@ pub fn crypto_routes() -> Router<crate::startup::AppState> {
         .route("/prices", get(price::crypto_price_handler))
         .route("/coins", get(coins::all_coins))
         .route("/coin_prices", get(prices::get_coin_market_data))
+        .route("/history/:coin", get(history::get_price_history))
+        .route("/history/:coin/candles", get(history::get_candle_history))
 }
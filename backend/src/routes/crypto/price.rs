@@ -1,22 +1,47 @@
-use crate::utils::{get_crypto_prices, CryptoPrices, CustomAppError, CustomAppJson};
-use axum::extract::Query;
+use crate::{
+    market_data::{PriceOptions, CACHE_STATUS_HEADER},
+    startup::AppState,
+    utils::{get_crypto_prices, CustomAppError, CustomAppJson},
+};
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+};
 
 #[derive(serde::Deserialize, Debug)]
 pub struct CryptoPriceRequest {
     tags: String,
-    currency: String,
+    /// Comma-separated, e.g. `usd,eur`. `currency` is still accepted.
+    #[serde(alias = "currency")]
+    vs_currencies: String,
+    #[serde(default)]
+    include_market_cap: bool,
+    #[serde(default)]
+    include_24hr_change: bool,
 }
 
 #[axum::debug_handler]
-#[tracing::instrument(name = "crypto_price_handler")]
+#[tracing::instrument(name = "crypto_price_handler", skip(state))]
 pub async fn crypto_price_handler(
     Query(crypto_req): Query<CryptoPriceRequest>,
-) -> Result<CustomAppJson<CryptoPrices>, CustomAppError> {
-    // Call the get_crypto_prices function with the tags
-    let prices = get_crypto_prices(crypto_req.tags, &crypto_req.currency)
-        .await
-        .map_err(CustomAppError::from)?;
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    let options = PriceOptions {
+        include_market_cap: crypto_req.include_market_cap,
+        include_24hr_change: crypto_req.include_24hr_change,
+    };
 
-    // Return the prices wrapped in CustomAppJson
-    Ok(CustomAppJson(prices))
+    let (prices, cache_status) = get_crypto_prices(
+        &state.market_data,
+        &crypto_req.tags,
+        &crypto_req.vs_currencies,
+        options,
+        state.market_data_settings.max_tags_per_request,
+    )
+    .await?;
+
+    Ok((
+        [(CACHE_STATUS_HEADER, cache_status.as_str())],
+        CustomAppJson(prices),
+    ))
 }
@@ -1,8 +1,15 @@
 use crate::{
-    settings,
-    utils::{CustomAppError, CustomAppJson},
+    market_data::{
+        CacheStatus, CoinMarketData, MarketDataError, CACHE_STATUS_HEADER, MAX_CHART_DAYS,
+    },
+    startup::AppState,
+    utils::{CustomAppError, CustomAppJson, ErrorContext},
 };
-use axum::extract::Query;
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+};
+use futures::StreamExt;
 use std::collections::HashMap;
 
 #[derive(serde::Deserialize, Debug)]
@@ -12,40 +19,138 @@ pub struct CoinMarketDataRequest {
     days: i32,
 }
 
-#[derive(serde::Deserialize, Debug, serde::Serialize)]
-pub struct CoinMarketData {
-    prices: Vec<Vec<f64>>,
-    market_caps: Vec<Vec<f64>>,
-    total_volumes: Vec<Vec<f64>>,
+/// Why a coin has no chart: the provider knows nothing about it, or asking
+/// failed and may work on a retry.
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum CoinDataErrorKind {
+    NotFound,
+    FetchFailed,
+}
+
+#[derive(serde::Serialize, Debug)]
+pub struct CoinDataError {
+    kind: CoinDataErrorKind,
+    message: String,
+}
+
+impl From<MarketDataError> for CoinDataError {
+    fn from(error: MarketDataError) -> Self {
+        match error {
+            MarketDataError::UnknownCoin(id) => CoinDataError {
+                kind: CoinDataErrorKind::NotFound,
+                message: format!("No market data for coin {}", id),
+            },
+            error => {
+                tracing::error!("Failed to fetch market data: {}", error);
+                CoinDataError {
+                    kind: CoinDataErrorKind::FetchFailed,
+                    message: "Failed to fetch market data".to_string(),
+                }
+            }
+        }
+    }
+}
+
+/// Charts of the coins that could be fetched, and why the others could not.
+#[derive(serde::Serialize, Debug, Default)]
+pub struct CoinMarketDataResponse {
+    data: HashMap<String, CoinMarketData>,
+    errors: HashMap<String, CoinDataError>,
 }
 
 #[axum::debug_handler]
-#[tracing::instrument(name = "get_coin_market_data")]
+#[tracing::instrument(name = "get_coin_market_data", skip(state))]
 pub async fn get_coin_market_data(
     Query(coin_req): Query<CoinMarketDataRequest>,
-) -> Result<CustomAppJson<HashMap<String, CoinMarketData>>, CustomAppError> {
-    let tag_ids: Vec<String> = coin_req.tags.split(',').map(|s| s.to_string()).collect();
-    let mut responses = HashMap::new();
-    let settings = settings::get_settings().expect("Failed to get settings");
-    for tag_id in tag_ids {
-        let url = format!(
-            "{}/coins/{}/market_chart?vs_currency={}&days={}",
-            settings.coingecko.api_url, &tag_id, coin_req.currency, coin_req.days
-        );
-        match reqwest::get(&url).await {
-            Ok(response) => match response.json::<CoinMarketData>().await {
-                Ok(data) => {
-                    responses.insert(tag_id, data);
-                }
-                Err(e) => {
-                    tracing::error!("Failed to parse market data from response: {}", e);
-                }
-            },
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    let settings = &state.market_data_settings;
+
+    let mut tag_ids: Vec<String> = Vec::new();
+    for tag_id in coin_req.tags.split(',').map(|s| s.trim().to_lowercase()) {
+        if !tag_id.is_empty() && !tag_ids.contains(&tag_id) {
+            tag_ids.push(tag_id);
+        }
+    }
+
+    if tag_ids.is_empty() {
+        return Err(CustomAppError::from((
+            "At least one coin is required".to_string(),
+            ErrorContext::BadRequest,
+        )));
+    }
+    if let Some(tag_id) = tag_ids
+        .iter()
+        .find(|tag_id| !crate::market_data::is_valid_coin_id(tag_id))
+    {
+        return Err(CustomAppError::from((
+            format!("Invalid coin id: {}", tag_id),
+            ErrorContext::BadRequest,
+        )));
+    }
+    if coin_req.days < 1 {
+        return Err(CustomAppError::from((
+            "`days` must be at least 1".to_string(),
+            ErrorContext::BadRequest,
+        )));
+    }
+    if coin_req.days > MAX_CHART_DAYS {
+        return Err(CustomAppError::from((
+            format!("`days` must be at most {}", MAX_CHART_DAYS),
+            ErrorContext::BadRequest,
+        )));
+    }
+    if tag_ids.len() > settings.max_tags_per_request {
+        return Err(CustomAppError::from((
+            format!(
+                "At most {} coins can be charted at once",
+                settings.max_tags_per_request
+            ),
+            ErrorContext::BadRequest,
+        )));
+    }
+
+    let market_data = &state.market_data;
+
+    // Every currency is a separate cache entry, so only known ones get that far
+    let currency = coin_req.currency.trim().to_lowercase();
+    let (supported, _) = market_data.supported_vs_currencies().await?;
+    if !supported.contains(&currency) {
+        return Err(CustomAppError::from((
+            format!("Unsupported currency: {}", currency),
+            ErrorContext::BadRequest,
+        )));
+    }
+    let currency = &currency;
+    let results: Vec<_> = futures::stream::iter(tag_ids)
+        .map(|tag_id| async move {
+            let result = market_data
+                .market_chart(&tag_id, currency, coin_req.days)
+                .await;
+            (tag_id, result)
+        })
+        .buffer_unordered(settings.max_concurrent_requests.max(1))
+        .collect()
+        .await;
+
+    let mut response = CoinMarketDataResponse::default();
+    let mut cache_status = CacheStatus::Hit;
+    for (tag_id, result) in results {
+        match result {
+            Ok((data, status)) => {
+                response.data.insert(tag_id, data);
+                cache_status = cache_status.combine(status);
+            }
             Err(e) => {
-                tracing::error!("Failed to fetch market data from CoinGecko: {}", e);
+                response.errors.insert(tag_id, e.into());
+                cache_status = CacheStatus::Miss;
             }
         }
     }
 
-    Ok(CustomAppJson(responses))
+    Ok((
+        [(CACHE_STATUS_HEADER, cache_status.as_str())],
+        CustomAppJson(response),
+    ))
 }
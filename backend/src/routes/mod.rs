@@ -1,8 +1,10 @@
+mod admin;
 mod crypto;
 mod health;
 mod qa;
 mod users;
 
+pub use admin::admin_routes;
 pub use crypto::crypto_routes;
 pub use health::health_check;
 pub use qa::qa_routes;
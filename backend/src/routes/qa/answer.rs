@@ -1,31 +1,39 @@
 use crate::{
     models::{CreateAnswer, NewAnswer},
     startup::AppState,
-    utils::{CustomAppError, CustomAppJson},
+    utils::{AuthenticatedUser, CustomAppError, CustomAppJson, ErrorContext},
 };
 use axum::{
     extract::{Path, State},
     response::IntoResponse,
 };
-use axum_extra::extract::PrivateCookieJar;
 
 #[axum::debug_handler]
-#[tracing::instrument(name = "answer_question", skip(state))]
+#[tracing::instrument(name = "answer_question", skip(state, user))]
 pub async fn answer_question(
     Path(question_id): Path<uuid::Uuid>,
     State(state): State<AppState>,
-    cookies: PrivateCookieJar,
+    user: AuthenticatedUser,
     CustomAppJson(new_answer): CustomAppJson<NewAnswer>,
 ) -> Result<impl IntoResponse, CustomAppError> {
-    // Get author id from session
-    let (user_uuid, _) =
-        crate::utils::get_user_id_from_session(&cookies, &state.redis_store, false).await?;
+    let question = state
+        .db_store
+        .get_question_from_db(None, question_id)
+        .await?;
+
+    if question.closed_at.is_some() {
+        return Err(CustomAppError::from((
+            "This question has been closed and no longer accepts answers".to_string(),
+            ErrorContext::BadRequest,
+        )));
+    }
+    user.authorize_contribution(question.locked_at.is_some())?;
 
     // Create answer
     let create_answer = CreateAnswer {
         content: crate::utils::convert_markdown_to_html(&new_answer.content).await,
         raw_content: new_answer.content,
-        author: user_uuid,
+        author: user.id(),
         question: question_id,
     };
 
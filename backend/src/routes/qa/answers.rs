@@ -1,14 +1,15 @@
 use crate::{
-    models::{NewAnswer, UpdateAnswer},
+    models::{AuditTarget, ModerationActionKind, ModerationReason, NewAnswer, UpdateAnswer},
     startup::AppState,
-    utils::{CustomAppError, CustomAppJson, ErrorContext},
+    utils::{
+        AuthenticatedUser, ClientInfo, Credentials, CustomAppError, CustomAppJson, ErrorContext,
+    },
 };
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     response::IntoResponse,
 };
-use axum_extra::extract::PrivateCookieJar;
 
 #[axum::debug_handler]
 #[tracing::instrument(name = "question_answers", skip(state))]
@@ -25,19 +26,23 @@ pub async fn question_answers(
 }
 
 #[axum::debug_handler]
-#[tracing::instrument(name = "delete_an_answer", skip(state))]
+#[tracing::instrument(name = "delete_an_answer", skip(state, user, client))]
 pub async fn delete_an_answer(
     Path(answer_id): Path<uuid::Uuid>,
+    Query(moderation): Query<ModerationReason>,
     State(state): State<AppState>,
-    cookies: PrivateCookieJar,
+    user: AuthenticatedUser,
+    client: ClientInfo,
 ) -> Result<impl IntoResponse, CustomAppError> {
-    // Get author id from session
-    let (user_uuid, _) =
-        crate::utils::get_user_id_from_session(&cookies, &state.redis_store, false).await?;
+    let answer = state
+        .db_store
+        .get_an_answer_from_db(None, answer_id)
+        .await?;
+    let moderated = user.authorize_change(answer.author.id, answer.locked_at.is_some())?;
 
     state
         .db_store
-        .delete_answer_from_db(user_uuid, answer_id)
+        .delete_answer_from_db(user.id(), answer_id, moderated, moderation.reason.clone())
         .await
         .map_err(|_| {
             CustomAppError::from((
@@ -46,6 +51,17 @@ pub async fn delete_an_answer(
                 ErrorContext::UnauthorizedAccess,
             ))
         })?;
+    if moderated {
+        crate::utils::record_moderation_audit_event(
+            &state.db_store,
+            user.id(),
+            ModerationActionKind::DeleteAnswer,
+            AuditTarget::Answer(answer_id),
+            &client,
+            moderation.reason.as_deref(),
+        )
+        .await;
+    }
 
     Ok(crate::utils::SuccessResponse {
         message: "Answer deleted successfully".to_string(),
@@ -56,27 +72,32 @@ pub async fn delete_an_answer(
 }
 
 #[axum::debug_handler]
-#[tracing::instrument(name = "update_answer", skip(state))]
+#[tracing::instrument(name = "update_answer", skip(state, user, client))]
 pub async fn update_answer(
     Path(answer_id): Path<uuid::Uuid>,
     State(state): State<AppState>,
-    cookies: PrivateCookieJar,
+    user: AuthenticatedUser,
+    client: ClientInfo,
     CustomAppJson(new_answer): CustomAppJson<NewAnswer>,
 ) -> Result<impl IntoResponse, CustomAppError> {
-    // Get author id from session
-    let (user_uuid, _) =
-        crate::utils::get_user_id_from_session(&cookies, &state.redis_store, false).await?;
+    let answer = state
+        .db_store
+        .get_an_answer_from_db(None, answer_id)
+        .await?;
+    let moderated = user.authorize_change(answer.author.id, answer.locked_at.is_some())?;
 
     let new_answer = UpdateAnswer {
         content: crate::utils::convert_markdown_to_html(&new_answer.content).await,
         raw_content: new_answer.content,
-        author: user_uuid,
+        author: user.id(),
         answer_id,
+        edit_summary: new_answer.edit_summary,
     };
+    let edit_summary = new_answer.edit_summary.clone();
 
     let answer = state
         .db_store
-        .update_answer_in_db(new_answer)
+        .update_answer_in_db(new_answer, moderated)
         .await
         .map_err(|_| {
             CustomAppError::from((
@@ -85,6 +106,69 @@ pub async fn update_answer(
                 ErrorContext::UnauthorizedAccess,
             ))
         })?;
+    if moderated {
+        crate::utils::record_moderation_audit_event(
+            &state.db_store,
+            user.id(),
+            ModerationActionKind::EditAnswer,
+            AuditTarget::Answer(answer_id),
+            &client,
+            edit_summary.as_deref(),
+        )
+        .await;
+    }
+
+    Ok(CustomAppJson(answer).into_response())
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "accept_answer", skip(credentials, state))]
+pub async fn accept_answer(
+    Path(answer_id): Path<uuid::Uuid>,
+    State(state): State<AppState>,
+    credentials: Credentials,
+) -> Result<impl IntoResponse, CustomAppError> {
+    // Get question author id from session
+    let (user_uuid, _) =
+        crate::utils::get_user_id_from_session(&credentials, &state, false).await?;
+
+    let answer = state
+        .db_store
+        .accept_answer_in_db(user_uuid, answer_id)
+        .await
+        .map_err(|_| {
+            CustomAppError::from((
+                "Failed to accept answer and it's most probably due to not being the question's author"
+                    .to_string(),
+                ErrorContext::UnauthorizedAccess,
+            ))
+        })?;
+
+    Ok(CustomAppJson(answer).into_response())
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "unaccept_answer", skip(credentials, state))]
+pub async fn unaccept_answer(
+    Path(answer_id): Path<uuid::Uuid>,
+    State(state): State<AppState>,
+    credentials: Credentials,
+) -> Result<impl IntoResponse, CustomAppError> {
+    // Get question author id from session
+    let (user_uuid, _) =
+        crate::utils::get_user_id_from_session(&credentials, &state, false).await?;
+
+    let answer = state
+        .db_store
+        .unaccept_answer_in_db(user_uuid, answer_id)
+        .await
+        .map_err(|_| {
+            CustomAppError::from((
+                "Failed to unaccept answer and it's most probably due to not being accepted or not being the question's author"
+                    .to_string(),
+                ErrorContext::UnauthorizedAccess,
+            ))
+        })?;
 
     Ok(CustomAppJson(answer).into_response())
 }
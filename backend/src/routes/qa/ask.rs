@@ -1,14 +1,13 @@
 use crate::models::{CreateQuestion, NewQuestion};
 use crate::startup::AppState;
-use crate::utils::{CustomAppError, CustomAppJson};
+use crate::utils::{Credentials, CustomAppError, CustomAppJson};
 use axum::{extract::State, response::IntoResponse};
-use axum_extra::extract::PrivateCookieJar;
 
 #[axum::debug_handler]
-#[tracing::instrument(name = "ask_question", skip(state, cookies, new_question))]
+#[tracing::instrument(name = "ask_question", skip(state, credentials, new_question))]
 pub async fn ask_question(
     State(state): State<AppState>,
-    cookies: PrivateCookieJar,
+    credentials: Credentials,
     CustomAppJson(new_question): CustomAppJson<NewQuestion>,
 ) -> Result<impl IntoResponse, CustomAppError> {
     if new_question.title.is_empty() {
@@ -56,7 +55,7 @@ pub async fn ask_question(
 
     // Get author id from session
     let (user_uuid, _) =
-        crate::utils::get_user_id_from_session(&cookies, &state.redis_store, false).await?;
+        crate::utils::get_user_id_from_session(&credentials, &state, false).await?;
 
     // Create question
     let create_question = CreateQuestion {
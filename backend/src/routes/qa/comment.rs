@@ -0,0 +1,94 @@
+use crate::{
+    models::{CreateComment, NewComment},
+    startup::AppState,
+    utils::{AuthenticatedUser, CustomAppError, CustomAppJson, ErrorContext},
+};
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+};
+
+const MAX_COMMENT_LENGTH: usize = 600;
+
+pub(super) fn validate_comment_content(content: &str) -> Result<(), CustomAppError> {
+    if content.trim().is_empty() {
+        return Err(CustomAppError::from((
+            "Comment cannot be empty".to_string(),
+            ErrorContext::BadRequest,
+        )));
+    }
+
+    if content.chars().count() > MAX_COMMENT_LENGTH {
+        return Err(CustomAppError::from((
+            format!(
+                "Comment cannot be more than {} characters",
+                MAX_COMMENT_LENGTH
+            ),
+            ErrorContext::BadRequest,
+        )));
+    }
+
+    Ok(())
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "comment_on_question", skip(state, user))]
+pub async fn comment_on_question(
+    Path(question_id): Path<uuid::Uuid>,
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    CustomAppJson(new_comment): CustomAppJson<NewComment>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    validate_comment_content(&new_comment.content)?;
+
+    // Make sure the question exists and is open to comments
+    let question = state
+        .db_store
+        .get_question_from_db(None, question_id)
+        .await?;
+    user.authorize_contribution(question.locked_at.is_some())?;
+
+    let create_comment = CreateComment {
+        content: crate::utils::convert_markdown_to_html(&new_comment.content).await,
+        raw_content: new_comment.content,
+        author: user.id(),
+        question: Some(question_id),
+        answer: None,
+        parent: new_comment.parent,
+    };
+
+    let comment = state.db_store.create_comment_in_db(create_comment).await?;
+
+    Ok(axum::Json(comment).into_response())
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "comment_on_answer", skip(state, user))]
+pub async fn comment_on_answer(
+    Path(answer_id): Path<uuid::Uuid>,
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    CustomAppJson(new_comment): CustomAppJson<NewComment>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    validate_comment_content(&new_comment.content)?;
+
+    // Make sure the answer exists and is open to comments
+    let answer = state
+        .db_store
+        .get_an_answer_from_db(None, answer_id)
+        .await?;
+    user.authorize_contribution(answer.locked_at.is_some())?;
+
+    let create_comment = CreateComment {
+        content: crate::utils::convert_markdown_to_html(&new_comment.content).await,
+        raw_content: new_comment.content,
+        author: user.id(),
+        question: None,
+        answer: Some(answer_id),
+        parent: new_comment.parent,
+    };
+
+    let comment = state.db_store.create_comment_in_db(create_comment).await?;
+
+    Ok(axum::Json(comment).into_response())
+}
@@ -0,0 +1,134 @@
+use crate::{
+    models::{AuditTarget, ModerationActionKind, ModerationReason, NewComment, UpdateComment},
+    startup::AppState,
+    utils::{AuthenticatedUser, ClientInfo, CustomAppError, CustomAppJson, ErrorContext},
+};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "question_comments", skip(state))]
+pub async fn question_comments(
+    Path(question_id): Path<uuid::Uuid>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    let comments = state
+        .db_store
+        .get_question_comments_from_db(question_id)
+        .await?;
+
+    Ok(axum::Json(comments).into_response())
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "answer_comments", skip(state))]
+pub async fn answer_comments(
+    Path(answer_id): Path<uuid::Uuid>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    let comments = state
+        .db_store
+        .get_answer_comments_from_db(answer_id)
+        .await?;
+
+    Ok(axum::Json(comments).into_response())
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "delete_a_comment", skip(state, user, client))]
+pub async fn delete_a_comment(
+    Path(comment_id): Path<uuid::Uuid>,
+    Query(moderation): Query<ModerationReason>,
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    client: ClientInfo,
+) -> Result<impl IntoResponse, CustomAppError> {
+    let (author_id, is_locked) = state
+        .db_store
+        .get_comment_lock_state_from_db(comment_id)
+        .await?;
+    let moderated = user.authorize_change(author_id, is_locked)?;
+
+    state
+        .db_store
+        .delete_comment_from_db(user.id(), comment_id, moderated, moderation.reason.clone())
+        .await
+        .map_err(|_| {
+            CustomAppError::from((
+                "Failed to delete comment and it's most probably due to not being authorized"
+                    .to_string(),
+                ErrorContext::UnauthorizedAccess,
+            ))
+        })?;
+    if moderated {
+        crate::utils::record_moderation_audit_event(
+            &state.db_store,
+            user.id(),
+            ModerationActionKind::DeleteComment,
+            AuditTarget::Comment(comment_id),
+            &client,
+            moderation.reason.as_deref(),
+        )
+        .await;
+    }
+
+    Ok(crate::utils::SuccessResponse {
+        message: "Comment deleted successfully".to_string(),
+        status_code: StatusCode::NO_CONTENT.as_u16(),
+        ..Default::default()
+    }
+    .into_response())
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "update_comment", skip(state, user, client))]
+pub async fn update_comment(
+    Path(comment_id): Path<uuid::Uuid>,
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    client: ClientInfo,
+    CustomAppJson(new_comment): CustomAppJson<NewComment>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    super::comment::validate_comment_content(&new_comment.content)?;
+
+    let (author_id, is_locked) = state
+        .db_store
+        .get_comment_lock_state_from_db(comment_id)
+        .await?;
+    let moderated = user.authorize_change(author_id, is_locked)?;
+
+    let update_comment = UpdateComment {
+        content: crate::utils::convert_markdown_to_html(&new_comment.content).await,
+        raw_content: new_comment.content,
+        author: user.id(),
+        comment_id,
+    };
+
+    let comment = state
+        .db_store
+        .update_comment_in_db(update_comment, moderated)
+        .await
+        .map_err(|_| {
+            CustomAppError::from((
+                "Failed to update comment and it's most probably due to not being authorized"
+                    .to_string(),
+                ErrorContext::UnauthorizedAccess,
+            ))
+        })?;
+    if moderated {
+        crate::utils::record_moderation_audit_event(
+            &state.db_store,
+            user.id(),
+            ModerationActionKind::EditComment,
+            AuditTarget::Comment(comment_id),
+            &client,
+            None,
+        )
+        .await;
+    }
+
+    Ok(CustomAppJson(comment).into_response())
+}
@@ -7,7 +7,13 @@ use axum::{
 mod answer;
 mod answers;
 mod ask;
+mod comment;
+mod comments;
+mod moderation;
 mod questions;
+mod revisions;
+mod search;
+mod vote;
 
 pub fn qa_routes(state: crate::startup::AppState) -> Router<crate::startup::AppState> {
     Router::new()
@@ -21,14 +27,92 @@ pub fn qa_routes(state: crate::startup::AppState) -> Router<crate::startup::AppS
             "/answers/:answer_id",
             delete(answers::delete_an_answer).patch(answers::update_answer),
         )
+        .route(
+            "/questions/:question_id/comments",
+            post(comment::comment_on_question),
+        )
+        .route(
+            "/answers/:answer_id/comments",
+            post(comment::comment_on_answer),
+        )
+        .route(
+            "/comments/:comment_id",
+            delete(comments::delete_a_comment).patch(comments::update_comment),
+        )
+        .route(
+            "/questions/:question_id/revisions/:revision/rollback",
+            post(revisions::rollback_question),
+        )
+        .route(
+            "/answers/:answer_id/revisions/:revision/rollback",
+            post(revisions::rollback_answer),
+        )
+        .route(
+            "/questions/:question_id/vote",
+            post(vote::vote_question).delete(vote::retract_question_vote),
+        )
+        .route(
+            "/answers/:answer_id/accept",
+            post(answers::accept_answer).delete(answers::unaccept_answer),
+        )
+        .route(
+            "/answers/:answer_id/vote",
+            post(vote::vote_answer).delete(vote::retract_answer_vote),
+        )
+        .route(
+            "/questions/:question_id/close",
+            post(moderation::close_question).delete(moderation::reopen_question),
+        )
+        .route(
+            "/questions/:question_id/lock",
+            post(moderation::lock_question).delete(moderation::unlock_question),
+        )
+        .route(
+            "/answers/:answer_id/lock",
+            post(moderation::lock_answer).delete(moderation::unlock_answer),
+        )
+        .route("/moderation/actions", get(moderation::moderation_log))
         .route_layer(axum::middleware::from_fn_with_state(
             state.clone(),
             validate_authentication_session,
         ))
         .route("/questions", get(questions::all_questions))
+        .route("/search", get(search::search_questions))
         .route("/questions/:question_id", get(questions::get_question))
         .route(
             "/questions/:question_id/answers",
             get(answers::question_answers),
         )
+        .route(
+            "/questions/:question_id/comments",
+            get(comments::question_comments),
+        )
+        .route(
+            "/answers/:answer_id/comments",
+            get(comments::answer_comments),
+        )
+        .route(
+            "/questions/:question_id/revisions",
+            get(revisions::question_revisions),
+        )
+        .route(
+            "/questions/:question_id/revisions/:revision",
+            get(revisions::question_revision),
+        )
+        .route(
+            "/questions/:question_id/diff",
+            get(revisions::question_revisions_diff),
+        )
+        .route(
+            "/answers/:answer_id/revisions",
+            get(revisions::answer_revisions),
+        )
+        .route(
+            "/answers/:answer_id/revisions/:revision",
+            get(revisions::answer_revision),
+        )
+        .route(
+            "/answers/:answer_id/diff",
+            get(revisions::answer_revisions_diff),
+        )
 }
@@ -0,0 +1,273 @@
+use crate::{
+    models::{AuditTarget, ModerationActionKind, ModerationLogQuery, ModerationReason},
+    startup::AppState,
+    utils::{
+        AuthenticatedUser, ClientInfo, CustomAppError, CustomAppJson, ErrorContext, Paginated,
+        Pagination, Role,
+    },
+};
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+};
+
+fn already(message: &str) -> CustomAppError {
+    CustomAppError::from((message.to_string(), ErrorContext::BadRequest))
+}
+
+/// Blank reasons are treated as no reason at all.
+fn clean_reason(moderation: ModerationReason) -> Option<String> {
+    moderation
+        .reason
+        .map(|reason| reason.trim().to_string())
+        .filter(|reason| !reason.is_empty())
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "close_question", skip(state, user, client))]
+pub async fn close_question(
+    Path(question_id): Path<uuid::Uuid>,
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    client: ClientInfo,
+    CustomAppJson(moderation): CustomAppJson<ModerationReason>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    user.require_role(Role::Moderator)?;
+
+    // Askers are told why their question was closed
+    let reason = clean_reason(moderation).ok_or_else(|| {
+        CustomAppError::from((
+            "A reason is required to close a question".to_string(),
+            ErrorContext::BadRequest,
+        ))
+    })?;
+
+    let question = state
+        .db_store
+        .get_question_from_db(None, question_id)
+        .await?;
+    if question.closed_at.is_some() {
+        return Err(already("This question is already closed"));
+    }
+
+    let question = state
+        .db_store
+        .set_question_closed_in_db(user.id(), question_id, true, Some(reason.clone()))
+        .await?;
+    crate::utils::record_moderation_audit_event(
+        &state.db_store,
+        user.id(),
+        ModerationActionKind::CloseQuestion,
+        AuditTarget::Question(question_id),
+        &client,
+        Some(&reason),
+    )
+    .await;
+
+    Ok(CustomAppJson(question).into_response())
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "reopen_question", skip(state, user, client))]
+pub async fn reopen_question(
+    Path(question_id): Path<uuid::Uuid>,
+    Query(moderation): Query<ModerationReason>,
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    client: ClientInfo,
+) -> Result<impl IntoResponse, CustomAppError> {
+    user.require_role(Role::Moderator)?;
+    let reason = clean_reason(moderation);
+
+    let question = state
+        .db_store
+        .get_question_from_db(None, question_id)
+        .await?;
+    if question.closed_at.is_none() {
+        return Err(already("This question is not closed"));
+    }
+
+    let question = state
+        .db_store
+        .set_question_closed_in_db(user.id(), question_id, false, reason.clone())
+        .await?;
+    crate::utils::record_moderation_audit_event(
+        &state.db_store,
+        user.id(),
+        ModerationActionKind::ReopenQuestion,
+        AuditTarget::Question(question_id),
+        &client,
+        reason.as_deref(),
+    )
+    .await;
+
+    Ok(CustomAppJson(question).into_response())
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "lock_question", skip(state, user, client))]
+pub async fn lock_question(
+    Path(question_id): Path<uuid::Uuid>,
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    client: ClientInfo,
+    CustomAppJson(moderation): CustomAppJson<ModerationReason>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    user.require_role(Role::Moderator)?;
+    let reason = clean_reason(moderation);
+
+    let question = state
+        .db_store
+        .get_question_from_db(None, question_id)
+        .await?;
+    if question.locked_at.is_some() {
+        return Err(already("This question is already locked"));
+    }
+
+    let question = state
+        .db_store
+        .set_question_locked_in_db(user.id(), question_id, true, reason.clone())
+        .await?;
+    crate::utils::record_moderation_audit_event(
+        &state.db_store,
+        user.id(),
+        ModerationActionKind::LockQuestion,
+        AuditTarget::Question(question_id),
+        &client,
+        reason.as_deref(),
+    )
+    .await;
+
+    Ok(CustomAppJson(question).into_response())
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "unlock_question", skip(state, user, client))]
+pub async fn unlock_question(
+    Path(question_id): Path<uuid::Uuid>,
+    Query(moderation): Query<ModerationReason>,
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    client: ClientInfo,
+) -> Result<impl IntoResponse, CustomAppError> {
+    user.require_role(Role::Moderator)?;
+    let reason = clean_reason(moderation);
+
+    let question = state
+        .db_store
+        .get_question_from_db(None, question_id)
+        .await?;
+    if question.locked_at.is_none() {
+        return Err(already("This question is not locked"));
+    }
+
+    let question = state
+        .db_store
+        .set_question_locked_in_db(user.id(), question_id, false, reason.clone())
+        .await?;
+    crate::utils::record_moderation_audit_event(
+        &state.db_store,
+        user.id(),
+        ModerationActionKind::UnlockQuestion,
+        AuditTarget::Question(question_id),
+        &client,
+        reason.as_deref(),
+    )
+    .await;
+
+    Ok(CustomAppJson(question).into_response())
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "lock_answer", skip(state, user, client))]
+pub async fn lock_answer(
+    Path(answer_id): Path<uuid::Uuid>,
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    client: ClientInfo,
+    CustomAppJson(moderation): CustomAppJson<ModerationReason>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    user.require_role(Role::Moderator)?;
+    let reason = clean_reason(moderation);
+
+    let answer = state
+        .db_store
+        .get_an_answer_from_db(None, answer_id)
+        .await?;
+    // `locked_at` also reflects a lock on the question, which is lifted there
+    if answer.locked_at.is_some() {
+        return Err(already(
+            "This answer is already locked, either on its own or with its question",
+        ));
+    }
+
+    let answer = state
+        .db_store
+        .set_answer_locked_in_db(user.id(), answer_id, true, reason.clone())
+        .await?;
+    crate::utils::record_moderation_audit_event(
+        &state.db_store,
+        user.id(),
+        ModerationActionKind::LockAnswer,
+        AuditTarget::Answer(answer_id),
+        &client,
+        reason.as_deref(),
+    )
+    .await;
+
+    Ok(CustomAppJson(answer).into_response())
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "unlock_answer", skip(state, user, client))]
+pub async fn unlock_answer(
+    Path(answer_id): Path<uuid::Uuid>,
+    Query(moderation): Query<ModerationReason>,
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    client: ClientInfo,
+) -> Result<impl IntoResponse, CustomAppError> {
+    user.require_role(Role::Moderator)?;
+    let reason = clean_reason(moderation);
+
+    let answer = state
+        .db_store
+        .set_answer_locked_in_db(user.id(), answer_id, false, reason.clone())
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => {
+                already("This answer does not exist or is not locked on its own")
+            }
+            e => e.into(),
+        })?;
+    crate::utils::record_moderation_audit_event(
+        &state.db_store,
+        user.id(),
+        ModerationActionKind::UnlockAnswer,
+        AuditTarget::Answer(answer_id),
+        &client,
+        reason.as_deref(),
+    )
+    .await;
+
+    Ok(CustomAppJson(answer).into_response())
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "moderation_log", skip(state, user))]
+pub async fn moderation_log(
+    Query(log_query): Query<ModerationLogQuery>,
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+) -> Result<impl IntoResponse, CustomAppError> {
+    user.require_role(Role::Moderator)?;
+
+    let pagination = Pagination::new(log_query.page, log_query.page_size);
+
+    let (actions, total) = state
+        .db_store
+        .get_moderation_actions_from_db(&log_query, pagination)
+        .await?;
+
+    Ok(axum::Json(Paginated::new(actions, total, pagination)).into_response())
+}
@@ -1,22 +1,31 @@
 use crate::{
+    models::{AuditTarget, ModerationActionKind, ModerationReason, QuestionListQuery},
     startup::AppState,
-    utils::{CustomAppError, CustomAppJson, ErrorContext},
+    utils::{
+        AuthenticatedUser, ClientInfo, CustomAppError, CustomAppJson, ErrorContext, Paginated,
+        Pagination,
+    },
 };
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     response::IntoResponse,
 };
-use axum_extra::extract::PrivateCookieJar;
 
 #[axum::debug_handler]
 #[tracing::instrument(name = "all_question", skip(state))]
 pub async fn all_questions(
+    Query(list_query): Query<QuestionListQuery>,
     State(state): State<AppState>,
 ) -> Result<impl IntoResponse, CustomAppError> {
-    let questions = state.db_store.get_all_questions_from_db().await?;
+    let pagination = Pagination::new(list_query.page, list_query.page_size);
 
-    Ok(axum::Json(questions).into_response())
+    let (questions, total) = state
+        .db_store
+        .get_questions_from_db(&list_query, pagination)
+        .await?;
+
+    Ok(axum::Json(Paginated::new(questions, total, pagination)).into_response())
 }
 
 #[axum::debug_handler]
@@ -34,19 +43,23 @@ pub async fn get_question(
 }
 
 #[axum::debug_handler]
-#[tracing::instrument(name = "delete_a_question", skip(state))]
+#[tracing::instrument(name = "delete_a_question", skip(state, user, client))]
 pub async fn delete_a_question(
     Path(question_id): Path<uuid::Uuid>,
+    Query(moderation): Query<ModerationReason>,
     State(state): State<AppState>,
-    cookies: PrivateCookieJar,
+    user: AuthenticatedUser,
+    client: ClientInfo,
 ) -> Result<impl IntoResponse, CustomAppError> {
-    // Get author id from session
-    let (user_uuid, _) =
-        crate::utils::get_user_id_from_session(&cookies, &state.redis_store, false).await?;
+    let question = state
+        .db_store
+        .get_question_from_db(None, question_id)
+        .await?;
+    let moderated = user.authorize_change(question.author.id, question.locked_at.is_some())?;
 
     state
         .db_store
-        .delete_question_from_db(user_uuid, question_id)
+        .delete_question_from_db(user.id(), question_id, moderated, moderation.reason.clone())
         .await
         .map_err(|_| {
             CustomAppError::from((
@@ -55,6 +68,17 @@ pub async fn delete_a_question(
                 ErrorContext::UnauthorizedAccess,
             ))
         })?;
+    if moderated {
+        crate::utils::record_moderation_audit_event(
+            &state.db_store,
+            user.id(),
+            ModerationActionKind::DeleteQuestion,
+            AuditTarget::Question(question_id),
+            &client,
+            moderation.reason.as_deref(),
+        )
+        .await;
+    }
 
     let response = crate::utils::SuccessResponse {
         message: "Question deleted successfully".to_string(),
@@ -66,16 +90,19 @@ pub async fn delete_a_question(
 }
 
 #[axum::debug_handler]
-#[tracing::instrument(name = "update_a_question", skip(state))]
+#[tracing::instrument(name = "update_a_question", skip(state, user, client))]
 pub async fn update_a_question(
     Path(question_id): Path<uuid::Uuid>,
     State(state): State<AppState>,
-    cookies: PrivateCookieJar,
+    user: AuthenticatedUser,
+    client: ClientInfo,
     CustomAppJson(update_question): CustomAppJson<crate::models::UpdateQuestion>,
 ) -> Result<impl IntoResponse, CustomAppError> {
-    // Get author id from session
-    let (user_uuid, _) =
-        crate::utils::get_user_id_from_session(&cookies, &state.redis_store, false).await?;
+    let question = state
+        .db_store
+        .get_question_from_db(None, question_id)
+        .await?;
+    let moderated = user.authorize_change(question.author.id, question.locked_at.is_some())?;
 
     // Extract tags from update_question
     let mut tag_ids: Vec<String> = update_question
@@ -102,13 +129,18 @@ pub async fn update_a_question(
         title: update_question.title,
         content: crate::utils::convert_markdown_to_html(&update_question.content).await,
         raw_content: update_question.content,
-        author: user_uuid,
+        author: user.id(),
         tags: tag_ids,
     };
 
     state
         .db_store
-        .update_question_in_db(question_id, new_update_question)
+        .update_question_in_db(
+            question_id,
+            new_update_question,
+            update_question.edit_summary.clone(),
+            moderated,
+        )
         .await
         .map_err(|_| {
             CustomAppError::from((
@@ -117,6 +149,17 @@ pub async fn update_a_question(
                 ErrorContext::UnauthorizedAccess,
             ))
         })?;
+    if moderated {
+        crate::utils::record_moderation_audit_event(
+            &state.db_store,
+            user.id(),
+            ModerationActionKind::EditQuestion,
+            AuditTarget::Question(question_id),
+            &client,
+            update_question.edit_summary.as_deref(),
+        )
+        .await;
+    }
 
     let response = crate::utils::SuccessResponse {
         message: "Question updated successfully".to_string(),
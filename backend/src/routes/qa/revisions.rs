@@ -0,0 +1,231 @@
+use crate::{
+    models::{AuditTarget, CreateQuestion, ModerationActionKind, RevisionDiffQuery, UpdateAnswer},
+    startup::AppState,
+    utils::{AuthenticatedUser, ClientInfo, CustomAppError, CustomAppJson, ErrorContext},
+};
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+};
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "question_revisions", skip(state))]
+pub async fn question_revisions(
+    Path(question_id): Path<uuid::Uuid>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    let revisions = state
+        .db_store
+        .get_question_revisions_from_db(question_id)
+        .await?;
+
+    Ok(axum::Json(revisions).into_response())
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "question_revision", skip(state))]
+pub async fn question_revision(
+    Path((question_id, revision)): Path<(uuid::Uuid, i32)>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    let revision = state
+        .db_store
+        .get_question_revision_from_db(question_id, revision)
+        .await?;
+
+    Ok(axum::Json(revision).into_response())
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "question_revisions_diff", skip(state))]
+pub async fn question_revisions_diff(
+    Path(question_id): Path<uuid::Uuid>,
+    Query(diff_query): Query<RevisionDiffQuery>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    let from = state
+        .db_store
+        .get_question_revision_from_db(question_id, diff_query.from)
+        .await?;
+    let to = state
+        .db_store
+        .get_question_revision_from_db(question_id, diff_query.to)
+        .await?;
+
+    let diff = crate::utils::diff_markdown(
+        from.revision,
+        to.revision,
+        &from.raw_content,
+        &to.raw_content,
+    );
+
+    Ok(axum::Json(diff).into_response())
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "rollback_question", skip(state, user, client))]
+pub async fn rollback_question(
+    Path((question_id, revision)): Path<(uuid::Uuid, i32)>,
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    client: ClientInfo,
+) -> Result<impl IntoResponse, CustomAppError> {
+    let question = state
+        .db_store
+        .get_question_from_db(None, question_id)
+        .await?;
+    let moderated = user.authorize_change(question.author.id, question.locked_at.is_some())?;
+
+    let revision = state
+        .db_store
+        .get_question_revision_from_db(question_id, revision)
+        .await?;
+
+    let rollback_question = CreateQuestion {
+        slug: crate::utils::slugify(&revision.title).await,
+        title: revision.title,
+        content: crate::utils::convert_markdown_to_html(&revision.raw_content).await,
+        raw_content: revision.raw_content,
+        author: user.id(),
+        tags: revision.tags,
+    };
+
+    let edit_summary = format!("Rolled back to revision {}", revision.revision);
+    let question = state
+        .db_store
+        .update_question_in_db(
+            question_id,
+            rollback_question,
+            Some(edit_summary.clone()),
+            moderated,
+        )
+        .await
+        .map_err(|_| {
+            CustomAppError::from((
+                "Failed to roll back question and it's most probably due to not being authorized"
+                    .to_string(),
+                ErrorContext::UnauthorizedAccess,
+            ))
+        })?;
+    if moderated {
+        crate::utils::record_moderation_audit_event(
+            &state.db_store,
+            user.id(),
+            ModerationActionKind::EditQuestion,
+            AuditTarget::Question(question_id),
+            &client,
+            Some(&edit_summary),
+        )
+        .await;
+    }
+
+    Ok(CustomAppJson(question).into_response())
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "answer_revisions", skip(state))]
+pub async fn answer_revisions(
+    Path(answer_id): Path<uuid::Uuid>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    let revisions = state
+        .db_store
+        .get_answer_revisions_from_db(answer_id)
+        .await?;
+
+    Ok(axum::Json(revisions).into_response())
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "answer_revision", skip(state))]
+pub async fn answer_revision(
+    Path((answer_id, revision)): Path<(uuid::Uuid, i32)>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    let revision = state
+        .db_store
+        .get_answer_revision_from_db(answer_id, revision)
+        .await?;
+
+    Ok(axum::Json(revision).into_response())
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "answer_revisions_diff", skip(state))]
+pub async fn answer_revisions_diff(
+    Path(answer_id): Path<uuid::Uuid>,
+    Query(diff_query): Query<RevisionDiffQuery>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    let from = state
+        .db_store
+        .get_answer_revision_from_db(answer_id, diff_query.from)
+        .await?;
+    let to = state
+        .db_store
+        .get_answer_revision_from_db(answer_id, diff_query.to)
+        .await?;
+
+    let diff = crate::utils::diff_markdown(
+        from.revision,
+        to.revision,
+        &from.raw_content,
+        &to.raw_content,
+    );
+
+    Ok(axum::Json(diff).into_response())
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "rollback_answer", skip(state, user, client))]
+pub async fn rollback_answer(
+    Path((answer_id, revision)): Path<(uuid::Uuid, i32)>,
+    State(state): State<AppState>,
+    user: AuthenticatedUser,
+    client: ClientInfo,
+) -> Result<impl IntoResponse, CustomAppError> {
+    let answer = state
+        .db_store
+        .get_an_answer_from_db(None, answer_id)
+        .await?;
+    let moderated = user.authorize_change(answer.author.id, answer.locked_at.is_some())?;
+
+    let revision = state
+        .db_store
+        .get_answer_revision_from_db(answer_id, revision)
+        .await?;
+
+    let rollback_answer = UpdateAnswer {
+        content: crate::utils::convert_markdown_to_html(&revision.raw_content).await,
+        raw_content: revision.raw_content,
+        author: user.id(),
+        answer_id,
+        edit_summary: Some(format!("Rolled back to revision {}", revision.revision)),
+    };
+    let edit_summary = rollback_answer.edit_summary.clone();
+
+    let answer = state
+        .db_store
+        .update_answer_in_db(rollback_answer, moderated)
+        .await
+        .map_err(|_| {
+            CustomAppError::from((
+                "Failed to roll back answer and it's most probably due to not being authorized"
+                    .to_string(),
+                ErrorContext::UnauthorizedAccess,
+            ))
+        })?;
+    if moderated {
+        crate::utils::record_moderation_audit_event(
+            &state.db_store,
+            user.id(),
+            ModerationActionKind::EditAnswer,
+            AuditTarget::Answer(answer_id),
+            &client,
+            edit_summary.as_deref(),
+        )
+        .await;
+    }
+
+    Ok(CustomAppJson(answer).into_response())
+}
@@ -0,0 +1,26 @@
+use crate::{
+    models::SearchRequest,
+    startup::AppState,
+    utils::{CustomAppError, Paginated, Pagination},
+};
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+};
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "search_questions", skip(state))]
+pub async fn search_questions(
+    Query(search_request): Query<SearchRequest>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    let search = crate::utils::parse_search_query(&search_request.q)?;
+    let pagination = Pagination::new(search_request.page, search_request.page_size);
+
+    let (hits, total) = state
+        .db_store
+        .search_questions_in_db(&search, pagination)
+        .await?;
+
+    Ok(axum::Json(Paginated::new(hits, total, pagination)).into_response())
+}
@@ -0,0 +1,137 @@
+use crate::{
+    models::NewVote,
+    startup::AppState,
+    utils::{Credentials, CustomAppError, CustomAppJson, ErrorContext},
+};
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+};
+
+fn validate_vote(new_vote: &NewVote) -> Result<i16, CustomAppError> {
+    match new_vote.value {
+        1 | -1 => Ok(new_vote.value),
+        _ => Err(CustomAppError::from((
+            "Vote value must be either 1 (upvote) or -1 (downvote)".to_string(),
+            ErrorContext::BadRequest,
+        ))),
+    }
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "vote_question", skip(state, credentials))]
+pub async fn vote_question(
+    Path(question_id): Path<uuid::Uuid>,
+    State(state): State<AppState>,
+    credentials: Credentials,
+    CustomAppJson(new_vote): CustomAppJson<NewVote>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    let value = validate_vote(&new_vote)?;
+    cast_question_vote(question_id, state, credentials, Some(value)).await
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "retract_question_vote", skip(state, credentials))]
+pub async fn retract_question_vote(
+    Path(question_id): Path<uuid::Uuid>,
+    State(state): State<AppState>,
+    credentials: Credentials,
+) -> Result<impl IntoResponse, CustomAppError> {
+    cast_question_vote(question_id, state, credentials, None).await
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "vote_answer", skip(state, credentials))]
+pub async fn vote_answer(
+    Path(answer_id): Path<uuid::Uuid>,
+    State(state): State<AppState>,
+    credentials: Credentials,
+    CustomAppJson(new_vote): CustomAppJson<NewVote>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    let value = validate_vote(&new_vote)?;
+    cast_answer_vote(answer_id, state, credentials, Some(value)).await
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "retract_answer_vote", skip(state, credentials))]
+pub async fn retract_answer_vote(
+    Path(answer_id): Path<uuid::Uuid>,
+    State(state): State<AppState>,
+    credentials: Credentials,
+) -> Result<impl IntoResponse, CustomAppError> {
+    cast_answer_vote(answer_id, state, credentials, None).await
+}
+
+async fn cast_question_vote(
+    question_id: uuid::Uuid,
+    state: AppState,
+    credentials: Credentials,
+    value: Option<i16>,
+) -> Result<axum::response::Response, CustomAppError> {
+    // Get voter id from session
+    let (user_uuid, _) =
+        crate::utils::get_user_id_from_session(&credentials, &state, false).await?;
+
+    let question = state
+        .db_store
+        .get_question_from_db(None, question_id)
+        .await?;
+
+    if question.locked_at.is_some() {
+        return Err(CustomAppError::from((
+            "This question has been locked by a moderator".to_string(),
+            ErrorContext::Forbidden,
+        )));
+    }
+
+    if question.author.id == user_uuid {
+        return Err(CustomAppError::from((
+            "You cannot vote on your own question".to_string(),
+            ErrorContext::BadRequest,
+        )));
+    }
+
+    let summary = state
+        .db_store
+        .vote_on_question_in_db(question_id, user_uuid, value)
+        .await?;
+
+    Ok(CustomAppJson(summary).into_response())
+}
+
+async fn cast_answer_vote(
+    answer_id: uuid::Uuid,
+    state: AppState,
+    credentials: Credentials,
+    value: Option<i16>,
+) -> Result<axum::response::Response, CustomAppError> {
+    // Get voter id from session
+    let (user_uuid, _) =
+        crate::utils::get_user_id_from_session(&credentials, &state, false).await?;
+
+    let answer = state
+        .db_store
+        .get_an_answer_from_db(None, answer_id)
+        .await?;
+
+    if answer.locked_at.is_some() {
+        return Err(CustomAppError::from((
+            "This answer has been locked by a moderator".to_string(),
+            ErrorContext::Forbidden,
+        )));
+    }
+
+    if answer.author.id == user_uuid {
+        return Err(CustomAppError::from((
+            "You cannot vote on your own answer".to_string(),
+            ErrorContext::BadRequest,
+        )));
+    }
+
+    let summary = state
+        .db_store
+        .vote_on_answer_in_db(answer_id, user_uuid, value)
+        .await?;
+
+    Ok(CustomAppJson(summary).into_response())
+}
@@ -0,0 +1,170 @@
+use crate::{
+    models::{AccountExport, AuditEvent, AuditTarget, DeleteAccount},
+    startup::AppState,
+    utils::{
+        verify_password, ClientInfo, Credentials, CustomAppError, CustomAppJson, ErrorContext,
+        SuccessResponse,
+    },
+};
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::IntoResponse,
+};
+use axum_extra::extract::cookie::{Cookie, PrivateCookieJar};
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "export_account_data", skip(credentials, state))]
+pub async fn export_account_data(
+    credentials: Credentials,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    let (user_id, session_id) =
+        crate::utils::get_user_id_from_session(&credentials, &state, false).await?;
+
+    let profile = state.db_store.get_user_by_id(user_id).await?.into();
+    let two_factor_enabled = state
+        .db_store
+        .get_totp_secret_from_db(user_id)
+        .await?
+        .is_some();
+
+    let export = AccountExport {
+        exported_at: chrono::Utc::now(),
+        profile,
+        two_factor_enabled,
+        questions: state
+            .db_store
+            .get_exported_questions_from_db(user_id)
+            .await?,
+        answers: state.db_store.get_exported_answers_from_db(user_id).await?,
+        comments: state
+            .db_store
+            .get_exported_comments_from_db(user_id)
+            .await?,
+        votes: state.db_store.get_exported_votes_from_db(user_id).await?,
+        sessions: crate::utils::list_user_sessions(&state.redis_store, &user_id, Some(&session_id))
+            .await?,
+        api_tokens: state.db_store.get_api_tokens_from_db(user_id).await?,
+    };
+
+    // Browsers save the archive rather than display it
+    let content_disposition = format!("attachment; filename=\"cryptoflow-data-{}.json\"", user_id);
+
+    Ok((
+        [(header::CONTENT_DISPOSITION, content_disposition)],
+        CustomAppJson(export),
+    )
+        .into_response())
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(
+    name = "delete_account",
+    skip(cookies, credentials, state, client, deletion)
+)]
+pub async fn delete_account(
+    cookies: PrivateCookieJar,
+    credentials: Credentials,
+    State(state): State<AppState>,
+    client: ClientInfo,
+    CustomAppJson(deletion): CustomAppJson<DeleteAccount>,
+) -> Result<(PrivateCookieJar, impl IntoResponse), CustomAppError> {
+    let (user_id, _) = crate::utils::get_user_id_from_session(&credentials, &state, false).await?;
+
+    if credentials.uses_api_token() {
+        return Err(CustomAppError::from((
+            "Accounts can only be deleted from a logged in session".to_string(),
+            ErrorContext::Forbidden,
+        )));
+    }
+
+    let user = state.db_store.get_user_by_id(user_id).await?;
+
+    // The superuser is recreated from the settings on every start
+    if user.is_superuser.unwrap_or(false) {
+        return Err(CustomAppError::from((
+            "Superuser accounts are managed through the settings".to_string(),
+            ErrorContext::Forbidden,
+        )));
+    }
+
+    // Deleting needs the same proof as logging in, and is throttled alike
+    let ip_address = client.ip_address.as_deref();
+    crate::utils::check_login_throttle(&state.redis_store, &user.email, ip_address).await?;
+
+    let password_hash = user.password.clone();
+    let password_verified = tokio::task::spawn_blocking(move || {
+        verify_password(&password_hash, deletion.password.as_bytes())
+    })
+    .await
+    .map_err(|_| {
+        CustomAppError::from((
+            "Server error occurred".to_string(),
+            ErrorContext::InternalServerError,
+        ))
+    })?
+    .is_ok();
+    if !password_verified {
+        if crate::utils::record_failed_login(&state.redis_store, &user.email, ip_address).await? {
+            super::login::notify_lockout(&state, user, ip_address).await;
+        }
+        return Err(CustomAppError::from((
+            "Password is incorrect".to_string(),
+            ErrorContext::BadRequest,
+        )));
+    }
+
+    if let Some(secret) = state.db_store.get_totp_secret_from_db(user_id).await? {
+        let Some(code) = deletion.code.as_deref() else {
+            return Err(CustomAppError::from((
+                "Enter a two-factor code to delete your account".to_string(),
+                ErrorContext::BadRequest,
+            )));
+        };
+        if !crate::utils::verify_second_factor(&state, user_id, &user.email, &secret, code).await? {
+            if crate::utils::record_failed_login(&state.redis_store, &user.email, ip_address)
+                .await?
+            {
+                super::login::notify_lockout(&state, user, ip_address).await;
+            }
+            return Err(CustomAppError::from((
+                "Invalid two-factor code".to_string(),
+                ErrorContext::BadRequest,
+            )));
+        }
+    }
+
+    let settings = crate::settings::get_settings().map_err(|_| {
+        CustomAppError::from((
+            "Failed to read settings".to_string(),
+            ErrorContext::InternalServerError,
+        ))
+    })?;
+    let policy = settings.account_deletion.authored_content;
+
+    state.db_store.delete_account_in_db(user_id, policy).await?;
+
+    crate::utils::revoke_all_user_sessions(&state.redis_store, &user_id, None).await?;
+    crate::utils::lift_user_suspension(&state.redis_store, &user_id).await?;
+    // Where the deletion came from is personal data too
+    crate::utils::record_audit_event(
+        &state.db_store,
+        AuditEvent::AccountDeleted,
+        Some(user_id),
+        Some(AuditTarget::User(user_id)),
+        &ClientInfo::default(),
+        serde_json::json!({ "authored_content": policy }),
+    )
+    .await;
+
+    Ok((
+        cookies.remove(Cookie::from("sessionid")),
+        SuccessResponse {
+            message: "Your account has been deleted.".to_string(),
+            status_code: StatusCode::OK.as_u16(),
+            ..Default::default()
+        }
+        .into_response(),
+    ))
+}
@@ -1,15 +1,16 @@
 use crate::{
-    models::ActivateUser,
+    models::{ActivateUser, AuditEvent, AuditTarget},
     startup::AppState,
-    utils::{CustomAppError, CustomAppJson, ErrorContext, SuccessResponse},
+    utils::{ClientInfo, CustomAppError, CustomAppJson, ErrorContext, SuccessResponse},
 };
 use axum::{extract::State, http::StatusCode, response::IntoResponse};
 use sha2::{Digest, Sha256};
 
 #[axum::debug_handler]
-#[tracing::instrument(name = "activate_user_account", skip(state, acc_user))]
+#[tracing::instrument(name = "activate_user_account", skip(state, client, acc_user))]
 pub async fn activate_user_account(
     State(state): State<AppState>,
+    client: ClientInfo,
     CustomAppJson(acc_user): CustomAppJson<ActivateUser>,
 ) -> Result<impl IntoResponse, CustomAppError> {
     let mut redis_con = state.redis_store.get().await.map_err(|_| {
@@ -24,7 +25,7 @@ pub async fn activate_user_account(
     let hashed_token = format!("{:x}", hasher.finalize());
 
     let hashed_activation_code: String = bb8_redis::redis::cmd("GET")
-        .arg(&acc_user.id.to_string())
+        .arg(acc_user.id.to_string())
         .query_async(&mut *redis_con)
         .await
         .map_err(|_| {
@@ -39,7 +40,7 @@ pub async fn activate_user_account(
 
         // Delete activation code from redis
         bb8_redis::redis::cmd("DEL")
-            .arg(&acc_user.id.to_string())
+            .arg(acc_user.id.to_string())
             .query_async::<_, i64>(&mut *redis_con)
             .await
             .map_err(|_| {
@@ -49,6 +50,16 @@ pub async fn activate_user_account(
                 ))
             })?;
 
+        crate::utils::record_audit_event(
+            &state.db_store,
+            AuditEvent::AccountActivated,
+            Some(acc_user.id),
+            Some(AuditTarget::User(acc_user.id)),
+            &client,
+            serde_json::json!({}),
+        )
+        .await;
+
         Ok(SuccessResponse {
             message: "The activation process was successful. You can now login.".to_string(),
             status_code: StatusCode::OK.as_u16(),
@@ -0,0 +1,114 @@
+use crate::{
+    models::{CreatedApiToken, NewApiToken},
+    startup::AppState,
+    utils::{Credentials, CustomAppError, CustomAppJson, ErrorContext, SuccessResponse},
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+
+/// Tokens that do not say otherwise expire after a month.
+const DEFAULT_TOKEN_LIFETIME_DAYS: i64 = 30;
+const MAX_TOKEN_LIFETIME_DAYS: i64 = 365;
+const MAX_TOKEN_NAME_LENGTH: usize = 100;
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "list_api_tokens", skip(credentials, state))]
+pub async fn list_api_tokens(
+    credentials: Credentials,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    let (user_id, _) = crate::utils::get_user_id_from_session(&credentials, &state, false).await?;
+
+    let tokens = state.db_store.get_api_tokens_from_db(user_id).await?;
+
+    Ok(CustomAppJson(tokens).into_response())
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "create_api_token", skip(credentials, state, new_token))]
+pub async fn create_api_token(
+    credentials: Credentials,
+    State(state): State<AppState>,
+    CustomAppJson(new_token): CustomAppJson<NewApiToken>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    let (user_id, _) = crate::utils::get_user_id_from_session(&credentials, &state, false).await?;
+
+    // A leaked token must not be enough to mint longer-lived ones
+    if credentials.uses_api_token() {
+        return Err(CustomAppError::from((
+            "API tokens can only be created from a logged in session".to_string(),
+            ErrorContext::Forbidden,
+        )));
+    }
+
+    let name = new_token.name.trim();
+    if name.is_empty() || name.chars().count() > MAX_TOKEN_NAME_LENGTH {
+        return Err(CustomAppError::from((
+            format!(
+                "Token name must be between 1 and {} characters",
+                MAX_TOKEN_NAME_LENGTH
+            ),
+            ErrorContext::BadRequest,
+        )));
+    }
+
+    let lifetime_days = new_token
+        .expires_in_days
+        .unwrap_or(DEFAULT_TOKEN_LIFETIME_DAYS);
+    if !(1..=MAX_TOKEN_LIFETIME_DAYS).contains(&lifetime_days) {
+        return Err(CustomAppError::from((
+            format!(
+                "Tokens must expire within 1 to {} days",
+                MAX_TOKEN_LIFETIME_DAYS
+            ),
+            ErrorContext::BadRequest,
+        )));
+    }
+    let expires_at = chrono::Utc::now() + chrono::Duration::days(lifetime_days);
+
+    let (token, token_prefix, token_hash) = crate::utils::generate_api_token();
+    let api_token = state
+        .db_store
+        .create_api_token_in_db(
+            user_id,
+            name,
+            new_token.scope,
+            &token_hash,
+            &token_prefix,
+            expires_at,
+        )
+        .await?;
+
+    Ok(CustomAppJson(CreatedApiToken { api_token, token }).into_response())
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "revoke_api_token", skip(credentials, state))]
+pub async fn revoke_api_token(
+    Path(token_id): Path<uuid::Uuid>,
+    credentials: Credentials,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    let (user_id, _) = crate::utils::get_user_id_from_session(&credentials, &state, false).await?;
+
+    state
+        .db_store
+        .delete_api_token_from_db(user_id, token_id)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => {
+                CustomAppError::from(("API token not found".to_string(), ErrorContext::NotFound))
+            }
+            e => e.into(),
+        })?;
+
+    Ok(SuccessResponse {
+        message: "The API token has been revoked.".to_string(),
+        status_code: StatusCode::OK.as_u16(),
+        ..Default::default()
+    }
+    .into_response())
+}
@@ -0,0 +1,265 @@
+use crate::{
+    models::{AuditEvent, AuditTarget, ConfirmEmailChange, PendingEmailChange, RequestEmailChange},
+    startup::AppState,
+    utils::{
+        verify_password, ClientInfo, Credentials, CustomAppError, CustomAppJson, ErrorContext,
+        SuccessResponse,
+    },
+};
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use axum::{extract::State, http::StatusCode, response::IntoResponse};
+use sha2::{Digest, Sha256};
+
+/// Wrong codes after which a pending email change is dropped.
+const MAX_CONFIRMATION_ATTEMPTS: i64 = 5;
+
+fn email_change_key(user_id: &uuid::Uuid) -> String {
+    format!("email_change:{}", user_id)
+}
+
+fn email_change_attempts_key(user_id: &uuid::Uuid) -> String {
+    format!("email_change_attempts:{}", user_id)
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "request_email_change", skip(credentials, state, request), fields(new_email = request.email))]
+pub async fn request_email_change(
+    credentials: Credentials,
+    State(state): State<AppState>,
+    CustomAppJson(request): CustomAppJson<RequestEmailChange>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    let (user_id, _) = crate::utils::get_user_id_from_session(&credentials, &state, false).await?;
+
+    let new_email = request.email.trim().to_string();
+    if new_email.parse::<lettre::Address>().is_err() {
+        return Err(CustomAppError::from((
+            "Invalid email address".to_string(),
+            ErrorContext::BadRequest,
+        )));
+    }
+
+    let user = state.db_store.get_user_by_id(user_id).await?;
+
+    // Verify the current password
+    let password_hash = user.password.clone();
+    tokio::task::spawn_blocking(move || {
+        verify_password(&password_hash, request.password.as_bytes())
+    })
+    .await
+    .map_err(|_| {
+        CustomAppError::from((
+            "Server error occurred".to_string(),
+            ErrorContext::InternalServerError,
+        ))
+    })?
+    .map_err(|_| {
+        CustomAppError::from((
+            "Current password is incorrect".to_string(),
+            ErrorContext::BadRequest,
+        ))
+    })?;
+
+    if state.db_store.email_is_taken(&new_email).await? {
+        return Err(CustomAppError::from((
+            "This email address is already in use".to_string(),
+            ErrorContext::BadRequest,
+        )));
+    }
+
+    let confirmation_code = (OsRng.next_u32() % 900000 + 100000).to_string();
+    let mut hasher = Sha256::new();
+    hasher.update(confirmation_code.as_bytes());
+    let pending = PendingEmailChange {
+        email: new_email.clone(),
+        hashed_token: format!("{:x}", hasher.finalize()),
+    };
+
+    let mut redis_con = state.redis_store.get().await.map_err(|_| {
+        CustomAppError::from((
+            "Failed to get redis connection".to_string(),
+            ErrorContext::InternalServerError,
+        ))
+    })?;
+
+    let settings = crate::settings::get_settings().map_err(|_| {
+        CustomAppError::from((
+            "Failed to read settings".to_string(),
+            ErrorContext::InternalServerError,
+        ))
+    })?;
+
+    // Overwrites, and so invalidates, any change requested earlier, and
+    // starts counting wrong codes afresh
+    bb8_redis::redis::pipe()
+        .cmd("SET")
+        .arg(email_change_key(&user_id))
+        .arg(serde_json::to_string(&pending).map_err(|_| {
+            CustomAppError::from((
+                "Failed to save email change".to_string(),
+                ErrorContext::InternalServerError,
+            ))
+        })?)
+        .arg("EX")
+        .arg(settings.secret.token_expiration * 60)
+        .ignore()
+        .cmd("DEL")
+        .arg(email_change_attempts_key(&user_id))
+        .ignore()
+        .query_async::<_, ()>(&mut *redis_con)
+        .await
+        .map_err(|_| {
+            CustomAppError::from((
+                "Failed to save email change".to_string(),
+                ErrorContext::InternalServerError,
+            ))
+        })?;
+
+    // The code goes to the new address to prove it belongs to the user
+    let mut recipient = crate::models::UserVisible::from(user);
+    recipient.email = new_email;
+    crate::utils::send_multipart_email(
+        "Confirm your new CryptoFlow with Rust (axum) and SvelteKit email".to_string(),
+        recipient,
+        state.clone(),
+        "email_change.html",
+        confirmation_code,
+    )
+    .await
+    .map_err(|_| {
+        CustomAppError::from((
+            "Failed to send email change confirmation".to_string(),
+            ErrorContext::InternalServerError,
+        ))
+    })?;
+
+    Ok(SuccessResponse {
+        message: "Check your new email address for a code to confirm the change.".to_string(),
+        status_code: StatusCode::OK.as_u16(),
+        ..Default::default()
+    }
+    .into_response())
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(
+    name = "confirm_email_change",
+    skip(credentials, state, client, confirmation)
+)]
+pub async fn confirm_email_change(
+    credentials: Credentials,
+    State(state): State<AppState>,
+    client: ClientInfo,
+    CustomAppJson(confirmation): CustomAppJson<ConfirmEmailChange>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    let (user_id, _) = crate::utils::get_user_id_from_session(&credentials, &state, false).await?;
+
+    let mut redis_con = state.redis_store.get().await.map_err(|_| {
+        CustomAppError::from((
+            "Failed to get redis connection".to_string(),
+            ErrorContext::InternalServerError,
+        ))
+    })?;
+
+    let pending: String = bb8_redis::redis::cmd("GET")
+        .arg(email_change_key(&user_id))
+        .query_async(&mut *redis_con)
+        .await
+        .map_err(|_| {
+            CustomAppError::from((
+                "This email change has been confirmed or expired".to_string(),
+                ErrorContext::BadRequest,
+            ))
+        })?;
+    let pending: PendingEmailChange = serde_json::from_str(&pending).map_err(|_| {
+        CustomAppError::from((
+            "Failed to read email change".to_string(),
+            ErrorContext::InternalServerError,
+        ))
+    })?;
+
+    let mut hasher = Sha256::new();
+    hasher.update(confirmation.token.as_bytes());
+    if pending.hashed_token != format!("{:x}", hasher.finalize()) {
+        let settings = crate::settings::get_settings().map_err(|_| {
+            CustomAppError::from((
+                "Failed to read settings".to_string(),
+                ErrorContext::InternalServerError,
+            ))
+        })?;
+        let (attempts,): (i64,) = bb8_redis::redis::pipe()
+            .cmd("INCR")
+            .arg(email_change_attempts_key(&user_id))
+            .cmd("EXPIRE")
+            .arg(email_change_attempts_key(&user_id))
+            .arg(settings.secret.token_expiration * 60)
+            .ignore()
+            .query_async(&mut *redis_con)
+            .await
+            .map_err(|_| {
+                CustomAppError::from((
+                    "Failed to update email change".to_string(),
+                    ErrorContext::InternalServerError,
+                ))
+            })?;
+
+        if attempts >= MAX_CONFIRMATION_ATTEMPTS {
+            bb8_redis::redis::cmd("DEL")
+                .arg(email_change_key(&user_id))
+                .arg(email_change_attempts_key(&user_id))
+                .query_async::<_, i64>(&mut *redis_con)
+                .await
+                .map_err(|_| {
+                    CustomAppError::from((
+                        "Failed to delete email change from Redis".to_string(),
+                        ErrorContext::InternalServerError,
+                    ))
+                })?;
+            return Err(CustomAppError::from((
+                "Too many invalid codes. Please request the email change again".to_string(),
+                ErrorContext::BadRequest,
+            )));
+        }
+
+        return Err(CustomAppError::from((
+            "Confirmation code not found or expired".to_string(),
+            ErrorContext::BadRequest,
+        )));
+    }
+
+    let old_email = state.db_store.get_user_by_id(user_id).await?.email;
+
+    // Fails on the unique constraint if the address was taken in the meantime
+    state
+        .db_store
+        .update_user_email(&user_id, &pending.email)
+        .await?;
+
+    bb8_redis::redis::cmd("DEL")
+        .arg(email_change_key(&user_id))
+        .arg(email_change_attempts_key(&user_id))
+        .query_async::<_, i64>(&mut *redis_con)
+        .await
+        .map_err(|_| {
+            CustomAppError::from((
+                "Failed to delete email change from Redis".to_string(),
+                ErrorContext::InternalServerError,
+            ))
+        })?;
+
+    crate::utils::record_audit_event(
+        &state.db_store,
+        AuditEvent::EmailChanged,
+        Some(user_id),
+        Some(AuditTarget::User(user_id)),
+        &client,
+        serde_json::json!({ "old_email": old_email, "new_email": pending.email }),
+    )
+    .await;
+
+    Ok(SuccessResponse {
+        message: "Your email address has been changed.".to_string(),
+        status_code: StatusCode::OK.as_u16(),
+        ..Default::default()
+    }
+    .into_response())
+}
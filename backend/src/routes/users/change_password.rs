@@ -0,0 +1,73 @@
+use crate::{
+    models::{AuditEvent, AuditTarget, ChangePassword},
+    startup::AppState,
+    utils::{
+        verify_password, ClientInfo, Credentials, CustomAppError, CustomAppJson, ErrorContext,
+        SuccessResponse,
+    },
+};
+use axum::{extract::State, http::StatusCode, response::IntoResponse};
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "change_password", skip(credentials, state, client, change))]
+pub async fn change_password(
+    credentials: Credentials,
+    State(state): State<AppState>,
+    client: ClientInfo,
+    CustomAppJson(change): CustomAppJson<ChangePassword>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    let (user_id, session_id) =
+        crate::utils::get_user_id_from_session(&credentials, &state, false).await?;
+
+    if change.new_password.is_empty() {
+        return Err(CustomAppError::from((
+            "Password cannot be empty".to_string(),
+            ErrorContext::BadRequest,
+        )));
+    }
+
+    let user = state.db_store.get_user_by_id(user_id).await?;
+
+    // Verify the current password
+    tokio::task::spawn_blocking(move || {
+        verify_password(&user.password, change.current_password.as_bytes())
+    })
+    .await
+    .map_err(|_| {
+        CustomAppError::from((
+            "Server error occurred".to_string(),
+            ErrorContext::InternalServerError,
+        ))
+    })?
+    .map_err(|_| {
+        CustomAppError::from((
+            "Current password is incorrect".to_string(),
+            ErrorContext::BadRequest,
+        ))
+    })?;
+
+    let hashed_password = crate::utils::hash_password(change.new_password.as_bytes()).await;
+    state
+        .db_store
+        .update_user_password(&user_id, &hashed_password)
+        .await?;
+
+    // Keep this session but sign every other device out
+    crate::utils::revoke_all_user_sessions(&state.redis_store, &user_id, Some(&session_id)).await?;
+    crate::utils::record_audit_event(
+        &state.db_store,
+        AuditEvent::PasswordChanged,
+        Some(user_id),
+        Some(AuditTarget::User(user_id)),
+        &client,
+        serde_json::json!({}),
+    )
+    .await;
+
+    Ok(SuccessResponse {
+        message: "Your password has been changed.".to_string(),
+        status_code: StatusCode::OK.as_u16(),
+        ..Default::default()
+    }
+    .into_response())
+}
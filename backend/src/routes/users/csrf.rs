@@ -0,0 +1,28 @@
+use crate::{
+    models::CsrfToken,
+    startup::AppState,
+    utils::{Credentials, CustomAppError, CustomAppJson, ErrorContext},
+};
+use axum::{extract::State, response::IntoResponse};
+
+/// Hands out the token that cookie-authenticated `POST`, `PATCH`, `PUT` and
+/// `DELETE` requests must send in the `X-CSRF-Token` header.
+#[axum::debug_handler]
+#[tracing::instrument(name = "get_csrf_token", skip(credentials, state))]
+pub async fn get_csrf_token(
+    credentials: Credentials,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    if credentials.uses_api_token() {
+        return Err(CustomAppError::from((
+            "Requests made with an API token do not need a CSRF token".to_string(),
+            ErrorContext::BadRequest,
+        )));
+    }
+
+    let (_, session_id) =
+        crate::utils::get_user_id_from_session(&credentials, &state, false).await?;
+    let csrf_token = crate::utils::get_session_csrf_token(&state.redis_store, &session_id).await?;
+
+    Ok(CustomAppJson(CsrfToken { csrf_token }).into_response())
+}
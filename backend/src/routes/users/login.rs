@@ -1,34 +1,51 @@
-use crate::models::LoginUser;
+use crate::models::{AuditEvent, AuditTarget, LoginUser, TwoFactorChallenge};
 use crate::startup::AppState;
 use crate::utils::verify_password;
-use crate::utils::SuccessResponse;
+use crate::utils::{ClientInfo, SuccessResponse};
 use crate::utils::{CustomAppError, CustomAppJson, ErrorContext};
 use axum::{extract::State, http::StatusCode, response::IntoResponse};
 use axum_extra::extract::cookie::{Cookie, PrivateCookieJar, SameSite};
 use time::Duration;
 
 #[axum::debug_handler]
-#[tracing::instrument(name = "login_user", skip(cookies, state, login), fields(email = %login.email))]
+#[tracing::instrument(name = "login_user", skip(cookies, state, client, login), fields(email = %login.email, ip_address = ?client.ip_address))]
 pub async fn login_user(
     cookies: PrivateCookieJar,
     State(state): State<AppState>,
+    client: ClientInfo,
     CustomAppJson(login): CustomAppJson<LoginUser>,
-) -> Result<(PrivateCookieJar, impl IntoResponse), CustomAppError> {
+) -> Result<(PrivateCookieJar, axum::response::Response), CustomAppError> {
+    let ip_address = client.ip_address.as_deref();
+    crate::utils::check_login_throttle(&state.redis_store, &login.email, ip_address).await?;
+
     // Get user from db by email
-    let user = state
-        .db_store
-        .get_user_by_email(&login.email)
-        .await
-        .map_err(|_| {
-            CustomAppError::from((
+    let user = match state.db_store.get_user_by_email(&login.email).await {
+        Ok(user) => user,
+        Err(_) => {
+            crate::utils::record_failed_login(&state.redis_store, &login.email, ip_address).await?;
+            crate::utils::record_audit_event(
+                &state.db_store,
+                AuditEvent::LoginFailed,
+                None,
+                None,
+                &client,
+                serde_json::json!({
+                    "email_hash": crate::utils::audit_email_hash(&login.email),
+                    "reason": "unknown_email",
+                }),
+            )
+            .await;
+            return Err(CustomAppError::from((
                 "Invalid email or password".to_string(),
                 ErrorContext::BadRequest,
-            ))
-        })?;
+            )));
+        }
+    };
 
     // Verify password
-    tokio::task::spawn_blocking(move || {
-        verify_password(&user.password, &login.password.as_bytes())
+    let password_hash = user.password.clone();
+    let password_verified = tokio::task::spawn_blocking(move || {
+        verify_password(&password_hash, login.password.as_bytes())
     })
     .await
     .map_err(|_| {
@@ -37,24 +54,101 @@ pub async fn login_user(
             ErrorContext::InternalServerError,
         ))
     })?
-    .map_err(|_| {
-        CustomAppError::from((
+    .is_ok();
+
+    if !password_verified {
+        crate::utils::record_audit_event(
+            &state.db_store,
+            AuditEvent::LoginFailed,
+            None,
+            Some(AuditTarget::User(user.id)),
+            &client,
+            serde_json::json!({ "reason": "invalid_password" }),
+        )
+        .await;
+        if crate::utils::record_failed_login(&state.redis_store, &login.email, ip_address).await? {
+            notify_lockout(&state, user, ip_address).await;
+        }
+        return Err(CustomAppError::from((
             "Invalid email or password".to_string(),
             ErrorContext::BadRequest,
-        ))
-    })?;
+        )));
+    }
 
-    // Generate a truly random session id for the user
-    let session_id = uuid::Uuid::new_v4().to_string();
+    // Only tell whoever knows the password about the suspension
+    if let Some(suspension) = state.db_store.get_user_suspension_from_db(user.id).await? {
+        crate::utils::record_audit_event(
+            &state.db_store,
+            AuditEvent::LoginFailed,
+            None,
+            Some(AuditTarget::User(user.id)),
+            &client,
+            serde_json::json!({ "reason": "suspended" }),
+        )
+        .await;
+        return Err(crate::utils::suspension_error(&suspension));
+    }
 
-    // Save session id in redis
-    let mut redis_con = state.redis_store.get().await.map_err(|_| {
-        CustomAppError::from((
-            "Failed to connect to session store".to_string(),
-            ErrorContext::InternalServerError,
-        ))
-    })?;
+    // Accounts with 2FA on get a short-lived token to trade for a session
+    if state
+        .db_store
+        .get_totp_secret_from_db(user.id)
+        .await?
+        .is_some()
+    {
+        let two_factor_token =
+            crate::utils::create_pending_two_factor_login(&state.redis_store, &user.id).await?;
+        return Ok((
+            cookies,
+            CustomAppJson(TwoFactorChallenge {
+                message: "Enter the code from your authenticator app to finish logging in."
+                    .to_string(),
+                status_code: StatusCode::OK.as_u16(),
+                two_factor_required: true,
+                two_factor_token,
+            })
+            .into_response(),
+        ));
+    }
+
+    issue_session(cookies, &state, &user, &client).await
+}
+
+/// Lets the owner of an account know it has been locked after repeated failed logins.
+pub(super) async fn notify_lockout(
+    state: &AppState,
+    user: crate::models::User,
+    ip_address: Option<&str>,
+) {
+    let lockout_minutes = crate::settings::get_settings()
+        .map(|settings| settings.login_throttle.lockout_minutes)
+        .unwrap_or_default();
 
+    if let Err(e) = crate::utils::send_templated_email(
+        "Your CryptoFlow with Rust (axum) and SvelteKit account has been locked".to_string(),
+        user.into(),
+        state,
+        "account_locked.html",
+        minijinja::context! {
+            lockout_minutes => lockout_minutes,
+            ip_address => ip_address.unwrap_or("an unknown address"),
+        },
+    )
+    .await
+    {
+        tracing::error!("Failed to send lockout email: {}", e);
+    }
+}
+
+/// Opens a session for `user` and sets it as the `sessionid` cookie. Failed
+/// logins of the account are only forgotten here, once every factor passed.
+pub(super) async fn issue_session(
+    cookies: PrivateCookieJar,
+    state: &AppState,
+    user: &crate::models::User,
+    client: &ClientInfo,
+) -> Result<(PrivateCookieJar, axum::response::Response), CustomAppError> {
+    let user_id = user.id;
     let settings = crate::settings::get_settings().map_err(|_| {
         CustomAppError::from((
             "Failed to read settings".to_string(),
@@ -63,19 +157,19 @@ pub async fn login_user(
     })?;
     let cookie_expiration = settings.secret.cookie_expiration;
 
-    bb8_redis::redis::cmd("SET")
-        .arg(session_id.clone())
-        .arg(user.id.to_string())
-        .arg("EX")
-        .arg(cookie_expiration * 60)
-        .query_async::<_, String>(&mut *redis_con)
-        .await
-        .map_err(|_| {
-            CustomAppError::from((
-                "Failed to save session".to_string(),
-                ErrorContext::InternalServerError,
-            ))
-        })?;
+    crate::utils::clear_failed_logins(&state.redis_store, &user.email).await?;
+
+    // Save session in redis
+    let session_id = crate::utils::create_session(&state.redis_store, &user_id, client).await?;
+    crate::utils::record_audit_event(
+        &state.db_store,
+        AuditEvent::Login,
+        Some(user_id),
+        Some(AuditTarget::User(user_id)),
+        client,
+        serde_json::json!({}),
+    )
+    .await;
 
     // Create cookie
     let cookie = Cookie::build(("sessionid", session_id))
@@ -1,17 +1,30 @@
+use crate::models::{AuditEvent, AuditTarget};
 use crate::startup::AppState;
-use crate::utils::CustomAppError;
-use crate::utils::SuccessResponse;
+use crate::utils::{ClientInfo, SuccessResponse};
+use crate::utils::{Credentials, CustomAppError};
 use axum::{extract::State, http::StatusCode, response::IntoResponse};
 use axum_extra::extract::cookie::{Cookie, PrivateCookieJar};
 
 #[axum::debug_handler]
-#[tracing::instrument(name = "logout_user", skip(cookies, state))]
+#[tracing::instrument(name = "logout_user", skip(cookies, credentials, state, client))]
 pub async fn logout_user(
     cookies: PrivateCookieJar,
+    credentials: Credentials,
     State(state): State<AppState>,
+    client: ClientInfo,
 ) -> Result<(PrivateCookieJar, impl IntoResponse), CustomAppError> {
     // Get user_id and session_id from cookie and delete it
-    let (_, _) = crate::utils::get_user_id_from_session(&cookies, &state.redis_store, true).await?;
+    let (user_id, _) = crate::utils::get_user_id_from_session(&credentials, &state, true).await?;
+
+    crate::utils::record_audit_event(
+        &state.db_store,
+        AuditEvent::Logout,
+        Some(user_id),
+        Some(AuditTarget::User(user_id)),
+        &client,
+        serde_json::json!({}),
+    )
+    .await;
 
     Ok((
         cookies.remove(Cookie::from("sessionid")),
@@ -1,24 +1,81 @@
 use crate::utils::validate_authentication_session;
 use axum::{
-    routing::{get, post},
+    routing::{delete, get, patch, post},
     Router,
 };
 
+mod account;
 mod activate_account;
+mod api_tokens;
+mod change_email;
+mod change_password;
+mod csrf;
 mod current_user;
 mod login;
 mod logout;
+mod password_reset;
+mod profile;
+mod public_profile;
 mod register;
+mod resend_activation;
+mod sessions;
+mod two_factor;
 
 pub fn users_routes(state: crate::startup::AppState) -> Router<crate::startup::AppState> {
     Router::new()
-        .route("/logout", post(logout::logout_user))
         .route("/current", get(current_user::get_current_user))
+        .route("/csrf-token", get(csrf::get_csrf_token))
+        .route("/profile", patch(profile::update_profile))
+        .route("/account/export", get(account::export_account_data))
+        .route("/account/delete", post(account::delete_account))
+        .route("/password/change", post(change_password::change_password))
+        .route("/email/change", post(change_email::request_email_change))
+        .route("/email/confirm", post(change_email::confirm_email_change))
+        .route(
+            "/sessions",
+            get(sessions::list_sessions).delete(sessions::logout_everywhere),
+        )
+        .route("/sessions/:session_id", delete(sessions::revoke_session))
+        .route("/:user_id/sessions", delete(sessions::revoke_user_sessions))
+        .route(
+            "/tokens",
+            get(api_tokens::list_api_tokens).post(api_tokens::create_api_token),
+        )
+        .route("/tokens/:token_id", delete(api_tokens::revoke_api_token))
+        .route("/2fa", get(two_factor::get_two_factor_status))
+        .route("/2fa/enroll", post(two_factor::enroll_two_factor))
+        .route("/2fa/confirm", post(two_factor::confirm_two_factor))
+        .route(
+            "/2fa/recovery-codes",
+            post(two_factor::regenerate_recovery_codes),
+        )
+        .route("/2fa/disable", post(two_factor::disable_two_factor))
         .route_layer(axum::middleware::from_fn_with_state(
             state.clone(),
             validate_authentication_session,
         ))
+        // Authenticates on its own so that suspended users can still log out
+        .route("/logout", post(logout::logout_user))
         .route("/login", post(login::login_user))
+        .route("/login/2fa", post(two_factor::verify_two_factor_login))
         .route("/register", post(register::register_user))
         .route("/activate", post(activate_account::activate_user_account))
+        .route(
+            "/activate/resend",
+            post(resend_activation::resend_activation_code),
+        )
+        .route(
+            "/password/request-reset",
+            post(password_reset::request_password_reset),
+        )
+        .route(
+            "/password/confirm-reset",
+            post(password_reset::confirm_password_reset),
+        )
+        .route("/:user_id", get(public_profile::get_public_profile))
+        .route(
+            "/:user_id/questions",
+            get(public_profile::get_user_questions),
+        )
+        .route("/:user_id/answers", get(public_profile::get_user_answers))
 }
@@ -0,0 +1,170 @@
+use crate::{
+    models::{AuditEvent, AuditTarget, ConfirmPasswordReset, RequestPasswordReset},
+    startup::AppState,
+    utils::{ClientInfo, CustomAppError, CustomAppJson, ErrorContext, SuccessResponse},
+};
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use axum::{extract::State, http::StatusCode, response::IntoResponse};
+use sha2::{Digest, Sha256};
+
+fn password_reset_key(user_id: &uuid::Uuid) -> String {
+    format!("password_reset:{}", user_id)
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "request_password_reset", skip(state, request), fields(user_email = request.email))]
+pub async fn request_password_reset(
+    State(state): State<AppState>,
+    CustomAppJson(request): CustomAppJson<RequestPasswordReset>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    // Whether or not the email is registered, the response is the same
+    let response = SuccessResponse {
+        message:
+            "If an account with that email exists, we have sent it a link to reset its password."
+                .to_string(),
+        status_code: StatusCode::OK.as_u16(),
+        ..Default::default()
+    };
+
+    let user = match state.db_store.get_user_by_email(&request.email).await {
+        Ok(user) => crate::models::UserVisible::from(user),
+        Err(sqlx::Error::RowNotFound) => return Ok(response.into_response()),
+        Err(e) => return Err(e.into()),
+    };
+
+    // Reset tokens travel in a link rather than being typed in, so make them long
+    let mut token_bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut token_bytes);
+    let reset_token: String = token_bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    let mut hasher = Sha256::new();
+    hasher.update(reset_token.as_bytes());
+    let hashed_reset_token = format!("{:x}", hasher.finalize());
+
+    let mut redis_con = state.redis_store.get().await.map_err(|_| {
+        CustomAppError::from((
+            "Failed to get redis connection".to_string(),
+            ErrorContext::InternalServerError,
+        ))
+    })?;
+
+    let settings = crate::settings::get_settings().map_err(|_| {
+        CustomAppError::from((
+            "Failed to read settings".to_string(),
+            ErrorContext::InternalServerError,
+        ))
+    })?;
+
+    // Overwrites, and so invalidates, any token issued earlier
+    bb8_redis::redis::cmd("SET")
+        .arg(password_reset_key(&user.id))
+        .arg(hashed_reset_token)
+        .arg("EX")
+        .arg(settings.secret.token_expiration * 60)
+        .query_async::<_, String>(&mut *redis_con)
+        .await
+        .map_err(|_| {
+            CustomAppError::from((
+                "Failed to save password reset token".to_string(),
+                ErrorContext::InternalServerError,
+            ))
+        })?;
+
+    crate::utils::send_multipart_email(
+        "Reset your CryptoFlow with Rust (axum) and SvelteKit password".to_string(),
+        user,
+        state.clone(),
+        "password_reset.html",
+        reset_token,
+    )
+    .await
+    .map_err(|_| {
+        CustomAppError::from((
+            "Failed to send password reset email".to_string(),
+            ErrorContext::InternalServerError,
+        ))
+    })?;
+
+    Ok(response.into_response())
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "confirm_password_reset", skip(state, client, reset), fields(user_id = %reset.id))]
+pub async fn confirm_password_reset(
+    State(state): State<AppState>,
+    client: ClientInfo,
+    CustomAppJson(reset): CustomAppJson<ConfirmPasswordReset>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    if reset.password.is_empty() {
+        return Err(CustomAppError::from((
+            "Password cannot be empty".to_string(),
+            ErrorContext::BadRequest,
+        )));
+    }
+
+    let mut redis_con = state.redis_store.get().await.map_err(|_| {
+        CustomAppError::from((
+            "Failed to get redis connection".to_string(),
+            ErrorContext::InternalServerError,
+        ))
+    })?;
+
+    let mut hasher = Sha256::new();
+    hasher.update(reset.token.as_bytes());
+    let hashed_token = format!("{:x}", hasher.finalize());
+
+    let hashed_reset_token: String = bb8_redis::redis::cmd("GET")
+        .arg(password_reset_key(&reset.id))
+        .query_async(&mut *redis_con)
+        .await
+        .map_err(|_| {
+            CustomAppError::from((
+                "This password reset link has been used or expired".to_string(),
+                ErrorContext::BadRequest,
+            ))
+        })?;
+
+    if hashed_reset_token != hashed_token {
+        return Err(CustomAppError::from((
+            "Password reset token not found or expired".to_string(),
+            ErrorContext::BadRequest,
+        )));
+    }
+
+    // Burn the token before anything else so it cannot be replayed
+    bb8_redis::redis::cmd("DEL")
+        .arg(password_reset_key(&reset.id))
+        .query_async::<_, i64>(&mut *redis_con)
+        .await
+        .map_err(|_| {
+            CustomAppError::from((
+                "Failed to delete password reset token from Redis".to_string(),
+                ErrorContext::InternalServerError,
+            ))
+        })?;
+
+    let hashed_password = crate::utils::hash_password(reset.password.as_bytes()).await;
+    state
+        .db_store
+        .update_user_password(&reset.id, &hashed_password)
+        .await?;
+
+    // Whoever knew the old password must not stay signed in
+    crate::utils::revoke_all_user_sessions(&state.redis_store, &reset.id, None).await?;
+    crate::utils::record_audit_event(
+        &state.db_store,
+        AuditEvent::PasswordReset,
+        Some(reset.id),
+        Some(AuditTarget::User(reset.id)),
+        &client,
+        serde_json::json!({}),
+    )
+    .await;
+
+    Ok(SuccessResponse {
+        message: "Your password has been reset. You can now login with your new password."
+            .to_string(),
+        status_code: StatusCode::OK.as_u16(),
+        ..Default::default()
+    }
+    .into_response())
+}
@@ -0,0 +1,53 @@
+use crate::{
+    models::UpdateProfile,
+    startup::AppState,
+    utils::{Credentials, CustomAppError, CustomAppJson, ErrorContext},
+};
+use axum::{extract::State, response::IntoResponse};
+
+fn validate_profile(profile: UpdateProfile) -> Result<UpdateProfile, CustomAppError> {
+    let trim_name = |name: Option<String>, field: &str| match name.map(|n| n.trim().to_string()) {
+        Some(name) if name.is_empty() => Err(CustomAppError::from((
+            format!("{} cannot be empty", field),
+            ErrorContext::BadRequest,
+        ))),
+        name => Ok(name),
+    };
+
+    let thumbnail = profile.thumbnail.map(|t| t.trim().to_string());
+    if let Some(thumbnail) = thumbnail.as_deref().filter(|t| !t.is_empty()) {
+        match reqwest::Url::parse(thumbnail) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => {}
+            _ => {
+                return Err(CustomAppError::from((
+                    "Thumbnail must be an http(s) URL".to_string(),
+                    ErrorContext::BadRequest,
+                )))
+            }
+        }
+    }
+
+    Ok(UpdateProfile {
+        first_name: trim_name(profile.first_name, "First name")?,
+        last_name: trim_name(profile.last_name, "Last name")?,
+        thumbnail,
+    })
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "update_profile", skip(credentials, state))]
+pub async fn update_profile(
+    credentials: Credentials,
+    State(state): State<AppState>,
+    CustomAppJson(profile): CustomAppJson<UpdateProfile>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    let (user_id, _) = crate::utils::get_user_id_from_session(&credentials, &state, false).await?;
+
+    let profile = validate_profile(profile)?;
+    let user = state
+        .db_store
+        .update_user_profile(&user_id, &profile)
+        .await?;
+
+    Ok(CustomAppJson(user).into_response())
+}
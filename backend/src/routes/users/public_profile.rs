@@ -0,0 +1,78 @@
+use crate::{
+    models::{PublicProfile, PublicUser, QuestionListQuery, UserPostsQuery},
+    startup::AppState,
+    utils::{CustomAppError, CustomAppJson, Paginated, Pagination},
+};
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+};
+
+/// How many tags a profile shows.
+const TOP_TAGS_LIMIT: i64 = 5;
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "get_public_profile", skip(state))]
+pub async fn get_public_profile(
+    Path(user_id): Path<uuid::Uuid>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    // Deactivated and deleted accounts have no profile
+    let user: PublicUser = state.db_store.get_user_by_id(user_id).await?.into();
+    let activity = state.db_store.get_user_activity_from_db(user_id).await?;
+    let top_tags = state
+        .db_store
+        .get_top_tags_from_db(user_id, TOP_TAGS_LIMIT)
+        .await?;
+
+    Ok(CustomAppJson(PublicProfile {
+        user,
+        questions: activity.questions,
+        answers: activity.answers,
+        accepted_answers: activity.accepted_answers,
+        top_tags,
+    })
+    .into_response())
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "get_user_questions", skip(state))]
+pub async fn get_user_questions(
+    Path(user_id): Path<uuid::Uuid>,
+    Query(list_query): Query<QuestionListQuery>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    state.db_store.get_user_by_id(user_id).await?;
+
+    let list_query = QuestionListQuery {
+        author: Some(user_id),
+        ..list_query
+    };
+    let pagination = Pagination::new(list_query.page, list_query.page_size);
+
+    let (questions, total) = state
+        .db_store
+        .get_questions_from_db(&list_query, pagination)
+        .await?;
+
+    Ok(axum::Json(Paginated::new(questions, total, pagination)).into_response())
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "get_user_answers", skip(state))]
+pub async fn get_user_answers(
+    Path(user_id): Path<uuid::Uuid>,
+    Query(list_query): Query<UserPostsQuery>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    state.db_store.get_user_by_id(user_id).await?;
+
+    let pagination = Pagination::new(list_query.page, list_query.page_size);
+
+    let (answers, total) = state
+        .db_store
+        .get_answers_by_author_from_db(user_id, pagination)
+        .await?;
+
+    Ok(axum::Json(Paginated::new(answers, total, pagination)).into_response())
+}
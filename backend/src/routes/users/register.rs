@@ -12,7 +12,7 @@ pub async fn register_user(
     State(state): State<AppState>,
     CustomAppJson(new_user): CustomAppJson<NewUser>,
 ) -> Result<impl IntoResponse, CustomAppError> {
-    let hashed_password = crate::utils::hash_password(&new_user.password.as_bytes()).await;
+    let hashed_password = crate::utils::hash_password(new_user.password.as_bytes()).await;
 
     let user = state
         .db_store
@@ -24,6 +24,21 @@ pub async fn register_user(
         )
         .await?;
 
+    issue_activation_code(&state, user.clone()).await?;
+
+    Ok(SuccessResponse {
+        message: "Registration complete! Check your email for a verification code to activate your account.".to_string(),
+        status_code: StatusCode::CREATED.as_u16(),
+        user_id: Some(user.id),
+    }.into_response())
+}
+
+/// Generates a fresh activation code for `user`, replacing any earlier one,
+/// and emails it to them.
+pub(super) async fn issue_activation_code(
+    state: &AppState,
+    user: crate::models::UserVisible,
+) -> Result<(), CustomAppError> {
     // Generate a truly random activation code for the user using argon2::password_hash::rand_core::OsRng
     let activation_code = (OsRng.next_u32() % 900000 + 100000).to_string();
     // Hash the activation code
@@ -64,7 +79,7 @@ pub async fn register_user(
     // Send activation code to user's email
     crate::utils::send_multipart_email(
         "Welcome to CryptoFlow with Rust (axum) and SvelteKit".to_string(),
-        user.clone(),
+        user,
         state.clone(),
         "user_welcome.html",
         activation_code,
@@ -77,9 +92,5 @@ pub async fn register_user(
         ))
     })?;
 
-    Ok(SuccessResponse {
-        message: "Registration complete! Check your email for a verification code to activate your account.".to_string(),
-        status_code: StatusCode::CREATED.as_u16(),
-        user_id: Some(user.id),
-    }.into_response())
+    Ok(())
 }
@@ -0,0 +1,95 @@
+use crate::{
+    models::ResendActivation,
+    startup::AppState,
+    utils::{CustomAppError, CustomAppJson, ErrorContext, SuccessResponse},
+};
+use axum::{extract::State, http::StatusCode, response::IntoResponse};
+
+/// Minimum wait between two codes sent to the same address.
+const RESEND_COOLDOWN_SECONDS: u64 = 60;
+
+/// Codes that can be sent to the same address per day.
+const MAX_RESENDS_PER_DAY: i64 = 5;
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "resend_activation_code", skip(state, request), fields(user_email = request.email))]
+pub async fn resend_activation_code(
+    State(state): State<AppState>,
+    CustomAppJson(request): CustomAppJson<ResendActivation>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    // Limits apply to the address asked for, registered or not, so they
+    // cannot be used to tell which ones are
+    let address = request.email.trim().to_lowercase();
+    let mut redis_con = state.redis_store.get().await.map_err(|_| {
+        CustomAppError::from((
+            "Failed to get redis connection".to_string(),
+            ErrorContext::InternalServerError,
+        ))
+    })?;
+
+    let cooldown_started: Option<String> = bb8_redis::redis::cmd("SET")
+        .arg(format!("activation_resend_cooldown:{}", address))
+        .arg(1)
+        .arg("EX")
+        .arg(RESEND_COOLDOWN_SECONDS)
+        .arg("NX")
+        .query_async(&mut *redis_con)
+        .await
+        .map_err(|_| {
+            CustomAppError::from((
+                "Failed to check activation code requests".to_string(),
+                ErrorContext::InternalServerError,
+            ))
+        })?;
+    if cooldown_started.is_none() {
+        return Err(CustomAppError::TooManyRequests {
+            message: format!(
+                "Please wait {} seconds before asking for another code.",
+                RESEND_COOLDOWN_SECONDS
+            ),
+            retry_after: RESEND_COOLDOWN_SECONDS,
+        });
+    }
+
+    // Only requests past the cooldown count towards the daily quota
+    let (resends_today, quota_ttl): (i64, i64) = bb8_redis::redis::pipe()
+        .cmd("SET")
+        .arg(format!("activation_resends:{}", address))
+        .arg(0)
+        .arg("EX")
+        .arg(24 * 60 * 60)
+        .arg("NX")
+        .ignore()
+        .cmd("INCR")
+        .arg(format!("activation_resends:{}", address))
+        .cmd("TTL")
+        .arg(format!("activation_resends:{}", address))
+        .query_async(&mut *redis_con)
+        .await
+        .map_err(|_| {
+            CustomAppError::from((
+                "Failed to check activation code requests".to_string(),
+                ErrorContext::InternalServerError,
+            ))
+        })?;
+    if resends_today > MAX_RESENDS_PER_DAY {
+        return Err(CustomAppError::TooManyRequests {
+            message: "Too many activation codes requested for this address today.".to_string(),
+            // The quota starts over when its key expires
+            retry_after: quota_ttl.max(1) as u64,
+        });
+    }
+
+    match state.db_store.get_inactive_user_by_email(&address).await {
+        Ok(user) => super::register::issue_activation_code(&state, user).await?,
+        Err(sqlx::Error::RowNotFound) => {}
+        Err(e) => return Err(e.into()),
+    }
+
+    Ok(SuccessResponse {
+        message: "If an inactive account with that email exists, a new activation code has been sent to it.".to_string(),
+        status_code: StatusCode::OK.as_u16(),
+        ..Default::default()
+    }
+    .into_response())
+}
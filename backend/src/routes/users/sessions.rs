@@ -0,0 +1,114 @@
+use crate::{
+    models::{AuditEvent, AuditTarget},
+    startup::AppState,
+    utils::{
+        AuthenticatedUser, ClientInfo, Credentials, CustomAppError, CustomAppJson, ErrorContext,
+        SuccessResponse,
+    },
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use axum_extra::extract::cookie::{Cookie, PrivateCookieJar};
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "list_sessions", skip(credentials, state))]
+pub async fn list_sessions(
+    credentials: Credentials,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    let (user_id, session_id) =
+        crate::utils::get_user_id_from_session(&credentials, &state, false).await?;
+
+    let sessions =
+        crate::utils::list_user_sessions(&state.redis_store, &user_id, Some(&session_id)).await?;
+
+    Ok(CustomAppJson(sessions).into_response())
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "revoke_session", skip(credentials, state))]
+pub async fn revoke_session(
+    Path(handle): Path<String>,
+    credentials: Credentials,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    let (user_id, _) = crate::utils::get_user_id_from_session(&credentials, &state, false).await?;
+
+    if !crate::utils::revoke_user_session(&state.redis_store, &user_id, &handle).await? {
+        return Err(CustomAppError::from((
+            "Session not found or already expired".to_string(),
+            ErrorContext::NotFound,
+        )));
+    }
+
+    Ok(SuccessResponse {
+        message: "The session has been revoked.".to_string(),
+        status_code: StatusCode::OK.as_u16(),
+        ..Default::default()
+    }
+    .into_response())
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "logout_everywhere", skip(cookies, credentials, state, client))]
+pub async fn logout_everywhere(
+    cookies: PrivateCookieJar,
+    credentials: Credentials,
+    State(state): State<AppState>,
+    client: ClientInfo,
+) -> Result<(PrivateCookieJar, impl IntoResponse), CustomAppError> {
+    let (user_id, _) = crate::utils::get_user_id_from_session(&credentials, &state, false).await?;
+
+    crate::utils::revoke_all_user_sessions(&state.redis_store, &user_id, None).await?;
+    crate::utils::record_audit_event(
+        &state.db_store,
+        AuditEvent::Logout,
+        Some(user_id),
+        Some(AuditTarget::User(user_id)),
+        &client,
+        serde_json::json!({ "all_sessions": true }),
+    )
+    .await;
+
+    Ok((
+        cookies.remove(Cookie::from("sessionid")),
+        SuccessResponse {
+            message: "You have been logged out of every device.".to_string(),
+            status_code: StatusCode::OK.as_u16(),
+            ..Default::default()
+        }
+        .into_response(),
+    ))
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "revoke_user_sessions", skip(admin, state, client))]
+pub async fn revoke_user_sessions(
+    Path(user_id): Path<uuid::Uuid>,
+    admin: AuthenticatedUser,
+    State(state): State<AppState>,
+    client: ClientInfo,
+) -> Result<impl IntoResponse, CustomAppError> {
+    crate::routes::admin::manageable_user(&state, &admin, user_id).await?;
+
+    crate::utils::revoke_all_user_sessions(&state.redis_store, &user_id, None).await?;
+    crate::routes::admin::audit_admin_action(
+        &state,
+        &admin,
+        AuditEvent::SessionsRevoked,
+        user_id,
+        &client,
+        serde_json::json!({ "all_sessions": true }),
+    )
+    .await;
+
+    Ok(SuccessResponse {
+        message: "All sessions of the user have been revoked.".to_string(),
+        status_code: StatusCode::OK.as_u16(),
+        user_id: Some(user_id),
+    }
+    .into_response())
+}
@@ -0,0 +1,345 @@
+use crate::{
+    models::{
+        RecoveryCodes, TwoFactorCode, TwoFactorEnrollment, TwoFactorLogin,
+        TwoFactorReauthentication, TwoFactorStatus,
+    },
+    startup::AppState,
+    utils::{
+        verify_password, ClientInfo, Credentials, CustomAppError, CustomAppJson, ErrorContext,
+        SuccessResponse,
+    },
+};
+use axum::{extract::State, http::StatusCode, response::IntoResponse};
+use axum_extra::extract::PrivateCookieJar;
+
+fn totp_enrollment_key(user_id: &uuid::Uuid) -> String {
+    format!("totp_enrollment:{}", user_id)
+}
+
+fn invalid_code() -> CustomAppError {
+    CustomAppError::from((
+        "Invalid two-factor code".to_string(),
+        ErrorContext::BadRequest,
+    ))
+}
+
+/// Checks the code, and the current password where one is asked for, of a
+/// signed-in user changing their 2FA settings. Wrong ones are counted so that
+/// neither can be guessed.
+async fn verify_settings_change(
+    state: &AppState,
+    user: &crate::models::User,
+    secret: &str,
+    password: Option<String>,
+    code: &str,
+) -> Result<(), CustomAppError> {
+    crate::utils::check_two_factor_attempts(&state.redis_store, &user.id).await?;
+
+    if let Some(password) = password {
+        let password_hash = user.password.clone();
+        let password_verified = tokio::task::spawn_blocking(move || {
+            verify_password(&password_hash, password.as_bytes())
+        })
+        .await
+        .map_err(|_| {
+            CustomAppError::from((
+                "Server error occurred".to_string(),
+                ErrorContext::InternalServerError,
+            ))
+        })?
+        .is_ok();
+        if !password_verified {
+            crate::utils::record_two_factor_failure(&state.redis_store, &user.id).await?;
+            return Err(CustomAppError::from((
+                "Current password is incorrect".to_string(),
+                ErrorContext::BadRequest,
+            )));
+        }
+    }
+
+    if !crate::utils::verify_second_factor(state, user.id, &user.email, secret, code).await? {
+        crate::utils::record_two_factor_failure(&state.redis_store, &user.id).await?;
+        return Err(invalid_code());
+    }
+
+    Ok(())
+}
+
+/// Issues a new set of recovery codes and turns 2FA on with `secret`.
+async fn issue_recovery_codes(
+    state: &AppState,
+    user_id: uuid::Uuid,
+    secret: &str,
+) -> Result<RecoveryCodes, CustomAppError> {
+    let recovery_codes = crate::utils::generate_recovery_codes();
+    let hashes: Vec<String> = recovery_codes
+        .iter()
+        .map(|code| crate::utils::hash_recovery_code(code))
+        .collect();
+    state
+        .db_store
+        .enable_two_factor_in_db(user_id, secret, &hashes)
+        .await?;
+
+    Ok(RecoveryCodes { recovery_codes })
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "get_two_factor_status", skip(credentials, state))]
+pub async fn get_two_factor_status(
+    credentials: Credentials,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    let (user_id, _) = crate::utils::get_user_id_from_session(&credentials, &state, false).await?;
+
+    let enabled = state
+        .db_store
+        .get_totp_secret_from_db(user_id)
+        .await?
+        .is_some();
+    let recovery_codes_remaining = state
+        .db_store
+        .count_unused_recovery_codes_in_db(user_id)
+        .await?;
+
+    Ok(CustomAppJson(TwoFactorStatus {
+        enabled,
+        recovery_codes_remaining,
+    })
+    .into_response())
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "enroll_two_factor", skip(credentials, state))]
+pub async fn enroll_two_factor(
+    credentials: Credentials,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    let (user_id, _) = crate::utils::get_user_id_from_session(&credentials, &state, false).await?;
+
+    if state
+        .db_store
+        .get_totp_secret_from_db(user_id)
+        .await?
+        .is_some()
+    {
+        return Err(CustomAppError::from((
+            "Two-factor authentication is already enabled".to_string(),
+            ErrorContext::BadRequest,
+        )));
+    }
+
+    let user = state.db_store.get_user_by_id(user_id).await?;
+    let secret = crate::utils::generate_totp_secret();
+    let otpauth_uri = crate::utils::build_totp(&secret, &user.email)?.get_url();
+
+    let mut redis_con = state.redis_store.get().await.map_err(|_| {
+        CustomAppError::from((
+            "Failed to get redis connection".to_string(),
+            ErrorContext::InternalServerError,
+        ))
+    })?;
+
+    let settings = crate::settings::get_settings().map_err(|_| {
+        CustomAppError::from((
+            "Failed to read settings".to_string(),
+            ErrorContext::InternalServerError,
+        ))
+    })?;
+
+    // The secret only reaches the database once a first code proves the app has it
+    bb8_redis::redis::cmd("SET")
+        .arg(totp_enrollment_key(&user_id))
+        .arg(&secret)
+        .arg("EX")
+        .arg(settings.secret.token_expiration * 60)
+        .query_async::<_, String>(&mut *redis_con)
+        .await
+        .map_err(|_| {
+            CustomAppError::from((
+                "Failed to save two-factor enrollment".to_string(),
+                ErrorContext::InternalServerError,
+            ))
+        })?;
+
+    Ok(CustomAppJson(TwoFactorEnrollment {
+        secret,
+        otpauth_uri,
+    })
+    .into_response())
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "confirm_two_factor", skip(credentials, state, confirmation))]
+pub async fn confirm_two_factor(
+    credentials: Credentials,
+    State(state): State<AppState>,
+    CustomAppJson(confirmation): CustomAppJson<TwoFactorCode>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    let (user_id, _) = crate::utils::get_user_id_from_session(&credentials, &state, false).await?;
+
+    let mut redis_con = state.redis_store.get().await.map_err(|_| {
+        CustomAppError::from((
+            "Failed to get redis connection".to_string(),
+            ErrorContext::InternalServerError,
+        ))
+    })?;
+
+    let secret: String = bb8_redis::redis::cmd("GET")
+        .arg(totp_enrollment_key(&user_id))
+        .query_async(&mut *redis_con)
+        .await
+        .map_err(|_| {
+            CustomAppError::from((
+                "This two-factor enrollment has been confirmed or expired".to_string(),
+                ErrorContext::BadRequest,
+            ))
+        })?;
+
+    let user = state.db_store.get_user_by_id(user_id).await?;
+    verify_settings_change(&state, &user, &secret, None, &confirmation.code).await?;
+
+    let recovery_codes = issue_recovery_codes(&state, user_id, &secret).await?;
+
+    bb8_redis::redis::cmd("DEL")
+        .arg(totp_enrollment_key(&user_id))
+        .query_async::<_, i64>(&mut *redis_con)
+        .await
+        .map_err(|_| {
+            CustomAppError::from((
+                "Failed to delete two-factor enrollment from Redis".to_string(),
+                ErrorContext::InternalServerError,
+            ))
+        })?;
+
+    Ok(CustomAppJson(recovery_codes).into_response())
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(
+    name = "regenerate_recovery_codes",
+    skip(credentials, state, confirmation)
+)]
+pub async fn regenerate_recovery_codes(
+    credentials: Credentials,
+    State(state): State<AppState>,
+    CustomAppJson(confirmation): CustomAppJson<TwoFactorReauthentication>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    let (user_id, _) = crate::utils::get_user_id_from_session(&credentials, &state, false).await?;
+
+    let secret = state
+        .db_store
+        .get_totp_secret_from_db(user_id)
+        .await?
+        .ok_or_else(|| {
+            CustomAppError::from((
+                "Two-factor authentication is not enabled".to_string(),
+                ErrorContext::BadRequest,
+            ))
+        })?;
+
+    let user = state.db_store.get_user_by_id(user_id).await?;
+    verify_settings_change(
+        &state,
+        &user,
+        &secret,
+        Some(confirmation.password),
+        &confirmation.code,
+    )
+    .await?;
+
+    let recovery_codes = issue_recovery_codes(&state, user_id, &secret).await?;
+
+    Ok(CustomAppJson(recovery_codes).into_response())
+}
+
+#[axum::debug_handler]
+#[tracing::instrument(name = "disable_two_factor", skip(credentials, state, disable))]
+pub async fn disable_two_factor(
+    credentials: Credentials,
+    State(state): State<AppState>,
+    CustomAppJson(disable): CustomAppJson<TwoFactorReauthentication>,
+) -> Result<impl IntoResponse, CustomAppError> {
+    let (user_id, _) = crate::utils::get_user_id_from_session(&credentials, &state, false).await?;
+
+    let secret = state
+        .db_store
+        .get_totp_secret_from_db(user_id)
+        .await?
+        .ok_or_else(|| {
+            CustomAppError::from((
+                "Two-factor authentication is not enabled".to_string(),
+                ErrorContext::BadRequest,
+            ))
+        })?;
+
+    let user = state.db_store.get_user_by_id(user_id).await?;
+    verify_settings_change(
+        &state,
+        &user,
+        &secret,
+        Some(disable.password),
+        &disable.code,
+    )
+    .await?;
+
+    state.db_store.disable_two_factor_in_db(user_id).await?;
+
+    Ok(SuccessResponse {
+        message: "Two-factor authentication has been disabled.".to_string(),
+        status_code: StatusCode::OK.as_u16(),
+        ..Default::default()
+    }
+    .into_response())
+}
+
+/// Second login step for accounts with 2FA on.
+#[axum::debug_handler]
+#[tracing::instrument(name = "verify_two_factor_login", skip(cookies, state, client, login))]
+pub async fn verify_two_factor_login(
+    cookies: PrivateCookieJar,
+    State(state): State<AppState>,
+    client: ClientInfo,
+    CustomAppJson(login): CustomAppJson<TwoFactorLogin>,
+) -> Result<(PrivateCookieJar, axum::response::Response), CustomAppError> {
+    let user_id =
+        crate::utils::get_pending_two_factor_login(&state.redis_store, &login.token).await?;
+
+    let user = state.db_store.get_user_by_id(user_id).await?;
+    let ip_address = client.ip_address.as_deref();
+    crate::utils::check_login_throttle(&state.redis_store, &user.email, ip_address).await?;
+    let secret = state
+        .db_store
+        .get_totp_secret_from_db(user_id)
+        .await?
+        .ok_or_else(|| {
+            CustomAppError::from((
+                "This login has expired. Please log in again".to_string(),
+                ErrorContext::UnauthorizedAccess,
+            ))
+        })?;
+
+    if !crate::utils::verify_second_factor(&state, user_id, &user.email, &secret, &login.code)
+        .await?
+    {
+        crate::utils::record_audit_event(
+            &state.db_store,
+            crate::models::AuditEvent::LoginFailed,
+            None,
+            Some(crate::models::AuditTarget::User(user_id)),
+            &client,
+            serde_json::json!({ "reason": "invalid_second_factor" }),
+        )
+        .await;
+        // Wrong codes count like wrong passwords, so that fresh login tokens
+        // do not buy fresh guesses
+        if crate::utils::record_failed_login(&state.redis_store, &user.email, ip_address).await? {
+            super::login::notify_lockout(&state, user, ip_address).await;
+        }
+        return Err(invalid_code());
+    }
+
+    crate::utils::finish_pending_two_factor_login(&state.redis_store, &login.token).await?;
+
+    super::login::issue_session(cookies, &state, &user, &client).await
+}
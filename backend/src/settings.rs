@@ -7,6 +7,10 @@ pub struct ApplicationSettings {
     pub host: String,
     pub base_url: String,
     pub protocol: String,
+    /// Whether the right-most `X-Forwarded-For` entry can be trusted to be
+    /// the client's IP, i.e. the backend is only reachable through a proxy
+    /// that appends it.
+    pub trust_proxy_headers: bool,
 }
 
 #[derive(serde::Deserialize, Clone)]
@@ -28,6 +32,89 @@ pub struct CoinGeckoSettings {
     pub api_key: String,
 }
 
+/// The backends a `MarketDataProvider` can be built on.
+#[derive(serde::Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum MarketDataBackend {
+    #[serde(rename = "coingecko")]
+    CoinGecko,
+    /// Recorded responses on disk, for CI and offline development.
+    #[serde(rename = "fixture")]
+    Fixture,
+}
+
+/// Lifetimes of cached market data, per endpoint.
+#[derive(serde::Deserialize, Clone, Debug)]
+pub struct MarketDataCacheSettings {
+    pub prices_ttl_seconds: u64,
+    pub chart_ttl_seconds: u64,
+    pub currencies_ttl_seconds: u64,
+    /// How long past its TTL an entry is still served while it is refreshed.
+    pub stale_seconds: u64,
+    /// How long a coin or currency the provider returned no price for is
+    /// remembered as unknown.
+    pub unknown_ttl_seconds: u64,
+}
+
+#[derive(serde::Deserialize, Clone)]
+pub struct MarketDataSettings {
+    pub provider: MarketDataBackend,
+    /// Read by the `fixture` provider, relative to the working directory.
+    pub fixtures_dir: String,
+    pub cache: MarketDataCacheSettings,
+    /// Most coins a single chart or price request may ask for.
+    pub max_tags_per_request: usize,
+    /// Most provider requests a single chart request runs at once.
+    pub max_concurrent_requests: usize,
+}
+
+/// What the market history job records, and how much of it a range query
+/// may return.
+#[derive(serde::Deserialize, Clone, Debug)]
+pub struct MarketHistorySettings {
+    /// Currencies prices are recorded in.
+    pub currencies: Vec<String>,
+    /// How many coins are recorded, by market cap rank.
+    pub tracked_coins: i64,
+    pub snapshot_interval_minutes: u64,
+    /// How old a coin's candles may get before its chart is fetched again.
+    pub candle_refresh_hours: i64,
+    /// How far back the chart of a coin without candles is fetched.
+    pub backfill_days: i32,
+    /// Most points or candles a range query returns; longer ranges are
+    /// downsampled.
+    pub max_points: i64,
+}
+
+/// Limits on failed logins, counted per email address and per client IP.
+#[derive(serde::Deserialize, Clone)]
+pub struct LoginThrottleSettings {
+    /// Failures within `failure_window_minutes` that lock an email address.
+    pub max_failures_per_email: i64,
+    /// Failures within `failure_window_minutes` that lock a client IP.
+    pub max_failures_per_ip: i64,
+    pub failure_window_minutes: i64,
+    /// Wait after the first failure, doubled with each further one.
+    pub base_backoff_seconds: i64,
+    pub max_backoff_seconds: i64,
+    pub lockout_minutes: i64,
+}
+
+/// What becomes of the questions, answers and comments of a deleted account.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthoredContentPolicy {
+    /// Hand them over to the "Deleted User" placeholder.
+    Anonymize,
+    /// Remove them, except posts somebody else has answered, commented on or
+    /// replied to, which are anonymized so that the replies survive.
+    Delete,
+}
+
+#[derive(serde::Deserialize, Clone)]
+pub struct AccountDeletionSettings {
+    pub authored_content: AuthoredContentPolicy,
+}
+
 #[derive(serde::Deserialize, Clone)]
 pub struct SuperUser {
     pub email: String,
@@ -47,6 +134,10 @@ pub struct Settings {
     pub superuser: SuperUser,
     pub secret: Secret,
     pub coingecko: CoinGeckoSettings,
+    pub market_data: MarketDataSettings,
+    pub market_history: MarketHistorySettings,
+    pub login_throttle: LoginThrottleSettings,
+    pub account_deletion: AccountDeletionSettings,
 }
 
 /// The possible runtime environment for our application.
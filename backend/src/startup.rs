@@ -12,6 +12,11 @@ pub struct AppState {
     pub db_store: crate::store::Store,
     pub redis_store: bb8_redis::bb8::Pool<bb8_redis::RedisConnectionManager>,
     pub env: minijinja::Environment<'static>,
+    pub market_data: crate::market_data::MarketDataCache,
+    /// Limits of market data requests, read once at startup.
+    pub market_data_settings: crate::settings::MarketDataSettings,
+    /// Read once from `application.trust_proxy_headers`.
+    pub trust_proxy_headers: bool,
     key: axum_extra::extract::cookie::Key,
 }
 
@@ -44,11 +49,18 @@ impl Application {
         // Create superuser if not exists
         store.create_super_user_in_db(&settings).await;
         let store_for_update = store.clone();
+        let market_data = crate::market_data::build_provider(&settings);
+        let market_data_for_update = market_data.clone();
+        let store_for_history = store.clone();
+        let market_data_for_history = market_data.clone();
+        let history_settings = settings.market_history.clone();
 
         // Update coins
         tokio::spawn(async move {
             loop {
-                store_for_update.update_coins().await;
+                store_for_update
+                    .update_coins(market_data_for_update.as_ref())
+                    .await;
                 sleep(Duration::from_secs(
                     settings.interval_of_coin_update * 60 * 60,
                 ))
@@ -56,6 +68,19 @@ impl Application {
             }
         });
 
+        // Record price history
+        tokio::spawn(async move {
+            loop {
+                store_for_history
+                    .record_market_history(market_data_for_history.as_ref(), &history_settings)
+                    .await;
+                sleep(Duration::from_secs(
+                    history_settings.snapshot_interval_minutes * 60,
+                ))
+                .await;
+            }
+        });
+
         let address = format!(
             "{}:{}",
             settings.application.host, settings.application.port
@@ -66,7 +91,7 @@ impl Application {
 
         tracing::info!("Listening on {}", &address);
 
-        run(listener, store, settings).await;
+        run(listener, store, market_data, settings).await;
 
         Ok(Self { port })
     }
@@ -75,6 +100,7 @@ impl Application {
 async fn run(
     listener: tokio::net::TcpListener,
     store: crate::store::Store,
+    market_data: std::sync::Arc<dyn crate::market_data::MarketDataProvider>,
     settings: crate::settings::Settings,
 ) {
     let redis_url = std::env::var("REDIS_URL").expect("Failed to get REDIS_URL.");
@@ -93,12 +119,14 @@ async fn run(
             axum::http::Method::GET,
             axum::http::Method::POST,
             axum::http::Method::PUT,
+            axum::http::Method::PATCH,
             axum::http::Method::DELETE,
         ])
         .allow_headers(vec![
             axum::http::header::ORIGIN,
             axum::http::header::AUTHORIZATION,
             axum::http::header::ACCEPT,
+            axum::http::HeaderName::from_static(crate::utils::CSRF_HEADER),
         ])
         .allow_origin(
             settings
@@ -111,8 +139,15 @@ async fn run(
     env.set_loader(minijinja::path_loader("templates"));
     let app_state = AppState {
         db_store: store,
-        redis_store: redis_pool,
+        redis_store: redis_pool.clone(),
         env,
+        market_data: crate::market_data::MarketDataCache::new(
+            market_data,
+            redis_pool,
+            settings.market_data.cache.clone(),
+        ),
+        market_data_settings: settings.market_data.clone(),
+        trust_proxy_headers: settings.application.trust_proxy_headers,
         key: axum_extra::extract::cookie::Key::from(
             std::env::var("COOKIE_SECRET")
                 .expect("Failed to get COOKIE_SECRET.")
@@ -126,16 +161,24 @@ async fn run(
             axum::routing::get(routes::health_check),
         )
         .nest("/api/users", routes::users_routes(app_state.clone()))
+        .nest("/api/admin", routes::admin_routes(app_state.clone()))
         .nest("/api/qa", routes::qa_routes(app_state.clone()))
         .nest("/api/crypto", routes::crypto_routes())
+        .layer(axum::middleware::from_fn_with_state(
+            app_state.clone(),
+            crate::utils::verify_csrf_token,
+        ))
         .with_state(app_state.clone())
         .layer(tower_http::trace::TraceLayer::new_for_http())
         .layer(cors);
 
-    axum::serve(listener, app.into_make_service())
-        .with_graceful_shutdown(shutdown_signal())
-        .await
-        .unwrap();
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_signal())
+    .await
+    .unwrap();
 }
 
 async fn shutdown_signal() {
@@ -0,0 +1,167 @@
+use crate::settings::AuthoredContentPolicy;
+
+impl crate::store::Store {
+    #[tracing::instrument(name = "get_exported_questions_from_db", skip(self))]
+    pub async fn get_exported_questions_from_db(
+        &self,
+        user_id: uuid::Uuid,
+    ) -> Result<Vec<crate::models::ExportedQuestion>, sqlx::Error> {
+        sqlx::query_as::<_, crate::models::ExportedQuestion>(
+            r#"
+        SELECT
+            q.id, q.title, q.slug, q.raw_content, q.score, q.created_at, q.updated_at,
+            ARRAY(SELECT qt.tag::TEXT FROM question_tags qt WHERE qt.question = q.id ORDER BY qt.tag) AS tags
+        FROM questions q
+        WHERE q.author = $1
+        ORDER BY q.created_at
+        "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.connection)
+        .await
+    }
+
+    #[tracing::instrument(name = "get_exported_answers_from_db", skip(self))]
+    pub async fn get_exported_answers_from_db(
+        &self,
+        user_id: uuid::Uuid,
+    ) -> Result<Vec<crate::models::ExportedAnswer>, sqlx::Error> {
+        sqlx::query_as::<_, crate::models::ExportedAnswer>(
+            "SELECT id, question, raw_content, score, created_at, updated_at FROM answers WHERE author = $1 ORDER BY created_at",
+        )
+        .bind(user_id)
+        .fetch_all(&self.connection)
+        .await
+    }
+
+    #[tracing::instrument(name = "get_exported_comments_from_db", skip(self))]
+    pub async fn get_exported_comments_from_db(
+        &self,
+        user_id: uuid::Uuid,
+    ) -> Result<Vec<crate::models::ExportedComment>, sqlx::Error> {
+        sqlx::query_as::<_, crate::models::ExportedComment>(
+            "SELECT id, question, answer, parent, raw_content, created_at, updated_at FROM comments WHERE author = $1 ORDER BY created_at",
+        )
+        .bind(user_id)
+        .fetch_all(&self.connection)
+        .await
+    }
+
+    #[tracing::instrument(name = "get_exported_votes_from_db", skip(self))]
+    pub async fn get_exported_votes_from_db(
+        &self,
+        user_id: uuid::Uuid,
+    ) -> Result<Vec<crate::models::ExportedVote>, sqlx::Error> {
+        sqlx::query_as::<_, crate::models::ExportedVote>(
+            r#"
+        SELECT 'question' AS target_type, question AS target_id, value, created_at
+        FROM question_votes WHERE voter = $1
+        UNION ALL
+        SELECT 'answer' AS target_type, answer AS target_id, value, created_at
+        FROM answer_votes WHERE voter = $1
+        ORDER BY created_at
+        "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.connection)
+        .await
+    }
+
+    /// Deletes an account along with its votes, and anonymizes or deletes
+    /// what it wrote according to `policy`. Posts are never removed through
+    /// the `users` foreign keys, so nobody else's answers go with them.
+    #[tracing::instrument(name = "delete_account_in_db", skip(self))]
+    pub async fn delete_account_in_db(
+        &self,
+        user_id: uuid::Uuid,
+        policy: AuthoredContentPolicy,
+    ) -> Result<(), sqlx::Error> {
+        let mut transaction = self.connection.begin().await?;
+
+        // Votes go with the account, so take them out of the scores first
+        sqlx::query(
+            "UPDATE questions q SET score = q.score - v.value FROM question_votes v WHERE v.question = q.id AND v.voter = $1",
+        )
+        .bind(user_id)
+        .execute(&mut *transaction)
+        .await?;
+        sqlx::query(
+            "UPDATE answers a SET score = a.score - v.value FROM answer_votes v WHERE v.answer = a.id AND v.voter = $1",
+        )
+        .bind(user_id)
+        .execute(&mut *transaction)
+        .await?;
+        sqlx::query("DELETE FROM question_votes WHERE voter = $1")
+            .bind(user_id)
+            .execute(&mut *transaction)
+            .await?;
+        sqlx::query("DELETE FROM answer_votes WHERE voter = $1")
+            .bind(user_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        // Deleting cascades to replies and comments, so posts other people
+        // have replied to or commented on are anonymized below instead
+        if policy == AuthoredContentPolicy::Delete {
+            // Replies the user wrote to their own comments free up the
+            // comments above them, hence one pass per level
+            loop {
+                let deleted = sqlx::query(
+                    "DELETE FROM comments c WHERE c.author = $1 AND NOT EXISTS (SELECT 1 FROM comments r WHERE r.parent = c.id)",
+                )
+                .bind(user_id)
+                .execute(&mut *transaction)
+                .await?
+                .rows_affected();
+                if deleted == 0 {
+                    break;
+                }
+            }
+            sqlx::query(
+                "DELETE FROM answers a WHERE a.author = $1 AND NOT EXISTS (SELECT 1 FROM comments c WHERE c.answer = a.id)",
+            )
+            .bind(user_id)
+            .execute(&mut *transaction)
+            .await?;
+            sqlx::query(
+                "DELETE FROM questions q WHERE q.author = $1 AND NOT EXISTS (SELECT 1 FROM answers a WHERE a.question = q.id) AND NOT EXISTS (SELECT 1 FROM comments c WHERE c.question = q.id)",
+            )
+            .bind(user_id)
+            .execute(&mut *transaction)
+            .await?;
+        }
+
+        for table in ["questions", "answers", "comments"] {
+            sqlx::query(&format!("UPDATE {table} SET author = $2 WHERE author = $1"))
+                .bind(user_id)
+                .bind(crate::models::DELETED_USER_ID)
+                .execute(&mut *transaction)
+                .await?;
+        }
+
+        // The audit trail keeps what happened to the account, but not where
+        // from or under which email. Addresses of administrators acting on
+        // the account are theirs and stay.
+        sqlx::query(
+            "UPDATE audit_log SET ip_address = NULL, user_agent = NULL WHERE actor = $1 OR (actor IS NULL AND target_id = $1)",
+        )
+        .bind(user_id)
+        .execute(&mut *transaction)
+        .await?;
+        sqlx::query(
+            "UPDATE audit_log SET payload = payload - ARRAY['email', 'old_email', 'new_email'] WHERE (actor = $1 OR target_id = $1) AND payload ?| ARRAY['email', 'old_email', 'new_email']",
+        )
+        .bind(user_id)
+        .execute(&mut *transaction)
+        .await?;
+
+        sqlx::query("DELETE FROM users WHERE id = $1 RETURNING id")
+            .bind(user_id)
+            .fetch_one(&mut *transaction)
+            .await?;
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+}
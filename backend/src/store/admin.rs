@@ -0,0 +1,189 @@
+use sqlx::{Postgres, QueryBuilder, Row};
+
+const ADMIN_USER_COLUMNS: &str = "u.id, u.email, u.first_name, u.last_name, u.is_active, u.is_staff, u.is_superuser, u.thumbnail, u.date_joined, u.totp_secret IS NOT NULL AS two_factor_enabled, CASE WHEN u.suspended_until > NOW() THEN u.suspended_until END AS suspended_until, CASE WHEN u.suspended_until > NOW() THEN u.suspension_reason END AS suspension_reason";
+
+impl crate::store::Store {
+    #[tracing::instrument(name = "get_users_from_db", skip(self))]
+    pub async fn get_users_from_db(
+        &self,
+        list_query: &crate::models::AdminUserListQuery,
+        pagination: crate::utils::Pagination,
+    ) -> Result<(Vec<crate::models::AdminUser>, i64), sqlx::Error> {
+        let mut count_query = QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM users u");
+        push_user_filters(&mut count_query, list_query);
+        let total: i64 = count_query
+            .build()
+            .map(|row: sqlx::postgres::PgRow| -> i64 { row.get(0) })
+            .fetch_one(&self.connection)
+            .await?;
+
+        let mut query =
+            QueryBuilder::<Postgres>::new(format!("SELECT {} FROM users u", ADMIN_USER_COLUMNS));
+        push_user_filters(&mut query, list_query);
+        query.push(" ORDER BY u.date_joined DESC, u.id LIMIT ");
+        query.push_bind(pagination.limit());
+        query.push(" OFFSET ");
+        query.push_bind(pagination.offset());
+
+        let users = query
+            .build_query_as::<crate::models::AdminUser>()
+            .fetch_all(&self.connection)
+            .await?;
+
+        Ok((users, total))
+    }
+
+    /// Unlike `get_user_by_id`, also finds inactive users.
+    #[tracing::instrument(name = "get_admin_user_from_db", skip(self))]
+    pub async fn get_admin_user_from_db(
+        &self,
+        user_id: uuid::Uuid,
+    ) -> Result<crate::models::AdminUser, sqlx::Error> {
+        sqlx::query_as::<_, crate::models::AdminUser>(&format!(
+            "SELECT {} FROM users u WHERE u.id = $1",
+            ADMIN_USER_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_one(&self.connection)
+        .await
+    }
+
+    #[tracing::instrument(name = "get_user_activity_from_db", skip(self))]
+    pub async fn get_user_activity_from_db(
+        &self,
+        user_id: uuid::Uuid,
+    ) -> Result<crate::models::UserActivity, sqlx::Error> {
+        sqlx::query_as::<_, crate::models::UserActivity>(
+            r#"
+        SELECT
+            (SELECT COUNT(*) FROM questions WHERE author = $1) AS questions,
+            (SELECT COUNT(*) FROM answers WHERE author = $1) AS answers,
+            (SELECT COUNT(*) FROM answers a JOIN questions q ON q.accepted_answer = a.id WHERE a.author = $1) AS accepted_answers,
+            (SELECT COUNT(*) FROM comments WHERE author = $1) AS comments,
+            (SELECT COUNT(*) FROM question_votes WHERE voter = $1)
+                + (SELECT COUNT(*) FROM answer_votes WHERE voter = $1) AS votes
+        "#,
+        )
+        .bind(user_id)
+        .fetch_one(&self.connection)
+        .await
+    }
+
+    /// Activates or deactivates an account. Deactivated accounts cannot be
+    /// re-activated with an activation code, only by an administrator.
+    #[tracing::instrument(name = "set_user_active_in_db", skip(self))]
+    pub async fn set_user_active_in_db(
+        &self,
+        user_id: uuid::Uuid,
+        is_active: bool,
+    ) -> Result<crate::models::AdminUser, sqlx::Error> {
+        sqlx::query(
+            "UPDATE users SET is_active = $2, deactivated_at = CASE WHEN $2 THEN NULL ELSE NOW() END WHERE id = $1 RETURNING id",
+        )
+        .bind(user_id)
+        .bind(is_active)
+        .fetch_one(&self.connection)
+        .await?;
+
+        self.get_admin_user_from_db(user_id).await
+    }
+
+    #[tracing::instrument(name = "set_user_staff_in_db", skip(self))]
+    pub async fn set_user_staff_in_db(
+        &self,
+        user_id: uuid::Uuid,
+        is_staff: bool,
+    ) -> Result<crate::models::AdminUser, sqlx::Error> {
+        sqlx::query("UPDATE users SET is_staff = $2 WHERE id = $1 RETURNING id")
+            .bind(user_id)
+            .bind(is_staff)
+            .fetch_one(&self.connection)
+            .await?;
+
+        self.get_admin_user_from_db(user_id).await
+    }
+
+    #[tracing::instrument(name = "suspend_user_in_db", skip(self))]
+    pub async fn suspend_user_in_db(
+        &self,
+        user_id: uuid::Uuid,
+        admin_id: uuid::Uuid,
+        suspension: &crate::models::UserSuspension,
+    ) -> Result<crate::models::AdminUser, sqlx::Error> {
+        sqlx::query(
+            "UPDATE users SET suspended_until = $2, suspension_reason = $3, suspended_by = $4 WHERE id = $1 RETURNING id",
+        )
+        .bind(user_id)
+        .bind(suspension.until)
+        .bind(&suspension.reason)
+        .bind(admin_id)
+        .fetch_one(&self.connection)
+        .await?;
+
+        self.get_admin_user_from_db(user_id).await
+    }
+
+    #[tracing::instrument(name = "unsuspend_user_in_db", skip(self))]
+    pub async fn unsuspend_user_in_db(
+        &self,
+        user_id: uuid::Uuid,
+    ) -> Result<crate::models::AdminUser, sqlx::Error> {
+        sqlx::query(
+            "UPDATE users SET suspended_until = NULL, suspension_reason = NULL, suspended_by = NULL WHERE id = $1 RETURNING id",
+        )
+        .bind(user_id)
+        .fetch_one(&self.connection)
+        .await?;
+
+        self.get_admin_user_from_db(user_id).await
+    }
+}
+
+/// Appends the `WHERE` clause shared by the users listing and its count.
+fn push_user_filters(
+    builder: &mut QueryBuilder<'_, Postgres>,
+    list_query: &crate::models::AdminUserListQuery,
+) {
+    builder.push(" WHERE TRUE");
+
+    if let Some(q) = list_query
+        .q
+        .as_deref()
+        .map(str::trim)
+        .filter(|q| !q.is_empty())
+    {
+        let pattern = format!(
+            "%{}%",
+            q.replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_")
+        );
+        builder.push(" AND (u.email ILIKE ");
+        builder.push_bind(pattern.clone());
+        builder.push(" OR u.first_name ILIKE ");
+        builder.push_bind(pattern.clone());
+        builder.push(" OR u.last_name ILIKE ");
+        builder.push_bind(pattern);
+        builder.push(")");
+    }
+
+    if let Some(is_active) = list_query.is_active {
+        builder.push(" AND COALESCE(u.is_active, FALSE) = ");
+        builder.push_bind(is_active);
+    }
+
+    if let Some(is_staff) = list_query.is_staff {
+        builder.push(" AND COALESCE(u.is_staff, FALSE) = ");
+        builder.push_bind(is_staff);
+    }
+
+    match list_query.suspended {
+        Some(true) => {
+            builder.push(" AND u.suspended_until > NOW()");
+        }
+        Some(false) => {
+            builder.push(" AND (u.suspended_until IS NULL OR u.suspended_until <= NOW())");
+        }
+        None => {}
+    }
+}
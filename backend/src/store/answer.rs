@@ -31,8 +31,8 @@ impl crate::store::Store {
         )
         .bind(&create_answer.content)
         .bind(&create_answer.raw_content)
-        .bind(&create_answer.author)
-        .bind(&create_answer.question)
+        .bind(create_answer.author)
+        .bind(create_answer.question)
         .map(|row: sqlx::postgres::PgRow| -> uuid::Uuid { row.get("id") })
         .fetch_one(&mut *transaction)
         .await {
@@ -40,6 +40,15 @@ impl crate::store::Store {
             Err(e) => return Err(e),
         };
 
+        self.record_answer_revision_in_db(
+            &mut transaction,
+            a_id,
+            create_answer.author,
+            &create_answer.raw_content,
+            None,
+        )
+        .await?;
+
         let answer_author = self
             .get_an_answer_from_db(Some(&mut transaction), a_id)
             .await?;
@@ -70,51 +79,185 @@ impl crate::store::Store {
         Ok(answers)
     }
 
+    /// Answers written by `author_id`, newest first.
+    #[tracing::instrument(name = "get_answers_by_author_from_db", skip(self))]
+    pub async fn get_answers_by_author_from_db(
+        &self,
+        author_id: uuid::Uuid,
+        pagination: crate::utils::Pagination,
+    ) -> Result<(Vec<crate::models::AnswerWithQuestion>, i64), sqlx::Error> {
+        let total: i64 = sqlx::query("SELECT COUNT(*) FROM answers WHERE author = $1")
+            .bind(author_id)
+            .map(|row: sqlx::postgres::PgRow| -> i64 { row.get(0) })
+            .fetch_one(&self.connection)
+            .await?;
+
+        let results = sqlx::query_as::<_, crate::models::AnswerWithQuestionQueryResult>(
+            crate::utils::ANSWER_AUTHOR_QUERY_VIA_AUTHOR_ID,
+        )
+        .bind(author_id)
+        .bind(pagination.limit())
+        .bind(pagination.offset())
+        .fetch_all(&self.connection)
+        .await?;
+
+        let answers = results.into_iter().map(|result| result.into()).collect();
+
+        Ok((answers, total))
+    }
+
+    /// Answer counterpart of `delete_question_from_db`.
     #[tracing::instrument(name = "delete_answer_from_db")]
     pub async fn delete_answer_from_db(
         &self,
-        author_id: uuid::Uuid,
+        user_id: uuid::Uuid,
         answer_id: uuid::Uuid,
+        moderated: bool,
+        reason: Option<String>,
     ) -> Result<(), sqlx::Error> {
-        let deleted = sqlx::query("DELETE FROM answers WHERE id = $1 AND author = $2 RETURNING id")
-            .bind(answer_id)
-            .bind(author_id)
-            .fetch_optional(&self.connection)
-            .await?;
+        let mut transaction = self.connection.begin().await?;
+
+        let deleted = sqlx::query(
+            "DELETE FROM answers WHERE id = $1 AND (author = $2 OR $3) RETURNING question",
+        )
+        .bind(answer_id)
+        .bind(user_id)
+        .bind(moderated)
+        .map(|row: sqlx::postgres::PgRow| -> uuid::Uuid { row.get("question") })
+        .fetch_optional(&mut *transaction)
+        .await?;
 
-        if deleted.is_none() {
+        let Some(question_id) = deleted else {
             tracing::warn!(
-                "Attempt to delete question with id {} by non-author {}",
+                "Attempt to delete answer with id {} by non-author {}",
                 answer_id,
-                author_id
+                user_id
             );
             return Err(sqlx::Error::RowNotFound);
+        };
+
+        if moderated {
+            self.record_moderation_action_in_db(
+                &mut transaction,
+                user_id,
+                crate::models::ModerationActionKind::DeleteAnswer,
+                Some(question_id),
+                Some(answer_id),
+                reason,
+            )
+            .await?;
         }
 
+        transaction.commit().await?;
+
         Ok(())
     }
 
+    /// Answer counterpart of `update_question_in_db`.
     #[tracing::instrument(name = "update_answer_in_db", skip(update_answer))]
     pub async fn update_answer_in_db(
         &self,
         update_answer: crate::models::UpdateAnswer,
+        moderated: bool,
     ) -> Result<crate::models::AnswerAuthor, sqlx::Error> {
-        let a_id = match sqlx::query(
-            "UPDATE answers SET content = $1, raw_content = $2 WHERE id = $3 AND author = $4 RETURNING id",
+        let mut transaction = self.connection.begin().await?;
+
+        let (a_id, question_id) = match sqlx::query(
+            "UPDATE answers SET content = $1, raw_content = $2 WHERE id = $3 AND (author = $4 OR $5) RETURNING id, question",
         )
         .bind(&update_answer.content)
         .bind(&update_answer.raw_content)
-        .bind(&update_answer.answer_id)
-        .bind(&update_answer.author)
-        .map(|row: sqlx::postgres::PgRow| -> uuid::Uuid { row.get("id") })
-        .fetch_one(&self.connection)
+        .bind(update_answer.answer_id)
+        .bind(update_answer.author)
+        .bind(moderated)
+        .map(|row: sqlx::postgres::PgRow| -> (uuid::Uuid, uuid::Uuid) {
+            (row.get("id"), row.get("question"))
+        })
+        .fetch_one(&mut *transaction)
         .await
         {
-            Ok(id) => id,
+            Ok(ids) => ids,
             Err(e) => return Err(e),
         };
 
-        let answer_author = self.get_an_answer_from_db(None, a_id).await?;
+        if moderated {
+            self.record_moderation_action_in_db(
+                &mut transaction,
+                update_answer.author,
+                crate::models::ModerationActionKind::EditAnswer,
+                Some(question_id),
+                Some(a_id),
+                update_answer.edit_summary.clone(),
+            )
+            .await?;
+        }
+
+        self.record_answer_revision_in_db(
+            &mut transaction,
+            a_id,
+            update_answer.author,
+            &update_answer.raw_content,
+            update_answer.edit_summary,
+        )
+        .await?;
+
+        let answer_author = self
+            .get_an_answer_from_db(Some(&mut transaction), a_id)
+            .await?;
+
+        transaction.commit().await?;
+
+        Ok(answer_author)
+    }
+
+    /// Marks `answer_id` as the accepted answer of its question, replacing
+    /// any previously accepted answer. Only the question's author may do so.
+    #[tracing::instrument(name = "accept_answer_in_db")]
+    pub async fn accept_answer_in_db(
+        &self,
+        author_id: uuid::Uuid,
+        answer_id: uuid::Uuid,
+    ) -> Result<crate::models::AnswerAuthor, sqlx::Error> {
+        let mut transaction = self.connection.begin().await?;
+
+        sqlx::query(
+            "UPDATE questions q SET accepted_answer = a.id FROM answers a WHERE a.id = $1 AND q.id = a.question AND q.author = $2 RETURNING q.id",
+        )
+        .bind(answer_id)
+        .bind(author_id)
+        .fetch_one(&mut *transaction)
+        .await?;
+
+        let answer_author = self
+            .get_an_answer_from_db(Some(&mut transaction), answer_id)
+            .await?;
+
+        transaction.commit().await?;
+
+        Ok(answer_author)
+    }
+
+    #[tracing::instrument(name = "unaccept_answer_in_db")]
+    pub async fn unaccept_answer_in_db(
+        &self,
+        author_id: uuid::Uuid,
+        answer_id: uuid::Uuid,
+    ) -> Result<crate::models::AnswerAuthor, sqlx::Error> {
+        let mut transaction = self.connection.begin().await?;
+
+        sqlx::query(
+            "UPDATE questions q SET accepted_answer = NULL FROM answers a WHERE a.id = $1 AND q.id = a.question AND q.author = $2 AND q.accepted_answer = a.id RETURNING q.id",
+        )
+        .bind(answer_id)
+        .bind(author_id)
+        .fetch_one(&mut *transaction)
+        .await?;
+
+        let answer_author = self
+            .get_an_answer_from_db(Some(&mut transaction), answer_id)
+            .await?;
+
+        transaction.commit().await?;
 
         Ok(answer_author)
     }
@@ -0,0 +1,78 @@
+const API_TOKEN_COLUMNS: &str =
+    "id, name, token_prefix, scope, expires_at, last_used_at, created_at";
+
+impl crate::store::Store {
+    #[tracing::instrument(name = "create_api_token_in_db", skip(self, token_hash))]
+    pub async fn create_api_token_in_db(
+        &self,
+        user_id: uuid::Uuid,
+        name: &str,
+        scope: crate::models::ApiTokenScope,
+        token_hash: &str,
+        token_prefix: &str,
+        expires_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<crate::models::ApiToken, sqlx::Error> {
+        sqlx::query_as::<_, crate::models::ApiToken>(&format!(
+            "INSERT INTO api_tokens (user_id, name, scope, token_hash, token_prefix, expires_at) VALUES ($1, $2, $3, $4, $5, $6) RETURNING {}",
+            API_TOKEN_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(name)
+        .bind(scope.as_str())
+        .bind(token_hash)
+        .bind(token_prefix)
+        .bind(expires_at)
+        .fetch_one(&self.connection)
+        .await
+    }
+
+    /// Lists the tokens of `user_id`, expired ones included.
+    #[tracing::instrument(name = "get_api_tokens_from_db", skip(self))]
+    pub async fn get_api_tokens_from_db(
+        &self,
+        user_id: uuid::Uuid,
+    ) -> Result<Vec<crate::models::ApiToken>, sqlx::Error> {
+        sqlx::query_as::<_, crate::models::ApiToken>(&format!(
+            "SELECT {} FROM api_tokens WHERE user_id = $1 ORDER BY created_at DESC",
+            API_TOKEN_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_all(&self.connection)
+        .await
+    }
+
+    #[tracing::instrument(name = "delete_api_token_from_db", skip(self))]
+    pub async fn delete_api_token_from_db(
+        &self,
+        user_id: uuid::Uuid,
+        token_id: uuid::Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM api_tokens WHERE id = $1 AND user_id = $2 RETURNING id")
+            .bind(token_id)
+            .bind(user_id)
+            .fetch_one(&self.connection)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Finds the active owner of an unexpired token and marks the token as
+    /// used in the same statement.
+    #[tracing::instrument(name = "use_api_token_in_db", skip(self, token_hash))]
+    pub async fn use_api_token_in_db(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<crate::models::ApiTokenOwner>, sqlx::Error> {
+        sqlx::query_as::<_, crate::models::ApiTokenOwner>(
+            r#"
+        UPDATE api_tokens t SET last_used_at = NOW()
+        FROM users u
+        WHERE t.token_hash = $1 AND t.expires_at > NOW() AND u.id = t.user_id AND u.is_active = TRUE
+        RETURNING t.id, t.user_id, t.scope
+        "#,
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.connection)
+        .await
+    }
+}
@@ -0,0 +1,93 @@
+use sqlx::{Postgres, QueryBuilder, Row};
+
+impl crate::store::Store {
+    #[tracing::instrument(name = "insert_audit_entry_in_db", skip(self))]
+    pub async fn insert_audit_entry_in_db(
+        &self,
+        entry: &crate::models::NewAuditEntry,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO audit_log (event, actor, target_type, target_id, ip_address, user_agent, payload) VALUES ($1, $2, $3, $4, $5, $6, $7)",
+        )
+        .bind(entry.event.as_str())
+        .bind(entry.actor)
+        .bind(entry.target.map(|target| target.kind()))
+        .bind(entry.target.map(|target| target.id()))
+        .bind(&entry.ip_address)
+        .bind(&entry.user_agent)
+        .bind(&entry.payload)
+        .execute(&self.connection)
+        .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "get_audit_log_from_db", skip(self))]
+    pub async fn get_audit_log_from_db(
+        &self,
+        log_query: &crate::models::AuditLogQuery,
+        pagination: crate::utils::Pagination,
+    ) -> Result<(Vec<crate::models::AuditLogEntry>, i64), sqlx::Error> {
+        let mut count_query = QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM audit_log l");
+        push_audit_filters(&mut count_query, log_query);
+        let total: i64 = count_query
+            .build()
+            .map(|row: sqlx::postgres::PgRow| -> i64 { row.get(0) })
+            .fetch_one(&self.connection)
+            .await?;
+
+        let mut query = QueryBuilder::<Postgres>::new(
+            "SELECT l.id, l.event, l.actor, l.target_type, l.target_id, l.ip_address, l.user_agent, l.payload, l.created_at FROM audit_log l",
+        );
+        push_audit_filters(&mut query, log_query);
+        query.push(" ORDER BY l.created_at DESC, l.id LIMIT ");
+        query.push_bind(pagination.limit());
+        query.push(" OFFSET ");
+        query.push_bind(pagination.offset());
+
+        let entries = query
+            .build_query_as::<crate::models::AuditLogEntry>()
+            .fetch_all(&self.connection)
+            .await?;
+
+        Ok((entries, total))
+    }
+}
+
+/// Appends the `WHERE` clause shared by the audit log listing and its count.
+fn push_audit_filters(
+    builder: &mut QueryBuilder<'_, Postgres>,
+    log_query: &crate::models::AuditLogQuery,
+) {
+    builder.push(" WHERE TRUE");
+
+    if let Some(event) = &log_query.event {
+        builder.push(" AND l.event = ");
+        builder.push_bind(event.clone());
+    }
+
+    if let Some(actor) = log_query.actor {
+        builder.push(" AND l.actor = ");
+        builder.push_bind(actor);
+    }
+
+    if let Some(target) = log_query.target {
+        builder.push(" AND l.target_id = ");
+        builder.push_bind(target);
+    }
+
+    if let Some(ip_address) = &log_query.ip_address {
+        builder.push(" AND l.ip_address = ");
+        builder.push_bind(ip_address.clone());
+    }
+
+    if let Some(from) = log_query.from {
+        builder.push(" AND l.created_at >= ");
+        builder.push_bind(from);
+    }
+
+    if let Some(to) = log_query.to {
+        builder.push(" AND l.created_at < ");
+        builder.push_bind(to);
+    }
+}
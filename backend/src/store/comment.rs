@@ -0,0 +1,196 @@
+use sqlx::Row;
+
+impl crate::store::Store {
+    #[tracing::instrument(name = "get_a_comment_from_db", skip(transaction, comment_id))]
+    pub async fn get_a_comment_from_db(
+        &self,
+        transaction: Option<&mut sqlx::Transaction<'_, sqlx::Postgres>>,
+        comment_id: uuid::Uuid,
+    ) -> Result<crate::models::CommentAuthor, sqlx::Error> {
+        let query = sqlx::query_as::<_, crate::models::CommentAuthorQueryResult>(
+            crate::utils::COMMENT_AUTHOR_QUERY,
+        )
+        .bind(comment_id);
+
+        let query_result = match transaction {
+            Some(t) => query.fetch_one(&mut **t).await?,
+            None => query.fetch_one(&self.connection).await?,
+        };
+
+        Ok(query_result.into())
+    }
+
+    /// Inserts a comment on a question or an answer. A `parent` must be a
+    /// comment on the same post, otherwise nothing is inserted and
+    /// `sqlx::Error::RowNotFound` is returned.
+    #[tracing::instrument(name = "create_comment_in_db", skip(create_comment))]
+    pub async fn create_comment_in_db(
+        &self,
+        create_comment: crate::models::CreateComment,
+    ) -> Result<crate::models::CommentAuthor, sqlx::Error> {
+        let mut transaction = self.connection.begin().await?;
+        let c_id = sqlx::query(
+            "INSERT INTO comments (content, raw_content, author, question, answer, parent) SELECT $1, $2, $3, $4, $5, $6 WHERE $6::UUID IS NULL OR EXISTS (SELECT 1 FROM comments p WHERE p.id = $6 AND p.question IS NOT DISTINCT FROM $4 AND p.answer IS NOT DISTINCT FROM $5) RETURNING id",
+        )
+        .bind(&create_comment.content)
+        .bind(&create_comment.raw_content)
+        .bind(create_comment.author)
+        .bind(create_comment.question)
+        .bind(create_comment.answer)
+        .bind(create_comment.parent)
+        .map(|row: sqlx::postgres::PgRow| -> uuid::Uuid { row.get("id") })
+        .fetch_one(&mut *transaction)
+        .await?;
+
+        let comment_author = self
+            .get_a_comment_from_db(Some(&mut transaction), c_id)
+            .await?;
+
+        transaction.commit().await?;
+
+        Ok(comment_author)
+    }
+
+    #[tracing::instrument(name = "get_question_comments_from_db")]
+    pub async fn get_question_comments_from_db(
+        &self,
+        question_id: uuid::Uuid,
+    ) -> Result<Vec<crate::models::CommentAuthor>, sqlx::Error> {
+        let results = sqlx::query_as::<_, crate::models::CommentAuthorQueryResult>(
+            crate::utils::COMMENT_AUTHOR_QUERY_VIA_QUESTION_ID,
+        )
+        .bind(question_id)
+        .fetch_all(&self.connection)
+        .await?;
+
+        Ok(results.into_iter().map(|result| result.into()).collect())
+    }
+
+    #[tracing::instrument(name = "get_answer_comments_from_db")]
+    pub async fn get_answer_comments_from_db(
+        &self,
+        answer_id: uuid::Uuid,
+    ) -> Result<Vec<crate::models::CommentAuthor>, sqlx::Error> {
+        let results = sqlx::query_as::<_, crate::models::CommentAuthorQueryResult>(
+            crate::utils::COMMENT_AUTHOR_QUERY_VIA_ANSWER_ID,
+        )
+        .bind(answer_id)
+        .fetch_all(&self.connection)
+        .await?;
+
+        Ok(results.into_iter().map(|result| result.into()).collect())
+    }
+
+    /// The author of a comment, and whether the post it is on is locked,
+    /// which for comments on answers includes a lock on the question.
+    #[tracing::instrument(name = "get_comment_lock_state_from_db")]
+    pub async fn get_comment_lock_state_from_db(
+        &self,
+        comment_id: uuid::Uuid,
+    ) -> Result<(uuid::Uuid, bool), sqlx::Error> {
+        sqlx::query(
+            "SELECT c.author, COALESCE(q.locked_at, a.locked_at, aq.locked_at) IS NOT NULL AS is_locked FROM comments c LEFT JOIN questions q ON q.id = c.question LEFT JOIN answers a ON a.id = c.answer LEFT JOIN questions aq ON aq.id = a.question WHERE c.id = $1",
+        )
+        .bind(comment_id)
+        .map(|row: sqlx::postgres::PgRow| -> (uuid::Uuid, bool) {
+            (row.get("author"), row.get("is_locked"))
+        })
+        .fetch_one(&self.connection)
+        .await
+    }
+
+    /// Edits a comment. Authors edit their own comments; `moderated` lets a
+    /// moderator edit anybody's and records the action.
+    #[tracing::instrument(name = "update_comment_in_db", skip(update_comment))]
+    pub async fn update_comment_in_db(
+        &self,
+        update_comment: crate::models::UpdateComment,
+        moderated: bool,
+    ) -> Result<crate::models::CommentAuthor, sqlx::Error> {
+        let mut transaction = self.connection.begin().await?;
+
+        let (c_id, question_id, answer_id) = sqlx::query(
+            "UPDATE comments c SET content = $1, raw_content = $2 WHERE c.id = $3 AND (c.author = $4 OR $5) RETURNING c.id, COALESCE(c.question, (SELECT a.question FROM answers a WHERE a.id = c.answer)) AS question, c.answer",
+        )
+        .bind(&update_comment.content)
+        .bind(&update_comment.raw_content)
+        .bind(update_comment.comment_id)
+        .bind(update_comment.author)
+        .bind(moderated)
+        .map(|row: sqlx::postgres::PgRow| -> (uuid::Uuid, Option<uuid::Uuid>, Option<uuid::Uuid>) {
+            (row.get("id"), row.get("question"), row.get("answer"))
+        })
+        .fetch_one(&mut *transaction)
+        .await?;
+
+        if moderated {
+            self.record_moderation_action_in_db(
+                &mut transaction,
+                update_comment.author,
+                crate::models::ModerationActionKind::EditComment,
+                question_id,
+                answer_id,
+                None,
+            )
+            .await?;
+        }
+
+        let comment_author = self
+            .get_a_comment_from_db(Some(&mut transaction), c_id)
+            .await?;
+
+        transaction.commit().await?;
+
+        Ok(comment_author)
+    }
+
+    /// Comment counterpart of `delete_answer_from_db`. Replies go with the
+    /// comment.
+    #[tracing::instrument(name = "delete_comment_from_db")]
+    pub async fn delete_comment_from_db(
+        &self,
+        user_id: uuid::Uuid,
+        comment_id: uuid::Uuid,
+        moderated: bool,
+        reason: Option<String>,
+    ) -> Result<(), sqlx::Error> {
+        let mut transaction = self.connection.begin().await?;
+
+        let deleted = sqlx::query(
+            "DELETE FROM comments c WHERE c.id = $1 AND (c.author = $2 OR $3) RETURNING COALESCE(c.question, (SELECT a.question FROM answers a WHERE a.id = c.answer)) AS question, c.answer",
+        )
+        .bind(comment_id)
+        .bind(user_id)
+        .bind(moderated)
+        .map(|row: sqlx::postgres::PgRow| -> (Option<uuid::Uuid>, Option<uuid::Uuid>) {
+            (row.get("question"), row.get("answer"))
+        })
+        .fetch_optional(&mut *transaction)
+        .await?;
+
+        let Some((question_id, answer_id)) = deleted else {
+            tracing::warn!(
+                "Attempt to delete comment with id {} by non-author {}",
+                comment_id,
+                user_id
+            );
+            return Err(sqlx::Error::RowNotFound);
+        };
+
+        if moderated {
+            self.record_moderation_action_in_db(
+                &mut transaction,
+                user_id,
+                crate::models::ModerationActionKind::DeleteComment,
+                question_id,
+                answer_id,
+                reason,
+            )
+            .await?;
+        }
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,380 @@
+use crate::{
+    market_data::{CoinMarketData, MarketDataProvider, PriceOptions},
+    models::{DailyCandle, PriceSnapshot},
+    settings::MarketHistorySettings,
+};
+use sqlx::Row;
+use std::collections::{BTreeMap, HashMap};
+
+impl crate::store::Store {
+    /// Ids of the `limit` coins with the best market cap rank.
+    #[tracing::instrument(name = "get_tracked_coins_from_db", skip(self))]
+    pub async fn get_tracked_coins_from_db(&self, limit: i64) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query(
+            "SELECT id FROM tags WHERE market_cap_rank > 0 ORDER BY market_cap_rank, id LIMIT $1",
+        )
+        .bind(limit)
+        .map(|row: sqlx::postgres::PgRow| -> String { row.get("id") })
+        .fetch_all(&self.connection)
+        .await
+    }
+
+    /// Stores `(coin, snapshot)` pairs priced in `vs_currency`, keeping the
+    /// snapshots already recorded at the same time.
+    #[tracing::instrument(name = "insert_price_snapshots_in_db", skip(self, snapshots))]
+    pub async fn insert_price_snapshots_in_db(
+        &self,
+        vs_currency: &str,
+        snapshots: &[(String, PriceSnapshot)],
+    ) -> Result<(), sqlx::Error> {
+        let coins: Vec<&str> = snapshots.iter().map(|(coin, _)| coin.as_str()).collect();
+        let captured_ats: Vec<chrono::DateTime<chrono::Utc>> =
+            snapshots.iter().map(|(_, s)| s.captured_at).collect();
+        let prices: Vec<f64> = snapshots.iter().map(|(_, s)| s.price).collect();
+        let market_caps: Vec<Option<f64>> = snapshots.iter().map(|(_, s)| s.market_cap).collect();
+        let total_volumes: Vec<Option<f64>> =
+            snapshots.iter().map(|(_, s)| s.total_volume).collect();
+
+        sqlx::query(
+            "INSERT INTO price_snapshots (coin, vs_currency, captured_at, price, market_cap, total_volume) SELECT coin, $2, captured_at, price, market_cap, total_volume FROM UNNEST($1::text[], $3::timestamptz[], $4::float8[], $5::float8[], $6::float8[]) AS s (coin, captured_at, price, market_cap, total_volume) ON CONFLICT (coin, vs_currency, captured_at) DO NOTHING",
+        )
+        .bind(&coins)
+        .bind(vs_currency)
+        .bind(&captured_ats)
+        .bind(&prices)
+        .bind(&market_caps)
+        .bind(&total_volumes)
+        .execute(&self.connection)
+        .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "upsert_daily_candles_in_db", skip(self, candles))]
+    pub async fn upsert_daily_candles_in_db(
+        &self,
+        coin: &str,
+        vs_currency: &str,
+        candles: &[DailyCandle],
+    ) -> Result<(), sqlx::Error> {
+        let days: Vec<chrono::NaiveDate> = candles.iter().map(|c| c.day).collect();
+        let opens: Vec<f64> = candles.iter().map(|c| c.open).collect();
+        let highs: Vec<f64> = candles.iter().map(|c| c.high).collect();
+        let lows: Vec<f64> = candles.iter().map(|c| c.low).collect();
+        let closes: Vec<f64> = candles.iter().map(|c| c.close).collect();
+        let volumes: Vec<f64> = candles.iter().map(|c| c.volume).collect();
+
+        sqlx::query(
+            "INSERT INTO daily_candles (coin, vs_currency, day, open, high, low, close, volume) SELECT $1, $2, * FROM UNNEST($3::date[], $4::float8[], $5::float8[], $6::float8[], $7::float8[], $8::float8[]) ON CONFLICT (coin, vs_currency, day) DO UPDATE SET open = EXCLUDED.open, high = EXCLUDED.high, low = EXCLUDED.low, close = EXCLUDED.close, volume = EXCLUDED.volume, updated_at = NOW()",
+        )
+        .bind(coin)
+        .bind(vs_currency)
+        .bind(&days)
+        .bind(&opens)
+        .bind(&highs)
+        .bind(&lows)
+        .bind(&closes)
+        .bind(&volumes)
+        .execute(&self.connection)
+        .await?;
+
+        Ok(())
+    }
+
+    /// The latest day a coin has a candle for, and when candles were last
+    /// written, or `None` if it has none yet.
+    #[tracing::instrument(name = "get_candle_freshness_from_db", skip(self))]
+    pub async fn get_candle_freshness_from_db(
+        &self,
+        coin: &str,
+        vs_currency: &str,
+    ) -> Result<Option<(chrono::NaiveDate, chrono::DateTime<chrono::Utc>)>, sqlx::Error> {
+        let row = sqlx::query(
+            "SELECT MAX(day) AS latest_day, MAX(updated_at) AS updated_at FROM daily_candles WHERE coin = $1 AND vs_currency = $2",
+        )
+        .bind(coin)
+        .bind(vs_currency)
+        .fetch_one(&self.connection)
+        .await?;
+
+        let latest_day: Option<chrono::NaiveDate> = row.get("latest_day");
+        let updated_at: Option<chrono::DateTime<chrono::Utc>> = row.get("updated_at");
+
+        Ok(latest_day.zip(updated_at))
+    }
+
+    /// Prices of `coin` in `[from, to)`. When there are more than
+    /// `max_points`, they are averaged over equal buckets starting at `from`,
+    /// whose width in seconds is returned as well.
+    #[tracing::instrument(name = "get_price_history_from_db", skip(self))]
+    pub async fn get_price_history_from_db(
+        &self,
+        coin: &str,
+        vs_currency: &str,
+        from: chrono::DateTime<chrono::Utc>,
+        to: chrono::DateTime<chrono::Utc>,
+        max_points: i64,
+    ) -> Result<(Vec<PriceSnapshot>, Option<i64>), sqlx::Error> {
+        let total: i64 = sqlx::query(
+            "SELECT COUNT(*) FROM price_snapshots WHERE coin = $1 AND vs_currency = $2 AND captured_at >= $3 AND captured_at < $4",
+        )
+        .bind(coin)
+        .bind(vs_currency)
+        .bind(from)
+        .bind(to)
+        .map(|row: sqlx::postgres::PgRow| -> i64 { row.get(0) })
+        .fetch_one(&self.connection)
+        .await?;
+
+        if total <= max_points {
+            let points = sqlx::query_as::<_, PriceSnapshot>(
+                "SELECT captured_at, price, market_cap, total_volume FROM price_snapshots WHERE coin = $1 AND vs_currency = $2 AND captured_at >= $3 AND captured_at < $4 ORDER BY captured_at",
+            )
+            .bind(coin)
+            .bind(vs_currency)
+            .bind(from)
+            .bind(to)
+            .fetch_all(&self.connection)
+            .await?;
+
+            return Ok((points, None));
+        }
+
+        // Rounded up so that the buckets never outnumber `max_points`
+        let range_seconds = (to - from).num_seconds();
+        let interval_seconds = ((range_seconds + max_points - 1) / max_points).max(1);
+        let points = sqlx::query_as::<_, PriceSnapshot>(
+            "SELECT date_bin(make_interval(secs => $5), captured_at, $3) AS captured_at, AVG(price) AS price, AVG(market_cap) AS market_cap, AVG(total_volume) AS total_volume FROM price_snapshots WHERE coin = $1 AND vs_currency = $2 AND captured_at >= $3 AND captured_at < $4 GROUP BY 1 ORDER BY 1",
+        )
+        .bind(coin)
+        .bind(vs_currency)
+        .bind(from)
+        .bind(to)
+        .bind(interval_seconds as f64)
+        .fetch_all(&self.connection)
+        .await?;
+
+        Ok((points, Some(interval_seconds)))
+    }
+
+    /// Candles of `coin` from `from` to `to`, both included, merged into
+    /// candles of `interval_days` days starting at `from`.
+    #[tracing::instrument(name = "get_candle_history_from_db", skip(self))]
+    pub async fn get_candle_history_from_db(
+        &self,
+        coin: &str,
+        vs_currency: &str,
+        from: chrono::NaiveDate,
+        to: chrono::NaiveDate,
+        interval_days: i32,
+    ) -> Result<Vec<DailyCandle>, sqlx::Error> {
+        sqlx::query_as::<_, DailyCandle>(
+            "SELECT $3::date + ((day - $3::date) / $5) * $5 AS day, (ARRAY_AGG(open ORDER BY day))[1] AS open, MAX(high) AS high, MIN(low) AS low, (ARRAY_AGG(close ORDER BY day DESC))[1] AS close, SUM(volume) AS volume FROM daily_candles WHERE coin = $1 AND vs_currency = $2 AND day BETWEEN $3 AND $4 GROUP BY 1 ORDER BY 1",
+        )
+        .bind(coin)
+        .bind(vs_currency)
+        .bind(from)
+        .bind(to)
+        .bind(interval_days)
+        .fetch_all(&self.connection)
+        .await
+    }
+
+    /// Snapshots the prices of the tracked coins, then refreshes the candles
+    /// of those whose candles are missing or older than
+    /// `candle_refresh_hours`.
+    pub async fn record_market_history(
+        &self,
+        provider: &dyn MarketDataProvider,
+        settings: &MarketHistorySettings,
+    ) {
+        let coins = match self.get_tracked_coins_from_db(settings.tracked_coins).await {
+            Ok(coins) => coins,
+            Err(e) => {
+                tracing::error!("Failed to get tracked coins: {}", e);
+                return;
+            }
+        };
+        if coins.is_empty() {
+            return;
+        }
+
+        let now = chrono::Utc::now();
+        let options = PriceOptions {
+            include_market_cap: true,
+            ..Default::default()
+        };
+        match provider
+            .simple_prices(&coins, &settings.currencies, options)
+            .await
+        {
+            Ok(prices) => {
+                for currency in &settings.currencies {
+                    let snapshots: Vec<(String, PriceSnapshot)> = prices
+                        .iter()
+                        .filter_map(|(coin, quotes)| {
+                            let quote = quotes.get(currency)?;
+                            Some((
+                                coin.clone(),
+                                PriceSnapshot {
+                                    captured_at: now,
+                                    price: quote.price,
+                                    market_cap: quote.market_cap,
+                                    total_volume: None,
+                                },
+                            ))
+                        })
+                        .collect();
+
+                    if let Err(e) = self
+                        .insert_price_snapshots_in_db(currency, &snapshots)
+                        .await
+                    {
+                        tracing::error!("Failed to store price snapshots: {}", e);
+                    }
+                }
+            }
+            Err(e) => tracing::error!("Failed to fetch prices for snapshots: {}", e),
+        }
+
+        for coin in &coins {
+            for currency in &settings.currencies {
+                self.refresh_daily_candles(provider, settings, coin, currency, now)
+                    .await;
+            }
+        }
+    }
+
+    /// Fetches the chart of `coin` since its latest candle, or over
+    /// `backfill_days` if it has none, and stores its points as snapshots and
+    /// daily candles.
+    async fn refresh_daily_candles(
+        &self,
+        provider: &dyn MarketDataProvider,
+        settings: &MarketHistorySettings,
+        coin: &str,
+        currency: &str,
+        now: chrono::DateTime<chrono::Utc>,
+    ) {
+        let freshness = match self.get_candle_freshness_from_db(coin, currency).await {
+            Ok(freshness) => freshness,
+            Err(e) => {
+                tracing::error!("Failed to get the candles of {}: {}", coin, e);
+                return;
+            }
+        };
+
+        let days = match freshness {
+            Some((_, updated_at))
+                if now - updated_at < chrono::Duration::hours(settings.candle_refresh_hours) =>
+            {
+                return;
+            }
+            // One day more than needed, as the first day of a chart is cut off
+            Some((latest_day, _)) => ((now.date_naive() - latest_day).num_days() + 2)
+                .min(i64::from(settings.backfill_days)) as i32,
+            None => settings.backfill_days,
+        };
+
+        let chart = match provider.market_chart(coin, currency, days).await {
+            Ok(chart) => chart,
+            Err(e) => {
+                tracing::error!("Failed to fetch the chart of {}: {}", coin, e);
+                return;
+            }
+        };
+
+        let snapshots: Vec<(String, PriceSnapshot)> = snapshots_from_chart(&chart)
+            .into_iter()
+            .map(|snapshot| (coin.to_string(), snapshot))
+            .collect();
+        if let Err(e) = self
+            .insert_price_snapshots_in_db(currency, &snapshots)
+            .await
+        {
+            tracing::error!("Failed to store the chart of {}: {}", coin, e);
+        }
+
+        let candles = candles_from_chart(&chart);
+        if candles.is_empty() {
+            return;
+        }
+        if let Err(e) = self
+            .upsert_daily_candles_in_db(coin, currency, &candles)
+            .await
+        {
+            tracing::error!("Failed to store the candles of {}: {}", coin, e);
+        }
+    }
+}
+
+fn point_time(point: &[f64]) -> Option<chrono::DateTime<chrono::Utc>> {
+    chrono::DateTime::from_timestamp_millis(*point.first()? as i64)
+}
+
+/// One snapshot per price point, with the market cap and volume recorded at
+/// the same time.
+fn snapshots_from_chart(chart: &CoinMarketData) -> Vec<PriceSnapshot> {
+    let figures_at = |points: &[Vec<f64>]| -> HashMap<i64, f64> {
+        points
+            .iter()
+            .filter_map(|point| Some((*point.first()? as i64, *point.get(1)?)))
+            .collect()
+    };
+    let market_caps = figures_at(&chart.market_caps);
+    let total_volumes = figures_at(&chart.total_volumes);
+
+    chart
+        .prices
+        .iter()
+        .filter_map(|point| {
+            let timestamp = *point.first()? as i64;
+            Some(PriceSnapshot {
+                captured_at: point_time(point)?,
+                price: *point.get(1)?,
+                market_cap: market_caps.get(&timestamp).copied(),
+                total_volume: total_volumes.get(&timestamp).copied(),
+            })
+        })
+        .collect()
+}
+
+/// Daily candles of a chart's prices. A day's volume is the 24-hour volume
+/// of its last point. The first day is left out, as the chart starts in the
+/// middle of it.
+fn candles_from_chart(chart: &CoinMarketData) -> Vec<DailyCandle> {
+    let mut candles: BTreeMap<chrono::NaiveDate, DailyCandle> = BTreeMap::new();
+    for point in &chart.prices {
+        let (Some(time), Some(&price)) = (point_time(point), point.get(1)) else {
+            continue;
+        };
+
+        candles
+            .entry(time.date_naive())
+            .and_modify(|candle| {
+                candle.high = candle.high.max(price);
+                candle.low = candle.low.min(price);
+                candle.close = price;
+            })
+            .or_insert(DailyCandle {
+                day: time.date_naive(),
+                open: price,
+                high: price,
+                low: price,
+                close: price,
+                volume: 0.0,
+            });
+    }
+
+    for point in &chart.total_volumes {
+        let (Some(time), Some(&volume)) = (point_time(point), point.get(1)) else {
+            continue;
+        };
+        if let Some(candle) = candles.get_mut(&time.date_naive()) {
+            candle.volume = volume;
+        }
+    }
+
+    candles.pop_first();
+    candles.into_values().collect()
+}
@@ -1,8 +1,20 @@
+mod account;
+mod admin;
 mod answer;
+mod api_tokens;
+mod audit;
+mod comment;
 mod crypto;
 mod general;
+mod market_history;
+mod moderation;
 mod question;
+mod render;
+mod revision;
+mod search;
 mod tag;
+mod two_factor;
 mod users;
+mod vote;
 
 pub use general::Store;
@@ -0,0 +1,228 @@
+use sqlx::{Postgres, QueryBuilder, Row};
+
+impl crate::store::Store {
+    /// Writes a moderator action to the moderation log. Must run in the
+    /// transaction that performed the action so neither happens without the other.
+    #[tracing::instrument(name = "record_moderation_action_in_db", skip(transaction))]
+    pub async fn record_moderation_action_in_db(
+        &self,
+        transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        moderator_id: uuid::Uuid,
+        action: crate::models::ModerationActionKind,
+        question_id: Option<uuid::Uuid>,
+        answer_id: Option<uuid::Uuid>,
+        reason: Option<String>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO moderation_actions (moderator, action, question, answer, reason) VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(moderator_id)
+        .bind(action.as_str())
+        .bind(question_id)
+        .bind(answer_id)
+        .bind(reason)
+        .execute(&mut **transaction)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Closes a question to new answers, or reopens it.
+    #[tracing::instrument(name = "set_question_closed_in_db")]
+    pub async fn set_question_closed_in_db(
+        &self,
+        moderator_id: uuid::Uuid,
+        question_id: uuid::Uuid,
+        closed: bool,
+        reason: Option<String>,
+    ) -> Result<crate::models::QuestionAuthorWithTags, sqlx::Error> {
+        let mut transaction = self.connection.begin().await?;
+
+        // Only a state change counts, so closing twice is a `RowNotFound`
+        let query = if closed {
+            sqlx::query(
+                "UPDATE questions SET closed_at = NOW(), closed_by = $2, close_reason = $3 WHERE id = $1 AND closed_at IS NULL RETURNING id",
+            )
+            .bind(question_id)
+            .bind(moderator_id)
+            .bind(&reason)
+        } else {
+            sqlx::query(
+                "UPDATE questions SET closed_at = NULL, closed_by = NULL, close_reason = NULL WHERE id = $1 AND closed_at IS NOT NULL RETURNING id",
+            )
+            .bind(question_id)
+        };
+        query.fetch_one(&mut *transaction).await?;
+
+        let action = if closed {
+            crate::models::ModerationActionKind::CloseQuestion
+        } else {
+            crate::models::ModerationActionKind::ReopenQuestion
+        };
+        self.record_moderation_action_in_db(
+            &mut transaction,
+            moderator_id,
+            action,
+            Some(question_id),
+            None,
+            reason,
+        )
+        .await?;
+
+        let question = self
+            .get_question_from_db(Some(&mut transaction), question_id)
+            .await?;
+
+        transaction.commit().await?;
+
+        Ok(question)
+    }
+
+    /// Locks or unlocks a question, together with its answers and comments.
+    #[tracing::instrument(name = "set_question_locked_in_db")]
+    pub async fn set_question_locked_in_db(
+        &self,
+        moderator_id: uuid::Uuid,
+        question_id: uuid::Uuid,
+        locked: bool,
+        reason: Option<String>,
+    ) -> Result<crate::models::QuestionAuthorWithTags, sqlx::Error> {
+        let mut transaction = self.connection.begin().await?;
+
+        let query = if locked {
+            sqlx::query(
+                "UPDATE questions SET locked_at = NOW(), locked_by = $2 WHERE id = $1 AND locked_at IS NULL RETURNING id",
+            )
+            .bind(question_id)
+            .bind(moderator_id)
+        } else {
+            sqlx::query(
+                "UPDATE questions SET locked_at = NULL, locked_by = NULL WHERE id = $1 AND locked_at IS NOT NULL RETURNING id",
+            )
+            .bind(question_id)
+        };
+        query.fetch_one(&mut *transaction).await?;
+
+        let action = if locked {
+            crate::models::ModerationActionKind::LockQuestion
+        } else {
+            crate::models::ModerationActionKind::UnlockQuestion
+        };
+        self.record_moderation_action_in_db(
+            &mut transaction,
+            moderator_id,
+            action,
+            Some(question_id),
+            None,
+            reason,
+        )
+        .await?;
+
+        let question = self
+            .get_question_from_db(Some(&mut transaction), question_id)
+            .await?;
+
+        transaction.commit().await?;
+
+        Ok(question)
+    }
+
+    /// Locks or unlocks a single answer and its comments.
+    #[tracing::instrument(name = "set_answer_locked_in_db")]
+    pub async fn set_answer_locked_in_db(
+        &self,
+        moderator_id: uuid::Uuid,
+        answer_id: uuid::Uuid,
+        locked: bool,
+        reason: Option<String>,
+    ) -> Result<crate::models::AnswerAuthor, sqlx::Error> {
+        let mut transaction = self.connection.begin().await?;
+
+        let query = if locked {
+            sqlx::query(
+                "UPDATE answers SET locked_at = NOW(), locked_by = $2 WHERE id = $1 AND locked_at IS NULL RETURNING question",
+            )
+            .bind(answer_id)
+            .bind(moderator_id)
+        } else {
+            sqlx::query(
+                "UPDATE answers SET locked_at = NULL, locked_by = NULL WHERE id = $1 AND locked_at IS NOT NULL RETURNING question",
+            )
+            .bind(answer_id)
+        };
+        let question_id: uuid::Uuid = query.fetch_one(&mut *transaction).await?.get("question");
+
+        let action = if locked {
+            crate::models::ModerationActionKind::LockAnswer
+        } else {
+            crate::models::ModerationActionKind::UnlockAnswer
+        };
+        self.record_moderation_action_in_db(
+            &mut transaction,
+            moderator_id,
+            action,
+            Some(question_id),
+            Some(answer_id),
+            reason,
+        )
+        .await?;
+
+        let answer = self
+            .get_an_answer_from_db(Some(&mut transaction), answer_id)
+            .await?;
+
+        transaction.commit().await?;
+
+        Ok(answer)
+    }
+
+    #[tracing::instrument(name = "get_moderation_actions_from_db", skip(self))]
+    pub async fn get_moderation_actions_from_db(
+        &self,
+        log_query: &crate::models::ModerationLogQuery,
+        pagination: crate::utils::Pagination,
+    ) -> Result<(Vec<crate::models::ModerationAction>, i64), sqlx::Error> {
+        let mut count_query =
+            QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM moderation_actions m");
+        push_moderation_filters(&mut count_query, log_query);
+        let total: i64 = count_query
+            .build()
+            .map(|row: sqlx::postgres::PgRow| -> i64 { row.get(0) })
+            .fetch_one(&self.connection)
+            .await?;
+
+        let mut query = QueryBuilder::<Postgres>::new(
+            "SELECT m.id, m.moderator, m.action, m.question, m.answer, m.reason, m.created_at FROM moderation_actions m",
+        );
+        push_moderation_filters(&mut query, log_query);
+        query.push(" ORDER BY m.created_at DESC, m.id LIMIT ");
+        query.push_bind(pagination.limit());
+        query.push(" OFFSET ");
+        query.push_bind(pagination.offset());
+
+        let actions = query
+            .build_query_as::<crate::models::ModerationAction>()
+            .fetch_all(&self.connection)
+            .await?;
+
+        Ok((actions, total))
+    }
+}
+
+/// Appends the `WHERE` clause shared by the moderation log and its count.
+fn push_moderation_filters(
+    builder: &mut QueryBuilder<'_, Postgres>,
+    log_query: &crate::models::ModerationLogQuery,
+) {
+    builder.push(" WHERE TRUE");
+
+    if let Some(moderator) = log_query.moderator {
+        builder.push(" AND m.moderator = ");
+        builder.push_bind(moderator);
+    }
+
+    if let Some(question) = log_query.question {
+        builder.push(" AND m.question = ");
+        builder.push_bind(question);
+    }
+}
@@ -1,5 +1,4 @@
-use sqlx::Row;
-use std::collections::HashMap;
+use sqlx::{Postgres, QueryBuilder, Row};
 
 impl crate::store::Store {
     #[tracing::instrument(name = "get_question_from_db", skip(question_id))]
@@ -18,28 +17,8 @@ impl crate::store::Store {
             None => query.fetch_one(&self.connection).await?,
         };
 
-        let question_author_with_tags = crate::models::QuestionAuthorWithTags {
-            id: result.id,
-            title: result.title,
-            slug: result.slug,
-            content: result.content,
-            raw_content: result.raw_content,
-            author: crate::models::UserVisible {
-                id: result.user_id,
-                email: result.user_email,
-                first_name: result.user_first_name,
-                last_name: result.user_last_name,
-                is_active: result.user_is_active,
-                is_staff: result.user_is_staff,
-                is_superuser: result.user_is_superuser,
-                thumbnail: result.user_thumbnail,
-                date_joined: result.user_date_joined,
-            },
-            created_at: result.created_at,
-            updated_at: result.updated_at,
-            tags: serde_json::from_value(result.tags_json)
-                .map_err(|e| sqlx::Error::Protocol(e.to_string().into()))?,
-        };
+        let question_author_with_tags = crate::models::QuestionAuthorWithTags::try_from(result)
+            .map_err(|e| sqlx::Error::Protocol(e.to_string()))?;
 
         Ok(question_author_with_tags)
     }
@@ -57,7 +36,7 @@ impl crate::store::Store {
         .bind(&create_question.slug)
         .bind(&create_question.content)
         .bind(&create_question.raw_content)
-        .bind(&create_question.author).map(|row: sqlx::postgres::PgRow| -> uuid::Uuid { row.get("id") })
+        .bind(create_question.author).map(|row: sqlx::postgres::PgRow| -> uuid::Uuid { row.get("id") })
         .fetch_one(&mut *transaction)
         .await {
             Ok(id) => id,
@@ -76,6 +55,15 @@ impl crate::store::Store {
             Err(e) => return Err(e),
         }
 
+        self.record_question_revision_in_db(
+            &mut transaction,
+            q_id,
+            create_question.author,
+            &create_question,
+            None,
+        )
+        .await?;
+
         let question_author_with_tags = self
             .get_question_from_db(Some(&mut transaction), q_id)
             .await?;
@@ -85,23 +73,29 @@ impl crate::store::Store {
         Ok(question_author_with_tags)
     }
 
-    #[tracing::instrument(name = "update_question_in_db", skip(update_question))]
+    /// Saves a new version of a question. `update_question.author` is the
+    /// editor, who must be the question's author unless `moderated` is set,
+    /// in which case the edit is also written to the moderation log.
+    #[tracing::instrument(name = "update_question_in_db", skip(update_question, edit_summary))]
     pub async fn update_question_in_db(
         &self,
         question_id: uuid::Uuid,
         update_question: crate::models::CreateQuestion,
+        edit_summary: Option<String>,
+        moderated: bool,
     ) -> Result<crate::models::QuestionAuthorWithTags, sqlx::Error> {
         let mut transaction = self.connection.begin().await?;
 
         let q_id = match sqlx::query(
-            "UPDATE questions SET title = $1, slug = $2, content = $3, raw_content = $4 WHERE id = $5 AND author = $6 RETURNING id",
+            "UPDATE questions SET title = $1, slug = $2, content = $3, raw_content = $4 WHERE id = $5 AND (author = $6 OR $7) RETURNING id",
         )
         .bind(&update_question.title)
         .bind(&update_question.slug)
         .bind(&update_question.content)
         .bind(&update_question.raw_content)
         .bind(question_id)
-        .bind(&update_question.author)
+        .bind(update_question.author)
+        .bind(moderated)
         .map(|row: sqlx::postgres::PgRow| -> uuid::Uuid { row.get("id") })
         .fetch_one(&mut *transaction)
         .await {
@@ -132,6 +126,27 @@ impl crate::store::Store {
             Err(e) => return Err(e),
         }
 
+        if moderated {
+            self.record_moderation_action_in_db(
+                &mut transaction,
+                update_question.author,
+                crate::models::ModerationActionKind::EditQuestion,
+                Some(q_id),
+                None,
+                edit_summary.clone(),
+            )
+            .await?;
+        }
+
+        self.record_question_revision_in_db(
+            &mut transaction,
+            q_id,
+            update_question.author,
+            &update_question,
+            edit_summary,
+        )
+        .await?;
+
         let question_author_with_tags = self
             .get_question_from_db(Some(&mut transaction), q_id)
             .await?;
@@ -141,73 +156,135 @@ impl crate::store::Store {
         Ok(question_author_with_tags)
     }
 
-    #[tracing::instrument(name = "get_all_questions_from_db")]
-    pub async fn get_all_questions_from_db(
+    #[tracing::instrument(name = "get_questions_from_db", skip(self))]
+    pub async fn get_questions_from_db(
         &self,
-    ) -> Result<Vec<crate::models::QuestionAuthorWithTags>, sqlx::Error> {
-        let results = sqlx::query_as::<_, crate::models::QuestionAuthorWithTagsQueryResult>(
-            crate::utils::QUESTION_AUTHOR_WITH_TAGS_QUERY_ALL,
-        )
-        .fetch_all(&self.connection)
-        .await?;
+        list_query: &crate::models::QuestionListQuery,
+        pagination: crate::utils::Pagination,
+    ) -> Result<(Vec<crate::models::QuestionAuthorWithTags>, i64), sqlx::Error> {
+        let mut count_query = QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM questions q");
+        push_question_filters(&mut count_query, list_query);
+        let total: i64 = count_query
+            .build()
+            .map(|row: sqlx::postgres::PgRow| -> i64 { row.get(0) })
+            .fetch_one(&self.connection)
+            .await?;
 
-        let mut questions_map: HashMap<uuid::Uuid, crate::models::QuestionAuthorWithTags> =
-            HashMap::new();
-
-        for result in results {
-            let tags: Vec<crate::models::Tag> = serde_json::from_value(result.tags_json)
-                .map_err(|e| sqlx::Error::Protocol(e.to_string().into()))?;
-
-            questions_map
-                .entry(result.id)
-                .or_insert(crate::models::QuestionAuthorWithTags {
-                    id: result.id,
-                    title: result.title,
-                    slug: result.slug,
-                    content: result.content,
-                    raw_content: result.raw_content,
-                    author: crate::models::UserVisible {
-                        id: result.user_id,
-                        email: result.user_email,
-                        first_name: result.user_first_name,
-                        last_name: result.user_last_name,
-                        is_active: result.user_is_active,
-                        is_staff: result.user_is_staff,
-                        is_superuser: result.user_is_superuser,
-                        thumbnail: result.user_thumbnail,
-                        date_joined: result.user_date_joined,
-                    },
-                    created_at: result.created_at,
-                    updated_at: result.updated_at,
-                    tags,
-                });
-        }
+        let mut query =
+            QueryBuilder::<Postgres>::new(crate::utils::QUESTION_AUTHOR_WITH_TAGS_QUERY_LIST);
+        push_question_filters(&mut query, list_query);
+        query.push(" GROUP BY q.id, u.id ORDER BY ");
+        query.push(match list_query.sort {
+            crate::models::QuestionSort::Newest => "q.created_at DESC",
+            crate::models::QuestionSort::RecentlyUpdated => "q.updated_at DESC",
+            crate::models::QuestionSort::MostAnswered => "answers_count DESC, q.created_at DESC",
+            crate::models::QuestionSort::HighestScore => "q.score DESC, q.created_at DESC",
+        });
+        // Tie-break on the primary key so pages are stable
+        query.push(", q.id LIMIT ");
+        query.push_bind(pagination.limit());
+        query.push(" OFFSET ");
+        query.push_bind(pagination.offset());
 
-        Ok(questions_map.into_values().collect())
+        let results = query
+            .build_query_as::<crate::models::QuestionAuthorWithTagsQueryResult>()
+            .fetch_all(&self.connection)
+            .await?;
+
+        let questions = results
+            .into_iter()
+            .map(crate::models::QuestionAuthorWithTags::try_from)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| sqlx::Error::Protocol(e.to_string()))?;
+
+        Ok((questions, total))
     }
 
+    /// Deletes a question on behalf of its author or, when `moderated` is
+    /// set, of a moderator, recording the latter in the moderation log.
     #[tracing::instrument(name = "delete_question_from_db")]
     pub async fn delete_question_from_db(
         &self,
-        author_id: uuid::Uuid,
+        user_id: uuid::Uuid,
         question_id: uuid::Uuid,
+        moderated: bool,
+        reason: Option<String>,
     ) -> Result<(), sqlx::Error> {
+        let mut transaction = self.connection.begin().await?;
+
         let deleted =
-            sqlx::query("DELETE FROM questions WHERE id = $1 AND author = $2 RETURNING id")
+            sqlx::query("DELETE FROM questions WHERE id = $1 AND (author = $2 OR $3) RETURNING id")
                 .bind(question_id)
-                .bind(author_id)
-                .fetch_optional(&self.connection)
+                .bind(user_id)
+                .bind(moderated)
+                .fetch_optional(&mut *transaction)
                 .await?;
 
         if deleted.is_none() {
             tracing::warn!(
                 "Attempt to delete question with id {} by non-author {}",
                 question_id,
-                author_id
+                user_id
             );
             return Err(sqlx::Error::RowNotFound);
         }
 
+        if moderated {
+            self.record_moderation_action_in_db(
+                &mut transaction,
+                user_id,
+                crate::models::ModerationActionKind::DeleteQuestion,
+                Some(question_id),
+                None,
+                reason,
+            )
+            .await?;
+        }
+
+        transaction.commit().await?;
+
         Ok(())
     }
 }
+
+/// Appends the `WHERE` clause shared by the questions listing and its count.
+/// Tag filtering goes through a subquery so the joined tags stay complete.
+fn push_question_filters(
+    builder: &mut QueryBuilder<'_, Postgres>,
+    list_query: &crate::models::QuestionListQuery,
+) {
+    builder.push(" WHERE TRUE");
+
+    if let Some(tag) = &list_query.tag {
+        builder.push(
+            " AND EXISTS (SELECT 1 FROM question_tags fqt WHERE fqt.question = q.id AND fqt.tag = ",
+        );
+        builder.push_bind(tag.clone());
+        builder.push(")");
+    }
+
+    if let Some(author) = list_query.author {
+        builder.push(" AND q.author = ");
+        builder.push_bind(author);
+    }
+
+    match list_query.unanswered {
+        Some(true) => {
+            builder.push(" AND NOT EXISTS (SELECT 1 FROM answers fa WHERE fa.question = q.id)");
+        }
+        Some(false) => {
+            builder.push(" AND EXISTS (SELECT 1 FROM answers fa WHERE fa.question = q.id)");
+        }
+        None => {}
+    }
+
+    match list_query.resolved {
+        Some(true) => {
+            builder.push(" AND q.accepted_answer IS NOT NULL");
+        }
+        Some(false) => {
+            builder.push(" AND q.accepted_answer IS NULL");
+        }
+        None => {}
+    }
+}
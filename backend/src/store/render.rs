@@ -0,0 +1,72 @@
+use sqlx::Row;
+
+/// Tables whose `content` column holds markdown rendered from `raw_content`.
+const RENDERED_TABLES: [&str; 3] = ["questions", "answers", "comments"];
+
+/// Rows re-rendered per round trip.
+const BATCH_SIZE: i64 = 200;
+
+impl crate::store::Store {
+    /// Re-renders the stored HTML of every question, answer and comment from
+    /// its raw markdown so content written before a renderer or sanitizer
+    /// change gets the current treatment. Returns the number of rows updated
+    /// per table.
+    #[tracing::instrument(name = "rerender_content_in_db", skip(self))]
+    pub async fn rerender_content_in_db(&self) -> Result<Vec<(&'static str, u64)>, sqlx::Error> {
+        let mut report = Vec::with_capacity(RENDERED_TABLES.len());
+        for table in RENDERED_TABLES {
+            report.push((table, self.rerender_table_in_db(table).await?));
+        }
+        Ok(report)
+    }
+
+    /// Walks `table` in primary key order, one batch per transaction, and
+    /// only writes the rows whose rendered HTML actually changed and whose
+    /// markdown is still the one that was rendered.
+    async fn rerender_table_in_db(&self, table: &str) -> Result<u64, sqlx::Error> {
+        let mut updated = 0;
+        let mut last_id: Option<uuid::Uuid> = None;
+
+        loop {
+            let rows = sqlx::query(&format!(
+                "SELECT id, content, raw_content FROM {} WHERE $1::UUID IS NULL OR id > $1 ORDER BY id LIMIT $2",
+                table
+            ))
+            .bind(last_id)
+            .bind(BATCH_SIZE)
+            .map(|row: sqlx::postgres::PgRow| -> (uuid::Uuid, String, String) {
+                (row.get("id"), row.get("content"), row.get("raw_content"))
+            })
+            .fetch_all(&self.connection)
+            .await?;
+
+            let Some((id, _, _)) = rows.last() else {
+                break;
+            };
+            last_id = Some(*id);
+
+            let mut transaction = self.connection.begin().await?;
+            for (id, content, raw_content) in rows {
+                let rendered = crate::utils::convert_markdown_to_html(&raw_content).await;
+                if rendered == content {
+                    continue;
+                }
+                // Skip rows edited since they were read, which already hold
+                // HTML rendered from their new markdown
+                updated += sqlx::query(&format!(
+                    "UPDATE {} SET content = $1 WHERE id = $2 AND raw_content = $3",
+                    table
+                ))
+                .bind(rendered)
+                .bind(id)
+                .bind(raw_content)
+                .execute(&mut *transaction)
+                .await?
+                .rows_affected();
+            }
+            transaction.commit().await?;
+        }
+
+        Ok(updated)
+    }
+}
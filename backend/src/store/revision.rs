@@ -0,0 +1,106 @@
+impl crate::store::Store {
+    /// Appends the next revision of a question. Must run in the transaction
+    /// that wrote the question so the snapshot matches what was stored.
+    #[tracing::instrument(name = "record_question_revision_in_db", skip(transaction, snapshot))]
+    pub async fn record_question_revision_in_db(
+        &self,
+        transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        question_id: uuid::Uuid,
+        editor_id: uuid::Uuid,
+        snapshot: &crate::models::CreateQuestion,
+        edit_summary: Option<String>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO question_revisions (question, revision, editor, title, tags, raw_content, edit_summary) SELECT $1, COALESCE(MAX(revision), 0) + 1, $2, $3, $4, $5, $6 FROM question_revisions WHERE question = $1",
+        )
+        .bind(question_id)
+        .bind(editor_id)
+        .bind(&snapshot.title)
+        .bind(&snapshot.tags)
+        .bind(&snapshot.raw_content)
+        .bind(edit_summary)
+        .execute(&mut **transaction)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Answer counterpart of `record_question_revision_in_db`.
+    #[tracing::instrument(name = "record_answer_revision_in_db", skip(transaction, raw_content))]
+    pub async fn record_answer_revision_in_db(
+        &self,
+        transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        answer_id: uuid::Uuid,
+        editor_id: uuid::Uuid,
+        raw_content: &str,
+        edit_summary: Option<String>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO answer_revisions (answer, revision, editor, raw_content, edit_summary) SELECT $1, COALESCE(MAX(revision), 0) + 1, $2, $3, $4 FROM answer_revisions WHERE answer = $1",
+        )
+        .bind(answer_id)
+        .bind(editor_id)
+        .bind(raw_content)
+        .bind(edit_summary)
+        .execute(&mut **transaction)
+        .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "get_question_revisions_from_db")]
+    pub async fn get_question_revisions_from_db(
+        &self,
+        question_id: uuid::Uuid,
+    ) -> Result<Vec<crate::models::QuestionRevision>, sqlx::Error> {
+        sqlx::query_as::<_, crate::models::QuestionRevision>(
+            "SELECT id, question, revision, editor, title, tags, raw_content, edit_summary, created_at FROM question_revisions WHERE question = $1 ORDER BY revision DESC",
+        )
+        .bind(question_id)
+        .fetch_all(&self.connection)
+        .await
+    }
+
+    #[tracing::instrument(name = "get_question_revision_from_db")]
+    pub async fn get_question_revision_from_db(
+        &self,
+        question_id: uuid::Uuid,
+        revision: i32,
+    ) -> Result<crate::models::QuestionRevision, sqlx::Error> {
+        sqlx::query_as::<_, crate::models::QuestionRevision>(
+            "SELECT id, question, revision, editor, title, tags, raw_content, edit_summary, created_at FROM question_revisions WHERE question = $1 AND revision = $2",
+        )
+        .bind(question_id)
+        .bind(revision)
+        .fetch_one(&self.connection)
+        .await
+    }
+
+    #[tracing::instrument(name = "get_answer_revisions_from_db")]
+    pub async fn get_answer_revisions_from_db(
+        &self,
+        answer_id: uuid::Uuid,
+    ) -> Result<Vec<crate::models::AnswerRevision>, sqlx::Error> {
+        sqlx::query_as::<_, crate::models::AnswerRevision>(
+            "SELECT id, answer, revision, editor, raw_content, edit_summary, created_at FROM answer_revisions WHERE answer = $1 ORDER BY revision DESC",
+        )
+        .bind(answer_id)
+        .fetch_all(&self.connection)
+        .await
+    }
+
+    #[tracing::instrument(name = "get_answer_revision_from_db")]
+    pub async fn get_answer_revision_from_db(
+        &self,
+        answer_id: uuid::Uuid,
+        revision: i32,
+    ) -> Result<crate::models::AnswerRevision, sqlx::Error> {
+        sqlx::query_as::<_, crate::models::AnswerRevision>(
+            "SELECT id, answer, revision, editor, raw_content, edit_summary, created_at FROM answer_revisions WHERE answer = $1 AND revision = $2",
+        )
+        .bind(answer_id)
+        .bind(revision)
+        .fetch_one(&self.connection)
+        .await
+    }
+}
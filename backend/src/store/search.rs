@@ -0,0 +1,138 @@
+use sqlx::{Postgres, QueryBuilder, Row};
+use std::collections::HashMap;
+
+impl crate::store::Store {
+    /// Ranks questions against `search`, matching their title and body as well
+    /// as the bodies of their answers. An answer match counts for half as much
+    /// as a match on the question itself.
+    #[tracing::instrument(name = "search_questions_in_db", skip(self))]
+    pub async fn search_questions_in_db(
+        &self,
+        search: &crate::models::SearchQuery,
+        pagination: crate::utils::Pagination,
+    ) -> Result<(Vec<crate::models::SearchHit>, i64), sqlx::Error> {
+        let has_text = !search.text.is_empty();
+
+        let mut count_query = QueryBuilder::<Postgres>::new("");
+        push_search_cte(&mut count_query, search, has_text);
+        count_query.push("SELECT COUNT(*)");
+        push_search_from(&mut count_query, search, has_text);
+        let total: i64 = count_query
+            .build()
+            .map(|row: sqlx::postgres::PgRow| -> i64 { row.get(0) })
+            .fetch_one(&self.connection)
+            .await?;
+
+        let mut query = QueryBuilder::<Postgres>::new("");
+        push_search_cte(&mut query, search, has_text);
+        if has_text {
+            query.push("SELECT q.id, (ts_rank(q.search_vector, s.query) + COALESCE(best_answer.rank, 0) * 0.5)::REAL AS rank, ts_headline('english', q.title, s.query, ");
+            query.push_bind(crate::utils::headline_options(false));
+            query.push(") AS title_highlight, ts_headline('english', q.raw_content, s.query, ");
+            query.push_bind(crate::utils::headline_options(true));
+            query.push(") AS snippet, (SELECT ts_headline('english', a.raw_content, s.query, ");
+            query.push_bind(crate::utils::headline_options(true));
+            query.push(") FROM answers a WHERE a.id = best_answer.id) AS answer_snippet");
+        } else {
+            query.push("SELECT q.id, 0::REAL AS rank, q.title AS title_highlight, LEFT(q.raw_content, 200) AS snippet, NULL::TEXT AS answer_snippet");
+        }
+        push_search_from(&mut query, search, has_text);
+        if has_text {
+            query.push(" ORDER BY rank DESC, q.created_at DESC, q.id");
+        } else {
+            query.push(" ORDER BY q.created_at DESC, q.id");
+        }
+        query.push(" LIMIT ");
+        query.push_bind(pagination.limit());
+        query.push(" OFFSET ");
+        query.push_bind(pagination.offset());
+
+        let matches = query
+            .build_query_as::<crate::models::SearchMatchQueryResult>()
+            .fetch_all(&self.connection)
+            .await?;
+
+        if matches.is_empty() {
+            return Ok((Vec::new(), total));
+        }
+
+        let ids: Vec<uuid::Uuid> = matches.iter().map(|m| m.id).collect();
+        let mut questions_query =
+            QueryBuilder::<Postgres>::new(crate::utils::QUESTION_AUTHOR_WITH_TAGS_QUERY_LIST);
+        questions_query.push(" WHERE q.id = ANY(");
+        questions_query.push_bind(ids);
+        questions_query.push(") GROUP BY q.id, u.id");
+
+        let mut questions = questions_query
+            .build_query_as::<crate::models::QuestionAuthorWithTagsQueryResult>()
+            .fetch_all(&self.connection)
+            .await?
+            .into_iter()
+            .map(|result| {
+                crate::models::QuestionAuthorWithTags::try_from(result)
+                    .map(|question| (question.id, question))
+            })
+            .collect::<Result<HashMap<_, _>, _>>()
+            .map_err(|e| sqlx::Error::Protocol(e.to_string()))?;
+
+        // Keep the relevance order of the first query
+        let hits = matches
+            .into_iter()
+            .filter_map(|m| {
+                questions
+                    .remove(&m.id)
+                    .map(|question| crate::models::SearchHit {
+                        question,
+                        rank: m.rank,
+                        title_highlight: crate::utils::highlight_to_html(&m.title_highlight),
+                        snippet: crate::utils::highlight_to_html(&m.snippet),
+                        answer_snippet: m
+                            .answer_snippet
+                            .as_deref()
+                            .map(crate::utils::highlight_to_html),
+                    })
+            })
+            .collect();
+
+        Ok((hits, total))
+    }
+}
+
+/// Opens the query with a CTE holding the parsed tsquery so it is parsed once.
+fn push_search_cte(
+    builder: &mut QueryBuilder<'_, Postgres>,
+    search: &crate::models::SearchQuery,
+    has_text: bool,
+) {
+    if has_text {
+        builder.push("WITH s AS (SELECT websearch_to_tsquery('english', ");
+        builder.push_bind(search.text.clone());
+        builder.push(") AS query) ");
+    }
+}
+
+/// Appends the `FROM` and `WHERE` clauses shared by the search and its count.
+fn push_search_from(
+    builder: &mut QueryBuilder<'_, Postgres>,
+    search: &crate::models::SearchQuery,
+    has_text: bool,
+) {
+    if has_text {
+        builder.push(" FROM questions q CROSS JOIN s LEFT JOIN LATERAL (SELECT a.id, ts_rank(a.search_vector, s.query) AS rank FROM answers a WHERE a.question = q.id AND a.search_vector @@ s.query ORDER BY rank DESC LIMIT 1) best_answer ON TRUE WHERE (q.search_vector @@ s.query OR best_answer.id IS NOT NULL)");
+    } else {
+        builder.push(" FROM questions q WHERE TRUE");
+    }
+
+    for tag in &search.tags {
+        builder.push(" AND EXISTS (SELECT 1 FROM question_tags fqt JOIN tags ft ON fqt.tag = ft.id WHERE fqt.question = q.id AND (ft.id = ");
+        builder.push_bind(tag.clone());
+        builder.push(" OR LOWER(ft.symbol) = ");
+        builder.push_bind(tag.clone());
+        builder.push("))");
+    }
+
+    if let Some(author) = search.author {
+        builder.push(" AND q.author = ");
+        builder.push_bind(author);
+    }
+}
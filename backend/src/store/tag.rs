@@ -22,7 +22,7 @@ impl crate::store::Store {
             return Err(sqlx::Error::RowNotFound);
         }
         let rows = sqlx::query("SELECT id FROM tags WHERE id = ANY($1)")
-            .bind(&tag_ids)
+            .bind(tag_ids)
             .fetch_all(&self.connection)
             .await?;
 
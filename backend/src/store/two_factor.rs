@@ -0,0 +1,105 @@
+use sqlx::Row;
+
+impl crate::store::Store {
+    /// The TOTP secret of `user_id` if 2FA is on for the account.
+    #[tracing::instrument(name = "get_totp_secret_from_db", skip(self))]
+    pub async fn get_totp_secret_from_db(
+        &self,
+        user_id: uuid::Uuid,
+    ) -> Result<Option<String>, sqlx::Error> {
+        sqlx::query("SELECT totp_secret FROM users WHERE id = $1")
+            .bind(user_id)
+            .map(|row: sqlx::postgres::PgRow| -> Option<String> { row.get("totp_secret") })
+            .fetch_one(&self.connection)
+            .await
+    }
+
+    #[tracing::instrument(name = "count_unused_recovery_codes_in_db", skip(self))]
+    pub async fn count_unused_recovery_codes_in_db(
+        &self,
+        user_id: uuid::Uuid,
+    ) -> Result<i64, sqlx::Error> {
+        sqlx::query(
+            "SELECT COUNT(*) AS remaining FROM user_recovery_codes WHERE user_id = $1 AND used_at IS NULL",
+        )
+        .bind(user_id)
+        .map(|row: sqlx::postgres::PgRow| -> i64 { row.get("remaining") })
+        .fetch_one(&self.connection)
+        .await
+    }
+
+    /// Turns 2FA on with `secret`, replacing any earlier recovery codes.
+    #[tracing::instrument(
+        name = "enable_two_factor_in_db",
+        skip(self, secret, recovery_code_hashes)
+    )]
+    pub async fn enable_two_factor_in_db(
+        &self,
+        user_id: uuid::Uuid,
+        secret: &str,
+        recovery_code_hashes: &[String],
+    ) -> Result<(), sqlx::Error> {
+        let mut transaction = self.connection.begin().await?;
+
+        sqlx::query("UPDATE users SET totp_secret = $2 WHERE id = $1 AND is_active = true")
+            .bind(user_id)
+            .bind(secret)
+            .execute(&mut *transaction)
+            .await?;
+
+        sqlx::query("DELETE FROM user_recovery_codes WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        sqlx::query(
+            "INSERT INTO user_recovery_codes (user_id, code_hash) SELECT $1, UNNEST($2::TEXT[])",
+        )
+        .bind(user_id)
+        .bind(recovery_code_hashes)
+        .execute(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "disable_two_factor_in_db", skip(self))]
+    pub async fn disable_two_factor_in_db(&self, user_id: uuid::Uuid) -> Result<(), sqlx::Error> {
+        let mut transaction = self.connection.begin().await?;
+
+        sqlx::query("UPDATE users SET totp_secret = NULL WHERE id = $1")
+            .bind(user_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        sqlx::query("DELETE FROM user_recovery_codes WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    /// Marks the matching unused recovery code as used. Returns whether there
+    /// was one, so each code works exactly once even under concurrent use.
+    #[tracing::instrument(name = "use_recovery_code_in_db", skip(self, code_hash))]
+    pub async fn use_recovery_code_in_db(
+        &self,
+        user_id: uuid::Uuid,
+        code_hash: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE user_recovery_codes SET used_at = NOW() WHERE user_id = $1 AND code_hash = $2 AND used_at IS NULL",
+        )
+        .bind(user_id)
+        .bind(code_hash)
+        .execute(&self.connection)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+}
@@ -49,6 +49,24 @@ impl crate::store::Store {
         .await
     }
 
+    #[tracing::instrument(name = "get_inactive_user_by_email", fields(user_email = email))]
+    pub async fn get_inactive_user_by_email(
+        &self,
+        email: &str,
+    ) -> Result<crate::models::UserVisible, sqlx::Error> {
+        sqlx::query_as::<_, crate::models::UserVisible>(
+            r#"
+        SELECT
+            id, email, first_name, last_name, is_active, is_staff, is_superuser, thumbnail, date_joined
+        FROM users
+        WHERE LOWER(email) = LOWER($1) AND is_active = false AND deactivated_at IS NULL
+        "#,
+        )
+        .bind(email)
+        .fetch_one(&self.connection)
+        .await
+    }
+
     #[tracing::instrument(name = "create_user", skip(password), fields(user_first_name = first_name, user_last_name = last_name, user_email = email))]
     pub async fn create_user(
         &self,
@@ -73,16 +91,109 @@ impl crate::store::Store {
     .await
     }
 
+    /// The suspension currently in force on `user_id`, if any.
+    #[tracing::instrument(name = "get_user_suspension_from_db", skip(self))]
+    pub async fn get_user_suspension_from_db(
+        &self,
+        user_id: uuid::Uuid,
+    ) -> Result<Option<crate::models::UserSuspension>, sqlx::Error> {
+        sqlx::query(
+            "SELECT suspended_until, suspension_reason FROM users WHERE id = $1 AND suspended_until > NOW()",
+        )
+        .bind(user_id)
+        .map(|row: sqlx::postgres::PgRow| crate::models::UserSuspension {
+            until: row.get("suspended_until"),
+            reason: row
+                .get::<Option<String>, _>("suspension_reason")
+                .unwrap_or_default(),
+        })
+        .fetch_optional(&self.connection)
+        .await
+    }
+
     #[tracing::instrument(name = "activate_user", fields(user_id = id.to_string()))]
     pub async fn activate_user(&self, id: &uuid::Uuid) -> Result<(), sqlx::Error> {
         sqlx::query(
             r#"
         UPDATE users
         SET is_active = true
-        WHERE id = $1
+        WHERE id = $1 AND deactivated_at IS NULL
+        "#,
+        )
+        .bind(id)
+        .execute(&self.connection)
+        .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "update_user_password", skip(password), fields(user_id = id.to_string()))]
+    pub async fn update_user_password(
+        &self,
+        id: &uuid::Uuid,
+        password: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+        UPDATE users
+        SET password = $2
+        WHERE id = $1 AND is_active = true
         "#,
         )
         .bind(id)
+        .bind(password)
+        .execute(&self.connection)
+        .await?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "update_user_profile", fields(user_id = id.to_string()))]
+    pub async fn update_user_profile(
+        &self,
+        id: &uuid::Uuid,
+        profile: &crate::models::UpdateProfile,
+    ) -> Result<crate::models::UserVisible, sqlx::Error> {
+        sqlx::query_as::<_, crate::models::UserVisible>(
+            r#"
+        UPDATE users
+        SET
+            first_name = COALESCE($2, first_name),
+            last_name = COALESCE($3, last_name),
+            thumbnail = CASE WHEN $4::TEXT IS NULL THEN thumbnail ELSE NULLIF($4, '') END
+        WHERE id = $1 AND is_active = true
+        RETURNING
+            id, email, first_name, last_name, is_active, is_staff, is_superuser, thumbnail, date_joined
+        "#,
+        )
+        .bind(id)
+        .bind(&profile.first_name)
+        .bind(&profile.last_name)
+        .bind(&profile.thumbnail)
+        .fetch_one(&self.connection)
+        .await
+    }
+
+    #[tracing::instrument(name = "email_is_taken", fields(user_email = email))]
+    pub async fn email_is_taken(&self, email: &str) -> Result<bool, sqlx::Error> {
+        sqlx::query("SELECT EXISTS(SELECT 1 FROM users WHERE email = $1) AS taken")
+            .bind(email)
+            .map(|row: sqlx::postgres::PgRow| -> bool { row.get("taken") })
+            .fetch_one(&self.connection)
+            .await
+    }
+
+    #[tracing::instrument(name = "update_user_email", fields(user_id = id.to_string(), user_email = email))]
+    pub async fn update_user_email(&self, id: &uuid::Uuid, email: &str) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+        UPDATE users
+        SET email = $2
+        WHERE id = $1 AND is_active = true
+        "#,
+        )
+        .bind(id)
+        .bind(email)
         .execute(&self.connection)
         .await?;
 
@@ -93,7 +204,7 @@ impl crate::store::Store {
     pub async fn create_super_user_in_db(&self, settings: &crate::settings::Settings) {
         let new_super_user = crate::models::NewUser {
             email: settings.superuser.email.clone(),
-            password: crate::utils::hash_password(&settings.superuser.password.as_bytes()).await,
+            password: crate::utils::hash_password(settings.superuser.password.as_bytes()).await,
             first_name: settings.superuser.first_name.clone(),
             last_name: settings.superuser.last_name.clone(),
         };
@@ -128,4 +239,32 @@ impl crate::store::Store {
             }
         };
     }
+
+    /// The tags of the questions a user asked or answered, most used first.
+    #[tracing::instrument(name = "get_top_tags_from_db", skip(self))]
+    pub async fn get_top_tags_from_db(
+        &self,
+        user_id: uuid::Uuid,
+        limit: i64,
+    ) -> Result<Vec<crate::models::TopTag>, sqlx::Error> {
+        sqlx::query_as::<_, crate::models::TopTag>(
+            r#"
+        SELECT t.id, t.name, t.image, COUNT(*) AS posts
+        FROM (
+            SELECT id AS question FROM questions WHERE author = $1
+            UNION ALL
+            SELECT question FROM answers WHERE author = $1
+        ) p
+        JOIN question_tags qt ON qt.question = p.question
+        JOIN tags t ON t.id = qt.tag
+        GROUP BY t.id
+        ORDER BY posts DESC, t.id
+        LIMIT $2
+        "#,
+        )
+        .bind(user_id)
+        .bind(limit)
+        .fetch_all(&self.connection)
+        .await
+    }
 }
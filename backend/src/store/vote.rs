@@ -0,0 +1,124 @@
+use sqlx::Row;
+
+/// The kind of post a vote is cast on.
+#[derive(Debug, Clone, Copy)]
+enum VoteTarget {
+    Question,
+    Answer,
+}
+
+impl VoteTarget {
+    fn post_table(&self) -> &'static str {
+        match self {
+            VoteTarget::Question => "questions",
+            VoteTarget::Answer => "answers",
+        }
+    }
+
+    fn vote_table(&self) -> &'static str {
+        match self {
+            VoteTarget::Question => "question_votes",
+            VoteTarget::Answer => "answer_votes",
+        }
+    }
+
+    fn vote_column(&self) -> &'static str {
+        match self {
+            VoteTarget::Question => "question",
+            VoteTarget::Answer => "answer",
+        }
+    }
+}
+
+impl crate::store::Store {
+    #[tracing::instrument(name = "vote_on_question_in_db", skip(self))]
+    pub async fn vote_on_question_in_db(
+        &self,
+        question_id: uuid::Uuid,
+        voter_id: uuid::Uuid,
+        value: Option<i16>,
+    ) -> Result<crate::models::VoteSummary, sqlx::Error> {
+        self.cast_vote_in_db(VoteTarget::Question, question_id, voter_id, value)
+            .await
+    }
+
+    #[tracing::instrument(name = "vote_on_answer_in_db", skip(self))]
+    pub async fn vote_on_answer_in_db(
+        &self,
+        answer_id: uuid::Uuid,
+        voter_id: uuid::Uuid,
+        value: Option<i16>,
+    ) -> Result<crate::models::VoteSummary, sqlx::Error> {
+        self.cast_vote_in_db(VoteTarget::Answer, answer_id, voter_id, value)
+            .await
+    }
+
+    /// Records (`Some`) or retracts (`None`) `voter_id`'s vote on a post and
+    /// recomputes its score. The post row is locked for the duration of the
+    /// transaction so concurrent votes on the same post are serialized.
+    /// Authors never match the lock query, so voting on one's own post
+    /// yields `sqlx::Error::RowNotFound`.
+    async fn cast_vote_in_db(
+        &self,
+        target: VoteTarget,
+        post_id: uuid::Uuid,
+        voter_id: uuid::Uuid,
+        value: Option<i16>,
+    ) -> Result<crate::models::VoteSummary, sqlx::Error> {
+        let mut transaction = self.connection.begin().await?;
+
+        sqlx::query(&format!(
+            "SELECT id FROM {} WHERE id = $1 AND author <> $2 FOR UPDATE",
+            target.post_table()
+        ))
+        .bind(post_id)
+        .bind(voter_id)
+        .fetch_one(&mut *transaction)
+        .await?;
+
+        match value {
+            Some(value) => {
+                sqlx::query(&format!(
+                    "INSERT INTO {table} ({column}, voter, value) VALUES ($1, $2, $3) ON CONFLICT ({column}, voter) DO UPDATE SET value = EXCLUDED.value",
+                    table = target.vote_table(),
+                    column = target.vote_column()
+                ))
+                .bind(post_id)
+                .bind(voter_id)
+                .bind(value)
+                .execute(&mut *transaction)
+                .await?;
+            }
+            None => {
+                sqlx::query(&format!(
+                    "DELETE FROM {} WHERE {} = $1 AND voter = $2",
+                    target.vote_table(),
+                    target.vote_column()
+                ))
+                .bind(post_id)
+                .bind(voter_id)
+                .execute(&mut *transaction)
+                .await?;
+            }
+        }
+
+        let score = sqlx::query(&format!(
+            "UPDATE {post_table} SET score = (SELECT COALESCE(SUM(value), 0)::INT FROM {vote_table} WHERE {column} = $1) WHERE id = $1 RETURNING score",
+            post_table = target.post_table(),
+            vote_table = target.vote_table(),
+            column = target.vote_column()
+        ))
+        .bind(post_id)
+        .map(|row: sqlx::postgres::PgRow| -> i32 { row.get("score") })
+        .fetch_one(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+
+        Ok(crate::models::VoteSummary {
+            id: post_id,
+            score,
+            vote: value,
+        })
+    }
+}
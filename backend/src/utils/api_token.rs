@@ -0,0 +1,32 @@
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use sha2::{Digest, Sha256};
+
+/// Marks our tokens so that they are easy to spot, e.g. by secret scanners.
+const API_TOKEN_PREFIX: &str = "cft_";
+
+/// Characters of the token kept in clear to tell tokens apart.
+const VISIBLE_TOKEN_LENGTH: usize = 12;
+
+pub fn hash_api_token(token: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(token.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Returns a new token, the part of it shown in listings and its hash.
+pub fn generate_api_token() -> (String, String, String) {
+    let mut token_bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut token_bytes);
+    let token = format!(
+        "{}{}",
+        API_TOKEN_PREFIX,
+        token_bytes
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>()
+    );
+
+    let visible_part = token[..VISIBLE_TOKEN_LENGTH].to_string();
+    let token_hash = hash_api_token(&token);
+    (token, visible_part, token_hash)
+}
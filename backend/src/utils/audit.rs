@@ -0,0 +1,63 @@
+use crate::{
+    models::{AuditEvent, AuditTarget, NewAuditEntry},
+    utils::ClientInfo,
+};
+use sha2::{Digest, Sha256};
+
+/// Refers to an email address that belongs to no account without keeping it,
+/// so that repeated attempts against it can still be told apart.
+pub fn audit_email_hash(email: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(email.trim().to_lowercase().as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Appends an event to the audit log. The action it describes has already
+/// happened by the time it is recorded, so a failure is logged rather than
+/// turned into an error for the client.
+#[tracing::instrument(name = "record_audit_event", skip(db_store, client, payload))]
+pub async fn record_audit_event(
+    db_store: &crate::store::Store,
+    event: AuditEvent,
+    actor: Option<uuid::Uuid>,
+    target: Option<AuditTarget>,
+    client: &ClientInfo,
+    payload: serde_json::Value,
+) {
+    let entry = NewAuditEntry {
+        event,
+        actor,
+        target,
+        ip_address: client.ip_address.clone(),
+        user_agent: client.user_agent.clone(),
+        payload,
+    };
+
+    if let Err(e) = db_store.insert_audit_entry_in_db(&entry).await {
+        tracing::error!(
+            "Failed to record {} in the audit log: {}",
+            event.as_str(),
+            e
+        );
+    }
+}
+
+/// Records a moderator acting on somebody else's post.
+pub async fn record_moderation_audit_event(
+    db_store: &crate::store::Store,
+    moderator_id: uuid::Uuid,
+    action: crate::models::ModerationActionKind,
+    target: AuditTarget,
+    client: &ClientInfo,
+    reason: Option<&str>,
+) {
+    record_audit_event(
+        db_store,
+        action.into(),
+        Some(moderator_id),
+        Some(target),
+        client,
+        serde_json::json!({ "action": action.as_str(), "reason": reason }),
+    )
+    .await;
+}
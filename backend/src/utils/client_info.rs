@@ -0,0 +1,59 @@
+use crate::startup::AppState;
+use axum::{
+    extract::{ConnectInfo, FromRequestParts},
+    http::{header, request::Parts},
+};
+use std::net::SocketAddr;
+
+/// Longest user agent kept with a session.
+const MAX_USER_AGENT_LENGTH: usize = 512;
+
+/// Where a request came from, as far as the backend can tell.
+#[derive(Debug, Clone, Default)]
+pub struct ClientInfo {
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+#[axum::async_trait]
+impl FromRequestParts<AppState> for ClientInfo {
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        // Only the right-most address was appended by our proxy; whatever
+        // precedes it came from the client and can be anything
+        let forwarded_for = state
+            .trust_proxy_headers
+            .then(|| {
+                parts
+                    .headers
+                    .get("x-forwarded-for")
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| value.rsplit(',').next())
+                    .and_then(|ip| ip.trim().parse::<std::net::IpAddr>().ok())
+                    .map(|ip| ip.to_string())
+            })
+            .flatten();
+
+        let ip_address = forwarded_for.or_else(|| {
+            parts
+                .extensions
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(address)| address.ip().to_string())
+        });
+
+        let user_agent = parts
+            .headers
+            .get(header::USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.chars().take(MAX_USER_AGENT_LENGTH).collect());
+
+        Ok(ClientInfo {
+            ip_address,
+            user_agent,
+        })
+    }
+}
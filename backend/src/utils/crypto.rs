@@ -1,39 +1,86 @@
-use reqwest;
-use serde_json::Value;
+use crate::{
+    market_data::{is_valid_coin_id, CacheStatus, MarketDataCache, PriceOptions, Quote},
+    utils::{CustomAppError, ErrorContext},
+};
 use std::collections::HashMap;
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct CryptoPrice {
     name: String,
-    price: f64,
+    /// Keyed by lowercase currency code.
+    prices: HashMap<String, Quote>,
 }
 
 pub type CryptoPrices = Vec<CryptoPrice>;
 
-#[tracing::instrument(name = "get_crypto_prices")]
+/// Splits a comma-separated list, dropping blanks and repeats.
+fn split_list(list: &str) -> Vec<String> {
+    let mut items: Vec<String> = Vec::new();
+    for item in list.split(',').map(|s| s.trim().to_lowercase()) {
+        if !item.is_empty() && !items.contains(&item) {
+            items.push(item);
+        }
+    }
+    items
+}
+
+/// Prices of the comma-separated coins `cryptos` in each of the
+/// comma-separated `vs_currencies`, in the order the coins were asked for. At
+/// most `max_coins` coins can be asked for at once.
+#[tracing::instrument(name = "get_crypto_prices", skip(market_data))]
 pub async fn get_crypto_prices(
-    cryptos: String,
-    currency: &str,
-) -> Result<CryptoPrices, reqwest::Error> {
-    let settings = crate::settings::get_settings().expect("Failed to get settings");
-    let url = format!(
-        "{}/simple/price?ids={}&vs_currencies={}",
-        settings.coingecko.api_url,
-        cryptos,
-        currency.to_lowercase()
-    );
-
-    let response: HashMap<String, Value> = reqwest::get(&url)
-        .await?
-        .json::<HashMap<String, Value>>()
-        .await?;
+    market_data: &MarketDataCache,
+    cryptos: &str,
+    vs_currencies: &str,
+    options: PriceOptions,
+    max_coins: usize,
+) -> Result<(CryptoPrices, CacheStatus), CustomAppError> {
+    let ids = split_list(cryptos);
+    let vs_currencies = split_list(vs_currencies);
 
-    let mut prices = CryptoPrices::new();
-    for (name, data) in response {
-        if let Some(price) = data.get("usd").and_then(|v| v.as_f64()) {
-            prices.push(CryptoPrice { name, price });
-        }
+    if ids.is_empty() || vs_currencies.is_empty() {
+        return Err(CustomAppError::from((
+            "At least one coin and one currency are required".to_string(),
+            ErrorContext::BadRequest,
+        )));
+    }
+    if let Some(id) = ids.iter().find(|id| !is_valid_coin_id(id)) {
+        return Err(CustomAppError::from((
+            format!("Invalid coin id: {}", id),
+            ErrorContext::BadRequest,
+        )));
     }
+    if ids.len() > max_coins {
+        return Err(CustomAppError::from((
+            format!("At most {} coins can be priced at once", max_coins),
+            ErrorContext::BadRequest,
+        )));
+    }
+
+    let (supported, _) = market_data.supported_vs_currencies().await?;
+    let unsupported: Vec<&str> = vs_currencies
+        .iter()
+        .filter(|currency| !supported.contains(currency))
+        .map(String::as_str)
+        .collect();
+    if !unsupported.is_empty() {
+        return Err(CustomAppError::from((
+            format!("Unsupported currencies: {}", unsupported.join(", ")),
+            ErrorContext::BadRequest,
+        )));
+    }
+
+    let (mut response, cache_status) = market_data
+        .simple_prices(&ids, &vs_currencies, options)
+        .await?;
+
+    let prices = ids
+        .into_iter()
+        .filter_map(|name| {
+            let prices = response.remove(&name)?;
+            Some(CryptoPrice { name, prices })
+        })
+        .collect();
 
-    Ok(prices)
+    Ok((prices, cache_status))
 }
@@ -0,0 +1,89 @@
+use crate::{
+    startup::AppState,
+    utils::{Credentials, CustomAppError, ErrorContext},
+};
+use axum::{
+    extract::{Request, State},
+    http::Method,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+/// Header carrying the token handed out by `GET /api/users/csrf-token`.
+pub const CSRF_HEADER: &str = "x-csrf-token";
+
+/// Compares in constant time so the token cannot be guessed byte by byte.
+fn tokens_match(expected: &str, provided: &str) -> bool {
+    expected.len() == provided.len()
+        && expected
+            .bytes()
+            .zip(provided.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+fn csrf_error() -> CustomAppError {
+    CustomAppError::from((
+        "Missing or invalid CSRF token".to_string(),
+        ErrorContext::Forbidden,
+    ))
+}
+
+/// Requires the session's CSRF token on every unsafe request authenticated
+/// by the `sessionid` cookie. Bearer requests are not sent by browsers on
+/// their own, so they carry no CSRF risk and are let through, as are
+/// requests whose cookie points to no live session.
+#[tracing::instrument(name = "verify_csrf_token", skip(credentials, state, req, next))]
+pub async fn verify_csrf_token(
+    credentials: Credentials,
+    State(state): State<AppState>,
+    req: Request,
+    next: Next,
+) -> Result<impl IntoResponse, Response> {
+    let is_safe_method = matches!(
+        *credentials.method(),
+        Method::GET | Method::HEAD | Method::OPTIONS
+    );
+    if is_safe_method || credentials.uses_api_token() {
+        return Ok(next.run(req).await);
+    }
+
+    let Some(session_id) = credentials.session_id() else {
+        return Ok(next.run(req).await);
+    };
+
+    let mut redis_con = state.redis_store.get().await.map_err(|_| {
+        CustomAppError::from((
+            "Failed to get redis connection".to_string(),
+            ErrorContext::InternalServerError,
+        ))
+        .into_response()
+    })?;
+
+    let expected = match crate::utils::stored_csrf_token(&mut *redis_con, &session_id).await {
+        Ok(Some(expected)) => expected,
+        Ok(None) => {
+            let session_exists: bool = bb8_redis::redis::cmd("EXISTS")
+                .arg(&session_id)
+                .query_async(&mut *redis_con)
+                .await
+                .unwrap_or(true);
+            if !session_exists {
+                return Ok(next.run(req).await);
+            }
+            return Err(csrf_error().into_response());
+        }
+        Err(error) => return Err(error.into_response()),
+    };
+
+    let provided = req
+        .headers()
+        .get(CSRF_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    if !tokens_match(&expected, provided) {
+        return Err(csrf_error().into_response());
+    }
+
+    Ok(next.run(req).await)
+}
@@ -107,48 +107,57 @@ pub async fn send_multipart_email(
     issued_token: String,
 ) -> Result<(), String> {
     let settings = crate::settings::get_settings().expect("Unable to load settings.");
-    let title = subject.clone();
 
     let now = chrono::Local::now();
     let expiration_time = now + chrono::Duration::minutes(settings.secret.token_expiration);
     let exact_time = expiration_time.format("%A %B %d, %Y at %r").to_string();
 
-    let template = state.env.get_template(template_name).unwrap();
     let ctx = minijinja::context! {
-        title => &title,
-        user_id => &user.id,
-        domain => &settings.frontend_url,
         token => &issued_token,
         expiration_time => &settings.secret.token_expiration,
         exact_time => &exact_time,
     };
-    let html_text = template.render(ctx).unwrap();
-
-    let text = format!(
-        r#"
-        Thanks for signing up for a CryptoFlow with Rust (axum) and SvelteKit. We're excited to have you on board! 
-
-        For future reference, your user ID number is {}.
-
-        Please visit {}/users/activate/{} and input the token below to activate your account:
-
-        {}
 
+    send_templated_email(subject, user, &state, template_name, ctx).await
+}
 
-        Please note that this is a one-time use token and it will expire in {} minutes ({}).
-
+/// Renders `template_name` and its plain-text twin with `ctx` (plus `title`,
+/// `user_id` and `domain`) and sends the result to `user` in the background.
+#[tracing::instrument(
+    name = "Templated e-mail sending function.",
+    skip(user, state, ctx),
+    fields(
+        recipient_user_id = %user.id,
+        recipient_email = %user.email
+    )
+)]
+pub async fn send_templated_email(
+    subject: String,
+    user: crate::models::UserVisible,
+    state: &crate::startup::AppState,
+    template_name: &str,
+    ctx: minijinja::Value,
+) -> Result<(), String> {
+    let settings = crate::settings::get_settings().expect("Unable to load settings.");
 
-        Thanks,
+    let ctx = minijinja::context! {
+        title => &subject,
+        user_id => &user.id,
+        domain => &settings.frontend_url,
+        ..ctx
+    };
 
-        CryptoFlow with Rust (axum) and SvelteKit Team 
-        "#,
-        user.id,
-        settings.frontend_url,
-        user.id,
-        issued_token,
-        settings.secret.token_expiration,
-        exact_time
-    );
+    let template = state.env.get_template(template_name).unwrap();
+    let html_text = template.render(&ctx).unwrap();
+
+    // Every HTML template has a plain-text twin next to it
+    let text_template_name = template_name.replace(".html", ".txt");
+    let text = state
+        .env
+        .get_template(&text_template_name)
+        .unwrap()
+        .render(&ctx)
+        .unwrap();
 
     tokio::spawn(send_email(user, subject, html_text, text));
     Ok(())
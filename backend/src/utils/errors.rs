@@ -10,6 +10,7 @@ use serde::Serialize;
 
 pub enum ErrorContext {
     UnauthorizedAccess,
+    Forbidden,
     InternalServerError,
     BadRequest,
     NotFound,
@@ -22,9 +23,15 @@ pub enum CustomAppError {
     RedisError(bb8_redis::redis::RedisError),
     UUIDError(uuid::Error),
     Unauthorized(String),
+    Forbidden(String),
     InternalError(String),
     BadRequest(String),
     NotFound(String),
+    /// The client has to wait `retry_after` seconds before trying again.
+    TooManyRequests {
+        message: String,
+        retry_after: u64,
+    },
     ReqwestError(reqwest::Error),
 }
 
@@ -38,6 +45,11 @@ impl IntoResponse for CustomAppError {
             status_code: u16,
         }
 
+        let retry_after = match &self {
+            CustomAppError::TooManyRequests { retry_after, .. } => Some(*retry_after),
+            _ => None,
+        };
+
         let (status, message) = match self {
             CustomAppError::JsonRejection(rejection) => {
                 // This error is caused by bad user input so don't log it
@@ -144,6 +156,10 @@ impl IntoResponse for CustomAppError {
                 tracing::warn!("Unauthorized access: {}", error_message);
                 (StatusCode::UNAUTHORIZED, error_message)
             }
+            CustomAppError::Forbidden(error_message) => {
+                tracing::warn!("Forbidden: {}", error_message);
+                (StatusCode::FORBIDDEN, error_message)
+            }
             CustomAppError::InternalError(error_message) => {
                 tracing::error!("Internal server error: {}", error_message);
                 (StatusCode::INTERNAL_SERVER_ERROR, error_message)
@@ -156,6 +172,10 @@ impl IntoResponse for CustomAppError {
                 tracing::warn!("Not found: {}", error_message);
                 (StatusCode::NOT_FOUND, error_message)
             }
+            CustomAppError::TooManyRequests { message, .. } => {
+                tracing::warn!("Too many requests: {}", message);
+                (StatusCode::TOO_MANY_REQUESTS, message)
+            }
             CustomAppError::ReqwestError(error) => {
                 tracing::error!("HTTP request error: {}", error);
                 let status_inner = if error.is_timeout() {
@@ -169,14 +189,22 @@ impl IntoResponse for CustomAppError {
             }
         };
 
-        (
+        let mut response = (
             status,
             CustomAppJson(ErrorResponse {
                 message,
                 status_code: status.as_u16(),
             }),
         )
-            .into_response()
+            .into_response();
+
+        if let Some(retry_after) = retry_after {
+            response
+                .headers_mut()
+                .insert(axum::http::header::RETRY_AFTER, retry_after.into());
+        }
+
+        response
     }
 }
 
@@ -220,6 +248,7 @@ impl From<(String, ErrorContext)> for CustomAppError {
     fn from((message, context): (String, ErrorContext)) -> Self {
         match context {
             ErrorContext::UnauthorizedAccess => CustomAppError::Unauthorized(message),
+            ErrorContext::Forbidden => CustomAppError::Forbidden(message),
             ErrorContext::InternalServerError => CustomAppError::InternalError(message),
             ErrorContext::BadRequest => CustomAppError::BadRequest(message),
             ErrorContext::NotFound => CustomAppError::NotFound(message),
@@ -0,0 +1,249 @@
+use crate::utils::{CustomAppError, ErrorContext};
+use bb8_redis::bb8;
+
+/// What failed logins are counted against.
+#[derive(Debug, Clone, Copy)]
+enum Subject {
+    Email,
+    Ip,
+}
+
+impl Subject {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Subject::Email => "email",
+            Subject::Ip => "ip",
+        }
+    }
+}
+
+fn failures_key(subject: Subject, value: &str) -> String {
+    format!("login_failures:{}:{}", subject.as_str(), value)
+}
+
+fn backoff_key(subject: Subject, value: &str) -> String {
+    format!("login_backoff:{}:{}", subject.as_str(), value)
+}
+
+fn lockout_key(subject: Subject, value: &str) -> String {
+    format!("login_lockout:{}:{}", subject.as_str(), value)
+}
+
+/// Email addresses are counted case-insensitively.
+fn subjects(email: &str, ip_address: Option<&str>) -> Vec<(Subject, String)> {
+    let mut subjects = vec![(Subject::Email, email.trim().to_lowercase())];
+    if let Some(ip_address) = ip_address {
+        subjects.push((Subject::Ip, ip_address.to_string()));
+    }
+    subjects
+}
+
+/// Seconds to wait after the `failures`-th failure in a row.
+fn backoff_seconds(failures: i64, settings: &crate::settings::LoginThrottleSettings) -> i64 {
+    settings
+        .base_backoff_seconds
+        .saturating_mul(1 << (failures - 1).clamp(0, 30))
+        .min(settings.max_backoff_seconds)
+        .max(1)
+}
+
+fn redis_error(message: &str) -> CustomAppError {
+    CustomAppError::from((message.to_string(), ErrorContext::InternalServerError))
+}
+
+fn read_settings() -> Result<crate::settings::LoginThrottleSettings, CustomAppError> {
+    crate::settings::get_settings()
+        .map(|settings| settings.login_throttle)
+        .map_err(|_| {
+            CustomAppError::from((
+                "Failed to read settings".to_string(),
+                ErrorContext::InternalServerError,
+            ))
+        })
+}
+
+/// Rejects the login attempt with a 429 while the email address or client IP
+/// is locked out or still backing off from earlier failures.
+#[tracing::instrument(name = "check_login_throttle", skip(redis_store))]
+pub async fn check_login_throttle(
+    redis_store: &bb8::Pool<bb8_redis::RedisConnectionManager>,
+    email: &str,
+    ip_address: Option<&str>,
+) -> Result<(), CustomAppError> {
+    let mut redis_con = redis_store
+        .get()
+        .await
+        .map_err(|_| redis_error("Failed to get redis connection"))?;
+
+    let mut pipe = bb8_redis::redis::pipe();
+    for (subject, value) in subjects(email, ip_address) {
+        pipe.cmd("PTTL").arg(lockout_key(subject, &value));
+        pipe.cmd("PTTL").arg(backoff_key(subject, &value));
+    }
+    let ttls: Vec<i64> = pipe
+        .query_async(&mut *redis_con)
+        .await
+        .map_err(|_| redis_error("Failed to check login attempts"))?;
+
+    // Missing keys report a negative TTL
+    let wait_ms = ttls.into_iter().max().unwrap_or(0);
+    if wait_ms <= 0 {
+        return Ok(());
+    }
+
+    let retry_after = (wait_ms as u64).div_ceil(1000);
+    Err(CustomAppError::TooManyRequests {
+        message: format!(
+            "Too many failed login attempts. Please try again in {} seconds.",
+            retry_after
+        ),
+        retry_after,
+    })
+}
+
+/// Counts a failed login against the email address and client IP, making
+/// them wait exponentially longer before the next attempt and locking them
+/// out once they reach their limit. Returns whether this failure just locked
+/// the email address.
+#[tracing::instrument(name = "record_failed_login", skip(redis_store))]
+pub async fn record_failed_login(
+    redis_store: &bb8::Pool<bb8_redis::RedisConnectionManager>,
+    email: &str,
+    ip_address: Option<&str>,
+) -> Result<bool, CustomAppError> {
+    let settings = read_settings()?;
+    let mut redis_con = redis_store
+        .get()
+        .await
+        .map_err(|_| redis_error("Failed to get redis connection"))?;
+
+    let mut email_locked = false;
+    for (subject, value) in subjects(email, ip_address) {
+        let (failures,): (i64,) = bb8_redis::redis::pipe()
+            .cmd("INCR")
+            .arg(failures_key(subject, &value))
+            .cmd("EXPIRE")
+            .arg(failures_key(subject, &value))
+            .arg(settings.failure_window_minutes * 60)
+            .ignore()
+            .query_async(&mut *redis_con)
+            .await
+            .map_err(|_| redis_error("Failed to record login attempt"))?;
+
+        let max_failures = match subject {
+            Subject::Email => settings.max_failures_per_email,
+            Subject::Ip => settings.max_failures_per_ip,
+        };
+
+        if failures >= max_failures {
+            // Start over once the lockout is served
+            let (newly_locked,): (Option<String>,) = bb8_redis::redis::pipe()
+                .cmd("SET")
+                .arg(lockout_key(subject, &value))
+                .arg(1)
+                .arg("EX")
+                .arg(settings.lockout_minutes * 60)
+                .arg("NX")
+                .cmd("DEL")
+                .arg(failures_key(subject, &value))
+                .arg(backoff_key(subject, &value))
+                .ignore()
+                .query_async(&mut *redis_con)
+                .await
+                .map_err(|_| redis_error("Failed to record login attempt"))?;
+            tracing::warn!("Locked out {} after {} failed logins", value, failures);
+            email_locked |= matches!(subject, Subject::Email) && newly_locked.is_some();
+        } else {
+            bb8_redis::redis::cmd("SET")
+                .arg(backoff_key(subject, &value))
+                .arg(1)
+                .arg("EX")
+                .arg(backoff_seconds(failures, &settings))
+                .query_async::<_, String>(&mut *redis_con)
+                .await
+                .map_err(|_| redis_error("Failed to record login attempt"))?;
+        }
+    }
+
+    Ok(email_locked)
+}
+
+/// Forgets the failed logins of an email address once its owner got in. The
+/// client IP keeps its count so logging into one's own account cannot be used
+/// to keep guessing others.
+#[tracing::instrument(name = "clear_failed_logins", skip(redis_store))]
+pub async fn clear_failed_logins(
+    redis_store: &bb8::Pool<bb8_redis::RedisConnectionManager>,
+    email: &str,
+) -> Result<(), CustomAppError> {
+    let mut redis_con = redis_store
+        .get()
+        .await
+        .map_err(|_| redis_error("Failed to get redis connection"))?;
+
+    let email = email.trim().to_lowercase();
+    bb8_redis::redis::cmd("DEL")
+        .arg(failures_key(Subject::Email, &email))
+        .arg(backoff_key(Subject::Email, &email))
+        .query_async::<_, i64>(&mut *redis_con)
+        .await
+        .map_err(|_| redis_error("Failed to clear login attempts"))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> crate::settings::LoginThrottleSettings {
+        crate::settings::LoginThrottleSettings {
+            max_failures_per_email: 5,
+            max_failures_per_ip: 20,
+            failure_window_minutes: 15,
+            base_backoff_seconds: 1,
+            max_backoff_seconds: 60,
+            lockout_minutes: 15,
+        }
+    }
+
+    #[test]
+    fn backoff_doubles_with_each_failure() {
+        let waits: Vec<i64> = (1..=6).map(|n| backoff_seconds(n, &settings())).collect();
+        assert_eq!(waits, vec![1, 2, 4, 8, 16, 32]);
+    }
+
+    #[test]
+    fn backoff_is_capped() {
+        assert_eq!(backoff_seconds(7, &settings()), 60);
+        assert_eq!(backoff_seconds(i64::MAX, &settings()), 60);
+    }
+
+    #[test]
+    fn backoff_waits_at_least_a_second() {
+        let settings = crate::settings::LoginThrottleSettings {
+            base_backoff_seconds: 0,
+            ..settings()
+        };
+        assert_eq!(backoff_seconds(0, &settings), 1);
+        assert_eq!(backoff_seconds(3, &settings), 1);
+    }
+
+    #[test]
+    fn emails_are_counted_case_insensitively() {
+        let subjects = subjects(" Alice@Example.COM ", Some("10.0.0.1"));
+        assert_eq!(
+            failures_key(subjects[0].0, &subjects[0].1),
+            "login_failures:email:alice@example.com"
+        );
+        assert_eq!(
+            failures_key(subjects[1].0, &subjects[1].1),
+            "login_failures:ip:10.0.0.1"
+        );
+    }
+
+    #[test]
+    fn logins_without_an_address_count_against_the_email_only() {
+        assert_eq!(subjects("a@b.c", None).len(), 1);
+    }
+}
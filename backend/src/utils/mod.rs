@@ -1,22 +1,55 @@
+mod api_token;
+mod audit;
+mod client_info;
 mod crypto;
+mod csrf;
 mod email;
 mod errors;
+mod login_throttle;
 mod middleware;
+mod pagination;
 mod password;
+mod permissions;
 mod qa;
 mod query_constants;
 mod responses;
+mod search;
+mod session;
+mod suspension;
+mod two_factor;
 mod user;
 
+pub use api_token::{generate_api_token, hash_api_token};
+pub use audit::{audit_email_hash, record_audit_event, record_moderation_audit_event};
+pub use client_info::ClientInfo;
 pub use crypto::{get_crypto_prices, CryptoPrices};
-pub use email::send_multipart_email;
+pub use csrf::{verify_csrf_token, CSRF_HEADER};
+pub use email::{send_multipart_email, send_templated_email};
 pub use errors::{CustomAppError, ErrorContext};
+pub use login_throttle::{check_login_throttle, clear_failed_logins, record_failed_login};
 pub use middleware::validate_authentication_session;
+pub use pagination::{Paginated, Pagination};
 pub use password::{hash_password, verify_password};
-pub use qa::{convert_markdown_to_html, slugify};
+pub use permissions::{AuthenticatedUser, Role};
+pub use qa::{convert_markdown_to_html, diff_markdown, slugify};
 pub use query_constants::{
-    ANSWER_AUTHOR_QUERY, ANSWER_AUTHOR_QUERY_VIA_QUESTION_ID, QUESTION_AUTHOR_WITH_TAGS_QUERY,
-    QUESTION_AUTHOR_WITH_TAGS_QUERY_ALL,
+    ANSWER_AUTHOR_QUERY, ANSWER_AUTHOR_QUERY_VIA_AUTHOR_ID, ANSWER_AUTHOR_QUERY_VIA_QUESTION_ID,
+    COMMENT_AUTHOR_QUERY, COMMENT_AUTHOR_QUERY_VIA_ANSWER_ID, COMMENT_AUTHOR_QUERY_VIA_QUESTION_ID,
+    QUESTION_AUTHOR_WITH_TAGS_QUERY, QUESTION_AUTHOR_WITH_TAGS_QUERY_LIST,
 };
 pub use responses::{CustomAppJson, SuccessResponse};
-pub use user::get_user_id_from_session;
+pub use search::{headline_options, highlight_to_html, parse_search_query};
+pub use session::{
+    create_session, delete_session, get_session_csrf_token, list_user_sessions,
+    revoke_all_user_sessions, revoke_user_session, stored_csrf_token, touch_session,
+};
+pub use suspension::{
+    check_user_suspension, lift_user_suspension, save_user_suspension, suspension_error,
+};
+pub use two_factor::{
+    build_totp, check_two_factor_attempts, create_pending_two_factor_login,
+    finish_pending_two_factor_login, generate_recovery_codes, generate_totp_secret,
+    get_pending_two_factor_login, hash_recovery_code, record_two_factor_failure,
+    verify_second_factor,
+};
+pub use user::{get_user_id_from_session, Credentials};
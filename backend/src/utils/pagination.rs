@@ -0,0 +1,53 @@
+pub const DEFAULT_PAGE_SIZE: i64 = 20;
+pub const MAX_PAGE_SIZE: i64 = 100;
+
+/// Page-based pagination parameters, normalized from user input so that
+/// `page` starts at 1 and `page_size` stays within `1..=MAX_PAGE_SIZE`.
+#[derive(Debug, Clone, Copy)]
+pub struct Pagination {
+    pub page: i64,
+    pub page_size: i64,
+}
+
+impl Pagination {
+    pub fn new(page: Option<i64>, page_size: Option<i64>) -> Self {
+        Self {
+            page: page.unwrap_or(1).max(1),
+            page_size: page_size
+                .unwrap_or(DEFAULT_PAGE_SIZE)
+                .clamp(1, MAX_PAGE_SIZE),
+        }
+    }
+
+    pub fn limit(&self) -> i64 {
+        self.page_size
+    }
+
+    /// Saturates rather than overflows for absurdly large pages, which then
+    /// simply come back empty.
+    pub fn offset(&self) -> i64 {
+        (self.page - 1).saturating_mul(self.page_size)
+    }
+}
+
+/// Response envelope for paginated listings.
+#[derive(serde::Serialize, Debug)]
+pub struct Paginated<T> {
+    pub results: Vec<T>,
+    pub total: i64,
+    pub page: i64,
+    pub page_size: i64,
+    pub total_pages: i64,
+}
+
+impl<T> Paginated<T> {
+    pub fn new(results: Vec<T>, total: i64, pagination: Pagination) -> Self {
+        Self {
+            results,
+            total,
+            page: pagination.page,
+            page_size: pagination.page_size,
+            total_pages: (total + pagination.page_size - 1) / pagination.page_size,
+        }
+    }
+}
@@ -0,0 +1,295 @@
+use crate::{
+    startup::AppState,
+    utils::{CustomAppError, ErrorContext},
+};
+use axum::{extract::FromRequestParts, http::request::Parts};
+
+/// What a user is allowed to do. Every active user authors content, staff
+/// moderate everybody's content and superusers administer the site. Staff
+/// only get their powers once they have turned two-factor authentication on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    Author,
+    Moderator,
+    Admin,
+}
+
+impl Role {
+    /// Roles are cumulative: admins are moderators too.
+    pub fn for_user(user: &crate::models::UserVisible, two_factor_enabled: bool) -> Vec<Role> {
+        let is_admin = two_factor_enabled && user.is_superuser.unwrap_or(false);
+        let is_moderator = is_admin || (two_factor_enabled && user.is_staff.unwrap_or(false));
+
+        let mut roles = vec![Role::Author];
+        if is_moderator {
+            roles.push(Role::Moderator);
+        }
+        if is_admin {
+            roles.push(Role::Admin);
+        }
+        roles
+    }
+}
+
+/// The user behind the current session, along with their roles.
+#[derive(Debug, Clone)]
+pub struct AuthenticatedUser {
+    pub user: crate::models::UserVisible,
+    pub session_id: String,
+    pub roles: Vec<Role>,
+    pub two_factor_enabled: bool,
+}
+
+impl AuthenticatedUser {
+    pub fn id(&self) -> uuid::Uuid {
+        self.user.id
+    }
+
+    pub fn has_role(&self, role: Role) -> bool {
+        self.roles.contains(&role)
+    }
+
+    pub fn require_role(&self, role: Role) -> Result<(), CustomAppError> {
+        if self.has_role(role) {
+            return Ok(());
+        }
+
+        let is_staff =
+            self.user.is_staff.unwrap_or(false) || self.user.is_superuser.unwrap_or(false);
+        let message = match role {
+            Role::Moderator | Role::Admin if is_staff && !self.two_factor_enabled => {
+                "Turn on two-factor authentication to use staff tools"
+            }
+            Role::Author => "You are not allowed to write content",
+            Role::Moderator => "Only moderators can do this",
+            Role::Admin => "Only administrators can do this",
+        };
+        Err(CustomAppError::from((
+            message.to_string(),
+            ErrorContext::Forbidden,
+        )))
+    }
+
+    /// Checks that the user may edit or delete a post written by `author_id`.
+    /// Authors may change their own posts unless a moderator locked them;
+    /// moderators may change any post. Returns whether the change is a
+    /// moderator action, i.e. one on somebody else's post.
+    pub fn authorize_change(
+        &self,
+        author_id: uuid::Uuid,
+        is_locked: bool,
+    ) -> Result<bool, CustomAppError> {
+        let is_author = self.user.id == author_id;
+
+        if self.has_role(Role::Moderator) {
+            return Ok(!is_author);
+        }
+
+        if !is_author {
+            return Err(CustomAppError::from((
+                "You can only change your own posts".to_string(),
+                ErrorContext::Forbidden,
+            )));
+        }
+
+        if is_locked {
+            return Err(CustomAppError::from((
+                "This post has been locked by a moderator".to_string(),
+                ErrorContext::Forbidden,
+            )));
+        }
+
+        Ok(false)
+    }
+
+    /// Checks that the user may add to a post, e.g. answer or comment on it.
+    /// Locked posts only take contributions from moderators.
+    pub fn authorize_contribution(&self, is_locked: bool) -> Result<(), CustomAppError> {
+        if is_locked && !self.has_role(Role::Moderator) {
+            return Err(CustomAppError::from((
+                "This post has been locked by a moderator".to_string(),
+                ErrorContext::Forbidden,
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+#[axum::async_trait]
+impl FromRequestParts<AppState> for AuthenticatedUser {
+    type Rejection = CustomAppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let credentials = crate::utils::Credentials::from_request_parts(parts, state).await?;
+        let (user_id, session_id) =
+            crate::utils::get_user_id_from_session(&credentials, state, false).await?;
+
+        // Deactivated users keep no rights even if their session survived
+        let user: crate::models::UserVisible = match state.db_store.get_user_by_id(user_id).await {
+            Ok(user) => user.into(),
+            Err(sqlx::Error::RowNotFound) => {
+                return Err(CustomAppError::from((
+                    "You are not authorized since you don't seem to have been authenticated"
+                        .to_string(),
+                    ErrorContext::UnauthorizedAccess,
+                )))
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let two_factor_enabled = state
+            .db_store
+            .get_totp_secret_from_db(user_id)
+            .await?
+            .is_some();
+        let roles = Role::for_user(&user, two_factor_enabled);
+
+        Ok(AuthenticatedUser {
+            user,
+            session_id,
+            roles,
+            two_factor_enabled,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(is_staff: bool, is_superuser: bool) -> crate::models::UserVisible {
+        crate::models::UserVisible {
+            id: uuid::Uuid::new_v4(),
+            email: "user@example.com".to_string(),
+            first_name: "Ada".to_string(),
+            last_name: "Lovelace".to_string(),
+            is_active: Some(true),
+            is_staff: Some(is_staff),
+            is_superuser: Some(is_superuser),
+            thumbnail: None,
+            date_joined: chrono::Utc::now(),
+        }
+    }
+
+    fn authenticated(is_staff: bool, is_superuser: bool, two_factor: bool) -> AuthenticatedUser {
+        let user = user(is_staff, is_superuser);
+        AuthenticatedUser {
+            roles: Role::for_user(&user, two_factor),
+            user,
+            session_id: "session".to_string(),
+            two_factor_enabled: two_factor,
+        }
+    }
+
+    fn forbidden_message<T>(result: Result<T, CustomAppError>) -> String {
+        match result {
+            Err(CustomAppError::Forbidden(message)) => message,
+            Err(_) => panic!("expected a forbidden error"),
+            Ok(_) => panic!("expected an error"),
+        }
+    }
+
+    #[test]
+    fn everybody_is_an_author() {
+        assert_eq!(
+            Role::for_user(&user(false, false), false),
+            vec![Role::Author]
+        );
+        assert_eq!(
+            Role::for_user(&user(false, false), true),
+            vec![Role::Author]
+        );
+    }
+
+    #[test]
+    fn staff_moderate_with_two_factor_on() {
+        assert_eq!(
+            Role::for_user(&user(true, false), true),
+            vec![Role::Author, Role::Moderator]
+        );
+        assert_eq!(
+            Role::for_user(&user(true, false), false),
+            vec![Role::Author]
+        );
+    }
+
+    #[test]
+    fn superusers_are_admins_and_moderators_with_two_factor_on() {
+        assert_eq!(
+            Role::for_user(&user(false, true), true),
+            vec![Role::Author, Role::Moderator, Role::Admin]
+        );
+        assert_eq!(Role::for_user(&user(true, true), false), vec![Role::Author]);
+    }
+
+    #[test]
+    fn missing_flags_grant_nothing() {
+        let user = crate::models::UserVisible {
+            is_staff: None,
+            is_superuser: None,
+            ..user(false, false)
+        };
+        assert_eq!(Role::for_user(&user, true), vec![Role::Author]);
+    }
+
+    #[test]
+    fn require_role_asks_staff_for_two_factor() {
+        let message =
+            forbidden_message(authenticated(true, false, false).require_role(Role::Moderator));
+        assert_eq!(
+            message,
+            "Turn on two-factor authentication to use staff tools"
+        );
+
+        let message =
+            forbidden_message(authenticated(false, false, false).require_role(Role::Moderator));
+        assert_eq!(message, "Only moderators can do this");
+
+        let message = forbidden_message(authenticated(true, false, true).require_role(Role::Admin));
+        assert_eq!(message, "Only administrators can do this");
+
+        assert!(authenticated(false, true, true)
+            .require_role(Role::Admin)
+            .is_ok());
+    }
+
+    #[test]
+    fn authors_change_their_own_unlocked_posts() {
+        let author = authenticated(false, false, false);
+        assert!(matches!(
+            author.authorize_change(author.id(), false),
+            Ok(false)
+        ));
+        forbidden_message(author.authorize_change(author.id(), true));
+        forbidden_message(author.authorize_change(uuid::Uuid::new_v4(), false));
+    }
+
+    #[test]
+    fn moderators_change_any_post() {
+        let moderator = authenticated(true, false, true);
+        assert!(matches!(
+            moderator.authorize_change(uuid::Uuid::new_v4(), true),
+            Ok(true)
+        ));
+        assert!(matches!(
+            moderator.authorize_change(moderator.id(), true),
+            Ok(false)
+        ));
+    }
+
+    #[test]
+    fn locked_posts_take_contributions_from_moderators_only() {
+        assert!(authenticated(false, false, false)
+            .authorize_contribution(false)
+            .is_ok());
+        forbidden_message(authenticated(false, false, false).authorize_contribution(true));
+        assert!(authenticated(true, false, true)
+            .authorize_contribution(true)
+            .is_ok());
+    }
+}
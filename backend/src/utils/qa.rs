@@ -1,4 +1,113 @@
 use itertools::Itertools;
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+/// Prefix given to user-supplied `id`s so they cannot clobber page globals.
+const ID_PREFIX: &str = "user-content-";
+
+/// Allowlist applied to every piece of rendered markdown before it is stored.
+/// Only what `pulldown_cmark` itself emits survives, plus a few inline tags
+/// commonly written by hand. Links may only point at http(s), mailto or
+/// relative URLs and all of them get `rel="nofollow noopener"`.
+static SANITIZER: LazyLock<ammonia::Builder<'static>> = LazyLock::new(|| {
+    let mut builder = ammonia::Builder::empty();
+    builder
+        .tags(HashSet::from([
+            "a",
+            "b",
+            "blockquote",
+            "br",
+            "code",
+            "del",
+            "div",
+            "em",
+            "h1",
+            "h2",
+            "h3",
+            "h4",
+            "h5",
+            "h6",
+            "hr",
+            "i",
+            "img",
+            "input",
+            "kbd",
+            "li",
+            "ol",
+            "p",
+            "pre",
+            "strong",
+            "sub",
+            "sup",
+            "table",
+            "tbody",
+            "td",
+            "th",
+            "thead",
+            "tr",
+            "ul",
+        ]))
+        .tag_attributes(
+            [
+                ("a", vec!["href", "title"]),
+                ("img", vec!["src", "alt", "title"]),
+                ("input", vec!["type", "checked", "disabled"]),
+                ("code", vec!["class"]),
+                ("div", vec!["class", "id"]),
+                ("sup", vec!["class"]),
+                ("td", vec!["style"]),
+                ("th", vec!["style"]),
+                ("h1", vec!["id"]),
+                ("h2", vec!["id"]),
+                ("h3", vec!["id"]),
+                ("h4", vec!["id"]),
+                ("h5", vec!["id"]),
+                ("h6", vec!["id"]),
+            ]
+            .into_iter()
+            .map(|(tag, attributes)| (tag, attributes.into_iter().collect()))
+            .collect(),
+        )
+        .url_schemes(HashSet::from(["http", "https", "mailto"]))
+        .link_rel(Some("nofollow noopener"))
+        .id_prefix(Some(ID_PREFIX))
+        .attribute_filter(filter_attribute);
+    builder
+});
+
+/// Narrows the attributes the allowlist lets through down to the values
+/// `pulldown_cmark` produces.
+fn filter_attribute<'u>(element: &str, attribute: &str, value: &'u str) -> Option<Cow<'u, str>> {
+    match (element, attribute) {
+        // Task list items are the only inputs markdown renders
+        ("input", "type") => (value == "checkbox").then_some(value.into()),
+        // Column alignment of tables
+        ("td" | "th", "style") => matches!(
+            value,
+            "text-align: left" | "text-align: center" | "text-align: right"
+        )
+        .then_some(value.into()),
+        // Syntax highlighting hints on code blocks and footnote markup
+        (_, "class") => {
+            let prefix = if element == "code" {
+                "language-"
+            } else {
+                "footnote-"
+            };
+            let classes = value
+                .split_whitespace()
+                .filter(|class| class.starts_with(prefix))
+                .join(" ");
+            (!classes.is_empty()).then_some(classes.into())
+        }
+        // Footnote references point at prefixed ids
+        ("a", "href") if value.starts_with('#') => {
+            Some(format!("#{}{}", ID_PREFIX, &value[1..]).into())
+        }
+        _ => Some(value.into()),
+    }
+}
 
 pub async fn slugify(title: &str) -> String {
     let regex = regex::Regex::new(r#"(?m)[\p{P}\p{S}]"#).unwrap();
@@ -22,5 +131,36 @@ pub async fn convert_markdown_to_html(text: &str) -> String {
     let mut html_output: String = String::with_capacity(text.len() * 3 / 2);
     pulldown_cmark::html::push_html(&mut html_output, parser);
 
-    html_output
+    SANITIZER.clean(&html_output).to_string()
+}
+
+/// Line-based diff between two revisions of a post's raw markdown.
+#[tracing::instrument(name = "Diff revisions", skip(old, new))]
+pub fn diff_markdown(from: i32, to: i32, old: &str, new: &str) -> crate::models::RevisionDiff {
+    let diff = similar::TextDiff::from_lines(old, new);
+
+    let changes = diff
+        .iter_all_changes()
+        .map(|change| crate::models::DiffChange {
+            tag: match change.tag() {
+                similar::ChangeTag::Equal => "equal",
+                similar::ChangeTag::Delete => "delete",
+                similar::ChangeTag::Insert => "insert",
+            }
+            .to_string(),
+            value: change.value().to_string(),
+        })
+        .collect();
+
+    let unified_diff = diff
+        .unified_diff()
+        .header(&format!("revision {}", from), &format!("revision {}", to))
+        .to_string();
+
+    crate::models::RevisionDiff {
+        from,
+        to,
+        unified_diff,
+        changes,
+    }
 }
@@ -7,6 +7,13 @@ SELECT
     q.raw_content,
     q.created_at,
     q.updated_at,
+    q.score,
+    q.accepted_answer,
+    q.closed_at,
+    q.close_reason,
+    q.locked_at,
+    (SELECT COUNT(*) FROM answers a WHERE a.question = q.id) as answers_count,
+    (SELECT COUNT(*) FROM comments c WHERE c.question = q.id) as comments_count,
     JSON_AGG(
         JSON_BUILD_OBJECT(
             'id', t.id,
@@ -17,12 +24,8 @@ SELECT
         )
     ) as tags_json,
     u.id as user_id,
-    u.email as user_email,
     u.first_name as user_first_name,
     u.last_name as user_last_name,
-    u.is_active as user_is_active,
-    u.is_staff as user_is_staff,
-    u.is_superuser as user_is_superuser,
     u.thumbnail as user_thumbnail,
     u.date_joined as user_date_joined
 FROM questions q
@@ -33,7 +36,9 @@ WHERE q.id = $1
 GROUP BY q.id, u.id;
 ";
 
-pub const QUESTION_AUTHOR_WITH_TAGS_QUERY_ALL: &str = "
+/// Listing counterpart of `QUESTION_AUTHOR_WITH_TAGS_QUERY`. Callers append
+/// their own `WHERE`, `GROUP BY q.id, u.id`, ordering and paging clauses.
+pub const QUESTION_AUTHOR_WITH_TAGS_QUERY_LIST: &str = "
 SELECT
     q.id,
     q.title,
@@ -42,6 +47,13 @@ SELECT
     q.raw_content,
     q.created_at,
     q.updated_at,
+    q.score,
+    q.accepted_answer,
+    q.closed_at,
+    q.close_reason,
+    q.locked_at,
+    (SELECT COUNT(*) FROM answers a WHERE a.question = q.id) as answers_count,
+    (SELECT COUNT(*) FROM comments c WHERE c.question = q.id) as comments_count,
     JSON_AGG(
         JSON_BUILD_OBJECT(
             'id', t.id,
@@ -52,19 +64,14 @@ SELECT
         )
     ) as tags_json,
     u.id as user_id,
-    u.email as user_email,
     u.first_name as user_first_name,
     u.last_name as user_last_name,
-    u.is_active as user_is_active,
-    u.is_staff as user_is_staff,
-    u.is_superuser as user_is_superuser,
     u.thumbnail as user_thumbnail,
     u.date_joined as user_date_joined
 FROM questions q
 LEFT JOIN users u ON q.author = u.id
 LEFT JOIN question_tags qt ON q.id = qt.question
 LEFT JOIN tags t ON qt.tag = t.id
-GROUP BY q.id, u.id;
 ";
 
 pub const ANSWER_AUTHOR_QUERY: &str = "
@@ -74,17 +81,18 @@ SELECT
     a.raw_content,
     a.created_at,
     a.updated_at,
+    a.score,
+    COALESCE(q.accepted_answer = a.id, FALSE) as is_accepted,
+    COALESCE(a.locked_at, q.locked_at) as locked_at,
+    (SELECT COUNT(*) FROM comments c WHERE c.answer = a.id) as comments_count,
     u.id as user_id,
-    u.email as user_email,
     u.first_name as user_first_name,
     u.last_name as user_last_name,
-    u.is_active as user_is_active,
-    u.is_staff as user_is_staff,
-    u.is_superuser as user_is_superuser,
     u.thumbnail as user_thumbnail,
     u.date_joined as user_date_joined
 FROM answers a
 LEFT JOIN users u ON a.author = u.id
+LEFT JOIN questions q ON a.question = q.id
 WHERE a.id = $1;
 ";
 
@@ -95,16 +103,107 @@ SELECT
     a.raw_content,
     a.created_at,
     a.updated_at,
+    a.score,
+    COALESCE(q.accepted_answer = a.id, FALSE) as is_accepted,
+    COALESCE(a.locked_at, q.locked_at) as locked_at,
+    (SELECT COUNT(*) FROM comments c WHERE c.answer = a.id) as comments_count,
     u.id as user_id,
-    u.email as user_email,
     u.first_name as user_first_name,
     u.last_name as user_last_name,
-    u.is_active as user_is_active,
-    u.is_staff as user_is_staff,
-    u.is_superuser as user_is_superuser,
     u.thumbnail as user_thumbnail,
     u.date_joined as user_date_joined
 FROM answers a
 LEFT JOIN users u ON a.author = u.id
-WHERE a.question = $1;
+LEFT JOIN questions q ON a.question = q.id
+WHERE a.question = $1
+ORDER BY is_accepted DESC, a.created_at ASC;
+";
+
+pub const ANSWER_AUTHOR_QUERY_VIA_AUTHOR_ID: &str = "
+SELECT
+    a.id,
+    a.content,
+    a.raw_content,
+    a.created_at,
+    a.updated_at,
+    a.score,
+    COALESCE(q.accepted_answer = a.id, FALSE) as is_accepted,
+    COALESCE(a.locked_at, q.locked_at) as locked_at,
+    (SELECT COUNT(*) FROM comments c WHERE c.answer = a.id) as comments_count,
+    u.id as user_id,
+    u.first_name as user_first_name,
+    u.last_name as user_last_name,
+    u.thumbnail as user_thumbnail,
+    u.date_joined as user_date_joined,
+    q.id as question,
+    q.title as question_title,
+    q.slug as question_slug
+FROM answers a
+LEFT JOIN users u ON a.author = u.id
+LEFT JOIN questions q ON a.question = q.id
+WHERE a.author = $1
+ORDER BY a.created_at DESC, a.id
+LIMIT $2 OFFSET $3;
+";
+
+pub const COMMENT_AUTHOR_QUERY: &str = "
+SELECT
+    c.id,
+    c.content,
+    c.raw_content,
+    c.question,
+    c.answer,
+    c.parent,
+    c.created_at,
+    c.updated_at,
+    u.id as user_id,
+    u.first_name as user_first_name,
+    u.last_name as user_last_name,
+    u.thumbnail as user_thumbnail,
+    u.date_joined as user_date_joined
+FROM comments c
+LEFT JOIN users u ON c.author = u.id
+WHERE c.id = $1;
+";
+
+pub const COMMENT_AUTHOR_QUERY_VIA_QUESTION_ID: &str = "
+SELECT
+    c.id,
+    c.content,
+    c.raw_content,
+    c.question,
+    c.answer,
+    c.parent,
+    c.created_at,
+    c.updated_at,
+    u.id as user_id,
+    u.first_name as user_first_name,
+    u.last_name as user_last_name,
+    u.thumbnail as user_thumbnail,
+    u.date_joined as user_date_joined
+FROM comments c
+LEFT JOIN users u ON c.author = u.id
+WHERE c.question = $1
+ORDER BY c.created_at ASC;
+";
+
+pub const COMMENT_AUTHOR_QUERY_VIA_ANSWER_ID: &str = "
+SELECT
+    c.id,
+    c.content,
+    c.raw_content,
+    c.question,
+    c.answer,
+    c.parent,
+    c.created_at,
+    c.updated_at,
+    u.id as user_id,
+    u.first_name as user_first_name,
+    u.last_name as user_last_name,
+    u.thumbnail as user_thumbnail,
+    u.date_joined as user_date_joined
+FROM comments c
+LEFT JOIN users u ON c.author = u.id
+WHERE c.answer = $1
+ORDER BY c.created_at ASC;
 ";
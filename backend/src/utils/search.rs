@@ -0,0 +1,77 @@
+use crate::utils::{CustomAppError, ErrorContext};
+
+/// Private-use characters handed to `ts_headline` as highlight delimiters so
+/// that user content can be HTML-escaped before the `<mark>` tags are added.
+pub const HIGHLIGHT_START: char = '\u{E000}';
+pub const HIGHLIGHT_STOP: char = '\u{E001}';
+
+/// Splits a raw search string into `[tag]` filters, an `author:<uuid>` filter
+/// and the remaining free text, which is handed to `websearch_to_tsquery`
+/// as-is so quoted phrases, `OR` and `-word` keep working.
+#[tracing::instrument(name = "parse_search_query")]
+pub fn parse_search_query(raw: &str) -> Result<crate::models::SearchQuery, CustomAppError> {
+    let tag_regex = regex::Regex::new(r"\[([^\[\]]+)\]").unwrap();
+    let author_regex = regex::Regex::new(r"(?i)\bauthor:(\S+)").unwrap();
+
+    let tags: Vec<String> = tag_regex
+        .captures_iter(raw)
+        .map(|c| c[1].trim().to_lowercase())
+        .filter(|t| !t.is_empty())
+        .collect();
+    let without_tags = tag_regex.replace_all(raw, " ");
+
+    let mut author = None;
+    for captures in author_regex.captures_iter(&without_tags) {
+        author = Some(uuid::Uuid::parse_str(&captures[1]).map_err(|_| {
+            CustomAppError::from((
+                "The author filter expects a user id, e.g. author:<uuid>".to_string(),
+                ErrorContext::BadRequest,
+            ))
+        })?);
+    }
+    let text = author_regex
+        .replace_all(&without_tags, " ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    if text.is_empty() && tags.is_empty() && author.is_none() {
+        return Err(CustomAppError::from((
+            "Search query cannot be empty".to_string(),
+            ErrorContext::BadRequest,
+        )));
+    }
+
+    Ok(crate::models::SearchQuery { text, tags, author })
+}
+
+/// `ts_headline` options shared by every highlighted field.
+pub fn headline_options(fragments: bool) -> String {
+    let base = format!(
+        "StartSel=\"{}\", StopSel=\"{}\"",
+        HIGHLIGHT_START, HIGHLIGHT_STOP
+    );
+    if fragments {
+        format!("{}, MaxFragments=2, MaxWords=30, MinWords=10", base)
+    } else {
+        format!("{}, HighlightAll=true", base)
+    }
+}
+
+/// Escapes a `ts_headline` result and turns its delimiters into `<mark>` tags.
+pub fn highlight_to_html(headline: &str) -> String {
+    let mut html = String::with_capacity(headline.len() + 16);
+    for c in headline.chars() {
+        match c {
+            HIGHLIGHT_START => html.push_str("<mark>"),
+            HIGHLIGHT_STOP => html.push_str("</mark>"),
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            '\'' => html.push_str("&#39;"),
+            _ => html.push(c),
+        }
+    }
+    html
+}
@@ -0,0 +1,363 @@
+use crate::utils::{ClientInfo, CustomAppError, ErrorContext};
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use bb8_redis::{bb8, redis::aio::ConnectionLike};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Redis set holding the ids of every session opened by `user_id`.
+fn user_sessions_key(user_id: &Uuid) -> String {
+    format!("user_sessions:{}", user_id)
+}
+
+/// Redis hash holding when and from where a session was opened and last used.
+fn session_meta_key(session_id: &str) -> String {
+    format!("session_meta:{}", session_id)
+}
+
+/// Field of the session hash holding its CSRF token.
+const CSRF_TOKEN_FIELD: &str = "csrf_token";
+
+/// Public handle of a session. Session ids are credentials, so clients only
+/// ever see (and name sessions by) their hash.
+fn session_handle(session_id: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(session_id.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+fn redis_error(message: &str) -> CustomAppError {
+    CustomAppError::from((message.to_string(), ErrorContext::InternalServerError))
+}
+
+/// Opens a session for `user_id` and returns its id, to be set as the
+/// `sessionid` cookie.
+#[tracing::instrument(name = "create_session", skip(redis_store))]
+pub async fn create_session(
+    redis_store: &bb8::Pool<bb8_redis::RedisConnectionManager>,
+    user_id: &Uuid,
+    client: &ClientInfo,
+) -> Result<String, CustomAppError> {
+    let settings = crate::settings::get_settings().map_err(|_| {
+        CustomAppError::from((
+            "Failed to read settings".to_string(),
+            ErrorContext::InternalServerError,
+        ))
+    })?;
+    let expiration_in_seconds = settings.secret.cookie_expiration * 60;
+
+    let mut redis_con = redis_store
+        .get()
+        .await
+        .map_err(|_| redis_error("Failed to connect to session store"))?;
+
+    // Generate a truly random session id for the user
+    let session_id = Uuid::new_v4().to_string();
+    let now = chrono::Utc::now().to_rfc3339();
+    let meta_key = session_meta_key(&session_id);
+    let user_sessions_key = user_sessions_key(user_id);
+
+    let mut pipe = bb8_redis::redis::pipe();
+    pipe.cmd("SET")
+        .arg(&session_id)
+        .arg(user_id.to_string())
+        .arg("EX")
+        .arg(expiration_in_seconds)
+        .ignore();
+
+    let mut meta = vec![("created_at", now.clone()), ("last_seen", now)];
+    if let Some(ip_address) = &client.ip_address {
+        meta.push(("ip_address", ip_address.clone()));
+    }
+    if let Some(user_agent) = &client.user_agent {
+        meta.push(("user_agent", user_agent.clone()));
+    }
+    pipe.cmd("HSET").arg(&meta_key).arg(meta).ignore();
+    pipe.cmd("EXPIRE")
+        .arg(&meta_key)
+        .arg(expiration_in_seconds)
+        .ignore();
+
+    // Index the session under its user so all of them can be listed and revoked
+    pipe.cmd("SADD")
+        .arg(&user_sessions_key)
+        .arg(&session_id)
+        .ignore();
+    pipe.cmd("EXPIRE")
+        .arg(&user_sessions_key)
+        .arg(expiration_in_seconds)
+        .ignore();
+
+    pipe.query_async::<_, ()>(&mut *redis_con)
+        .await
+        .map_err(|_| redis_error("Failed to save session"))?;
+
+    Ok(session_id)
+}
+
+/// Records that the session was just used.
+pub async fn touch_session<C: ConnectionLike + Send>(
+    redis_con: &mut C,
+    session_id: &str,
+) -> Result<(), CustomAppError> {
+    let meta_key = session_meta_key(session_id);
+    // Sessions opened before metadata was recorded have no hash to update,
+    // and creating one here would outlive the session.
+    let has_meta: bool = bb8_redis::redis::cmd("EXISTS")
+        .arg(&meta_key)
+        .query_async(redis_con)
+        .await
+        .map_err(|_| redis_error("Failed to update session"))?;
+    if has_meta {
+        bb8_redis::redis::cmd("HSET")
+            .arg(&meta_key)
+            .arg("last_seen")
+            .arg(chrono::Utc::now().to_rfc3339())
+            .query_async::<_, i64>(redis_con)
+            .await
+            .map_err(|_| redis_error("Failed to update session"))?;
+    }
+    Ok(())
+}
+
+/// Returns the CSRF token of a live session, creating it on first use.
+pub async fn get_session_csrf_token(
+    redis_store: &bb8::Pool<bb8_redis::RedisConnectionManager>,
+    session_id: &str,
+) -> Result<String, CustomAppError> {
+    let mut redis_con = redis_store
+        .get()
+        .await
+        .map_err(|_| redis_error("Failed to connect to session store"))?;
+
+    if let Some(csrf_token) = stored_csrf_token(&mut *redis_con, session_id).await? {
+        return Ok(csrf_token);
+    }
+
+    // The hash must not outlive the session, which it may predate
+    let seconds_left: i64 = bb8_redis::redis::cmd("TTL")
+        .arg(session_id)
+        .query_async(&mut *redis_con)
+        .await
+        .map_err(|_| redis_error("Failed to read session"))?;
+    if seconds_left <= 0 {
+        return Err(CustomAppError::from((
+            "You are not authorized since you don't seem to have been authenticated".to_string(),
+            ErrorContext::UnauthorizedAccess,
+        )));
+    }
+
+    let mut token_bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut token_bytes);
+    let csrf_token: String = token_bytes.iter().map(|b| format!("{:02x}", b)).collect();
+
+    let meta_key = session_meta_key(session_id);
+    bb8_redis::redis::pipe()
+        .cmd("HSET")
+        .arg(&meta_key)
+        .arg(CSRF_TOKEN_FIELD)
+        .arg(&csrf_token)
+        .ignore()
+        .cmd("EXPIRE")
+        .arg(&meta_key)
+        .arg(seconds_left)
+        .ignore()
+        .query_async::<_, ()>(&mut *redis_con)
+        .await
+        .map_err(|_| redis_error("Failed to save CSRF token"))?;
+
+    Ok(csrf_token)
+}
+
+/// The CSRF token issued for `session_id`, if one was.
+pub async fn stored_csrf_token<C: ConnectionLike + Send>(
+    redis_con: &mut C,
+    session_id: &str,
+) -> Result<Option<String>, CustomAppError> {
+    bb8_redis::redis::cmd("HGET")
+        .arg(session_meta_key(session_id))
+        .arg(CSRF_TOKEN_FIELD)
+        .query_async(redis_con)
+        .await
+        .map_err(|_| redis_error("Failed to read CSRF token"))
+}
+
+/// Deletes a single session of `user_id`.
+pub async fn delete_session<C: ConnectionLike + Send>(
+    redis_con: &mut C,
+    user_id: &Uuid,
+    session_id: &str,
+) -> Result<(), CustomAppError> {
+    bb8_redis::redis::pipe()
+        .cmd("DEL")
+        .arg(session_id)
+        .arg(session_meta_key(session_id))
+        .ignore()
+        .cmd("SREM")
+        .arg(user_sessions_key(user_id))
+        .arg(session_id)
+        .ignore()
+        .query_async::<_, ()>(redis_con)
+        .await
+        .map_err(|_| redis_error("Failed to delete session ID from redis"))
+}
+
+/// Session ids of `user_id`, dropping the ones that already expired.
+async fn live_session_ids<C: ConnectionLike + Send>(
+    redis_con: &mut C,
+    user_id: &Uuid,
+) -> Result<Vec<String>, CustomAppError> {
+    let user_sessions_key = user_sessions_key(user_id);
+    let session_ids: Vec<String> = bb8_redis::redis::cmd("SMEMBERS")
+        .arg(&user_sessions_key)
+        .query_async(redis_con)
+        .await
+        .map_err(|_| redis_error("Failed to get user sessions from redis"))?;
+    if session_ids.is_empty() {
+        return Ok(session_ids);
+    }
+
+    let owners: Vec<Option<String>> = bb8_redis::redis::cmd("MGET")
+        .arg(&session_ids)
+        .query_async(redis_con)
+        .await
+        .map_err(|_| redis_error("Failed to get user sessions from redis"))?;
+
+    let (live, expired): (Vec<_>, Vec<_>) = session_ids
+        .into_iter()
+        .zip(owners)
+        .partition(|(_, owner)| owner.is_some());
+
+    if !expired.is_empty() {
+        let expired: Vec<String> = expired.into_iter().map(|(id, _)| id).collect();
+        let mut pipe = bb8_redis::redis::pipe();
+        pipe.cmd("SREM")
+            .arg(&user_sessions_key)
+            .arg(&expired)
+            .ignore();
+        for session_id in &expired {
+            pipe.cmd("DEL").arg(session_meta_key(session_id)).ignore();
+        }
+        pipe.query_async::<_, ()>(redis_con)
+            .await
+            .map_err(|_| redis_error("Failed to clean up user sessions"))?;
+    }
+
+    Ok(live.into_iter().map(|(id, _)| id).collect())
+}
+
+/// Lists the live sessions of `user_id`, most recently used first.
+#[tracing::instrument(name = "list_user_sessions", skip(redis_store, current))]
+pub async fn list_user_sessions(
+    redis_store: &bb8::Pool<bb8_redis::RedisConnectionManager>,
+    user_id: &Uuid,
+    current: Option<&str>,
+) -> Result<Vec<crate::models::SessionInfo>, CustomAppError> {
+    let mut redis_con = redis_store
+        .get()
+        .await
+        .map_err(|_| redis_error("Failed to get redis connection"))?;
+
+    let parse_time = |value: Option<&String>| {
+        value
+            .and_then(|v| chrono::DateTime::parse_from_rfc3339(v).ok())
+            .map(|v| v.with_timezone(&chrono::Utc))
+    };
+
+    let mut sessions = Vec::new();
+    for session_id in live_session_ids(&mut *redis_con, user_id).await? {
+        let meta: HashMap<String, String> = bb8_redis::redis::cmd("HGETALL")
+            .arg(session_meta_key(&session_id))
+            .query_async(&mut *redis_con)
+            .await
+            .map_err(|_| redis_error("Failed to get session from redis"))?;
+
+        sessions.push(crate::models::SessionInfo {
+            id: session_handle(&session_id),
+            created_at: parse_time(meta.get("created_at")),
+            last_seen: parse_time(meta.get("last_seen")),
+            ip_address: meta.get("ip_address").cloned(),
+            user_agent: meta.get("user_agent").cloned(),
+            is_current: Some(session_id.as_str()) == current,
+        });
+    }
+    sessions.sort_by_key(|session| std::cmp::Reverse(session.last_seen));
+
+    Ok(sessions)
+}
+
+/// Revokes the session of `user_id` whose public handle is `handle`.
+/// Returns whether such a session existed.
+#[tracing::instrument(name = "revoke_user_session", skip(redis_store))]
+pub async fn revoke_user_session(
+    redis_store: &bb8::Pool<bb8_redis::RedisConnectionManager>,
+    user_id: &Uuid,
+    handle: &str,
+) -> Result<bool, CustomAppError> {
+    let mut redis_con = redis_store
+        .get()
+        .await
+        .map_err(|_| redis_error("Failed to get redis connection"))?;
+
+    let session_id = live_session_ids(&mut *redis_con, user_id)
+        .await?
+        .into_iter()
+        .find(|session_id| session_handle(session_id) == handle);
+
+    match session_id {
+        Some(session_id) => {
+            delete_session(&mut *redis_con, user_id, &session_id).await?;
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// Deletes every session of `user_id` except `keep`, signing them out on all
+/// (other) devices.
+#[tracing::instrument(name = "revoke_all_user_sessions", skip(redis_store, keep))]
+pub async fn revoke_all_user_sessions(
+    redis_store: &bb8::Pool<bb8_redis::RedisConnectionManager>,
+    user_id: &Uuid,
+    keep: Option<&str>,
+) -> Result<(), CustomAppError> {
+    let mut redis_con = redis_store
+        .get()
+        .await
+        .map_err(|_| redis_error("Failed to get redis connection"))?;
+
+    let user_sessions_key = user_sessions_key(user_id);
+    let session_ids: Vec<String> = bb8_redis::redis::cmd("SMEMBERS")
+        .arg(&user_sessions_key)
+        .query_async::<_, Vec<String>>(&mut *redis_con)
+        .await
+        .map_err(|_| redis_error("Failed to get user sessions from redis"))?
+        .into_iter()
+        .filter(|session_id| Some(session_id.as_str()) != keep)
+        .collect();
+
+    if session_ids.is_empty() && keep.is_some() {
+        return Ok(());
+    }
+
+    let mut pipe = bb8_redis::redis::pipe();
+    for session_id in &session_ids {
+        pipe.cmd("DEL")
+            .arg(session_id)
+            .arg(session_meta_key(session_id))
+            .ignore();
+    }
+    if keep.is_some() {
+        pipe.cmd("SREM")
+            .arg(&user_sessions_key)
+            .arg(&session_ids)
+            .ignore();
+    } else {
+        pipe.cmd("DEL").arg(&user_sessions_key).ignore();
+    }
+    pipe.query_async::<_, ()>(&mut *redis_con)
+        .await
+        .map_err(|_| redis_error("Failed to delete user sessions from redis"))?;
+
+    Ok(())
+}
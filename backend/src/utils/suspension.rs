@@ -0,0 +1,128 @@
+use crate::utils::{CustomAppError, ErrorContext};
+use bb8_redis::{bb8, redis::aio::ConnectionLike};
+use uuid::Uuid;
+
+/// Redis copy of a suspension stored on the user, so that every authenticated
+/// request can check it without going to the database. It expires together
+/// with the suspension, and briefly records that a user is not suspended.
+fn suspension_key(user_id: &Uuid) -> String {
+    format!("user_suspension:{}", user_id)
+}
+
+fn redis_error(message: &str) -> CustomAppError {
+    CustomAppError::from((message.to_string(), ErrorContext::InternalServerError))
+}
+
+/// The error suspended users get, telling them until when and why.
+pub fn suspension_error(suspension: &crate::models::UserSuspension) -> CustomAppError {
+    CustomAppError::from((
+        format!(
+            "Your account has been suspended until {}. Reason: {}",
+            suspension.until.format("%Y-%m-%d %H:%M UTC"),
+            suspension.reason
+        ),
+        ErrorContext::Forbidden,
+    ))
+}
+
+#[tracing::instrument(name = "save_user_suspension", skip(redis_store))]
+pub async fn save_user_suspension(
+    redis_store: &bb8::Pool<bb8_redis::RedisConnectionManager>,
+    user_id: &Uuid,
+    suspension: &crate::models::UserSuspension,
+) -> Result<(), CustomAppError> {
+    let seconds_left = (suspension.until - chrono::Utc::now()).num_seconds();
+    if seconds_left <= 0 {
+        return Ok(());
+    }
+
+    let payload = serde_json::to_string(suspension)
+        .map_err(|_| redis_error("Failed to serialize suspension"))?;
+
+    let mut redis_con = redis_store
+        .get()
+        .await
+        .map_err(|_| redis_error("Failed to get redis connection"))?;
+
+    bb8_redis::redis::cmd("SET")
+        .arg(suspension_key(user_id))
+        .arg(payload)
+        .arg("EX")
+        .arg(seconds_left)
+        .query_async::<_, String>(&mut *redis_con)
+        .await
+        .map_err(|_| redis_error("Failed to save suspension"))?;
+
+    Ok(())
+}
+
+#[tracing::instrument(name = "lift_user_suspension", skip(redis_store))]
+pub async fn lift_user_suspension(
+    redis_store: &bb8::Pool<bb8_redis::RedisConnectionManager>,
+    user_id: &Uuid,
+) -> Result<(), CustomAppError> {
+    let mut redis_con = redis_store
+        .get()
+        .await
+        .map_err(|_| redis_error("Failed to get redis connection"))?;
+
+    bb8_redis::redis::cmd("DEL")
+        .arg(suspension_key(user_id))
+        .query_async::<_, i64>(&mut *redis_con)
+        .await
+        .map_err(|_| redis_error("Failed to lift suspension"))?;
+
+    Ok(())
+}
+
+/// Stored under `suspension_key` for users found not to be suspended, so
+/// that the database is only asked again once it expires.
+const NOT_SUSPENDED: &str = "none";
+const NOT_SUSPENDED_TTL_SECONDS: u64 = 60;
+
+/// Rejects the request if `user_id` is currently suspended. The database is
+/// the source of truth: a missing Redis copy, e.g. after a flush or an
+/// eviction, is looked up there and restored.
+pub async fn check_user_suspension<C: ConnectionLike + Send>(
+    redis_con: &mut C,
+    db_store: &crate::store::Store,
+    user_id: &Uuid,
+) -> Result<(), CustomAppError> {
+    let cached: Option<String> = bb8_redis::redis::cmd("GET")
+        .arg(suspension_key(user_id))
+        .query_async(redis_con)
+        .await
+        .map_err(|_| redis_error("Failed to check account suspension"))?;
+
+    let suspension = match cached {
+        Some(cached) if cached == NOT_SUSPENDED => None,
+        Some(cached) => serde_json::from_str::<crate::models::UserSuspension>(&cached).ok(),
+        None => {
+            let suspension = db_store.get_user_suspension_from_db(*user_id).await?;
+            let (payload, seconds_left) = match &suspension {
+                Some(suspension) => (
+                    serde_json::to_string(suspension)
+                        .map_err(|_| redis_error("Failed to serialize suspension"))?,
+                    (suspension.until - chrono::Utc::now()).num_seconds().max(1) as u64,
+                ),
+                None => (NOT_SUSPENDED.to_string(), NOT_SUSPENDED_TTL_SECONDS),
+            };
+            // NX so that a suspension saved in the meantime is not overwritten
+            bb8_redis::redis::cmd("SET")
+                .arg(suspension_key(user_id))
+                .arg(payload)
+                .arg("EX")
+                .arg(seconds_left)
+                .arg("NX")
+                .query_async::<_, Option<String>>(redis_con)
+                .await
+                .map_err(|_| redis_error("Failed to check account suspension"))?;
+            suspension
+        }
+    };
+
+    match suspension {
+        Some(suspension) => Err(suspension_error(&suspension)),
+        None => Ok(()),
+    }
+}
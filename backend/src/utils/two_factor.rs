@@ -0,0 +1,337 @@
+use crate::utils::{CustomAppError, ErrorContext};
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use sha2::{Digest, Sha256};
+
+/// Name authenticator apps show next to the account.
+const TOTP_ISSUER: &str = "CryptoFlow";
+
+/// Number of recovery codes handed out when 2FA is turned on.
+const RECOVERY_CODES_COUNT: usize = 10;
+
+/// Characters recovery codes are drawn from; no `0/o` or `1/l` look-alikes.
+const RECOVERY_CODE_ALPHABET: &[u8] = b"23456789abcdefghjkmnpqrstuvwxyz";
+
+/// A fresh base32-encoded TOTP secret.
+pub fn generate_totp_secret() -> String {
+    match totp_rs::Secret::generate_secret().to_encoded() {
+        totp_rs::Secret::Encoded(secret) => secret,
+        totp_rs::Secret::Raw(_) => unreachable!("to_encoded always returns an encoded secret"),
+    }
+}
+
+/// Builds the RFC 6238 (SHA-1, 6 digits, 30 second steps) TOTP every
+/// authenticator app understands for `secret`.
+pub fn build_totp(secret: &str, account_name: &str) -> Result<totp_rs::TOTP, CustomAppError> {
+    let secret = totp_rs::Secret::Encoded(secret.to_string())
+        .to_bytes()
+        .map_err(|_| {
+            CustomAppError::from((
+                "Invalid two-factor secret".to_string(),
+                ErrorContext::InternalServerError,
+            ))
+        })?;
+
+    totp_rs::TOTP::new(
+        totp_rs::Algorithm::SHA1,
+        6,
+        1,
+        30,
+        secret,
+        Some(TOTP_ISSUER.to_string()),
+        account_name.to_string(),
+    )
+    .map_err(|_| {
+        CustomAppError::from((
+            "Failed to set up two-factor authentication".to_string(),
+            ErrorContext::InternalServerError,
+        ))
+    })
+}
+
+/// Whether `code` is the current (or an adjacent) TOTP code for `secret`.
+fn verify_totp_code(secret: &str, account_name: &str, code: &str) -> Result<bool, CustomAppError> {
+    build_totp(secret, account_name)?
+        .check_current(code.trim())
+        .map_err(|_| {
+            CustomAppError::from((
+                "Server error occurred".to_string(),
+                ErrorContext::InternalServerError,
+            ))
+        })
+}
+
+/// TOTP codes are all digits; anything else is taken for a recovery code.
+fn is_totp_code(code: &str) -> bool {
+    let code = code.trim();
+    code.len() == 6 && code.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Recovery codes are shown as `xxxxx-xxxxx` but compared without the dash,
+/// whitespace or case.
+pub fn hash_recovery_code(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    let mut hasher = Sha256::new();
+    hasher.update(normalized.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// A new set of recovery codes, to be shown to the user exactly once.
+pub fn generate_recovery_codes() -> Vec<String> {
+    (0..RECOVERY_CODES_COUNT)
+        .map(|_| {
+            let chars: String = (0..10)
+                .map(|_| {
+                    RECOVERY_CODE_ALPHABET[OsRng.next_u32() as usize % RECOVERY_CODE_ALPHABET.len()]
+                        as char
+                })
+                .collect();
+            format!("{}-{}", &chars[..5], &chars[5..])
+        })
+        .collect()
+}
+
+/// How long the token handed out by the first login step stays valid.
+const PENDING_LOGIN_EXPIRATION_SECONDS: i64 = 5 * 60;
+
+/// Codes that can be tried against one pending login before it is dropped.
+const PENDING_LOGIN_MAX_ATTEMPTS: i64 = 5;
+
+fn pending_login_key(hashed_token: &str) -> String {
+    format!("pending_2fa:{}", hashed_token)
+}
+
+fn pending_login_attempts_key(hashed_token: &str) -> String {
+    format!("pending_2fa_attempts:{}", hashed_token)
+}
+
+fn hash_token(token: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(token.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+fn redis_error(message: &str) -> CustomAppError {
+    CustomAppError::from((message.to_string(), ErrorContext::InternalServerError))
+}
+
+/// Starts a login that still needs a second factor and returns the token the
+/// client trades, together with a code, for a session.
+#[tracing::instrument(name = "create_pending_two_factor_login", skip(redis_store))]
+pub async fn create_pending_two_factor_login(
+    redis_store: &bb8_redis::bb8::Pool<bb8_redis::RedisConnectionManager>,
+    user_id: &uuid::Uuid,
+) -> Result<String, CustomAppError> {
+    let mut token_bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut token_bytes);
+    let token: String = token_bytes.iter().map(|b| format!("{:02x}", b)).collect();
+
+    let mut redis_con = redis_store
+        .get()
+        .await
+        .map_err(|_| redis_error("Failed to get redis connection"))?;
+
+    bb8_redis::redis::cmd("SET")
+        .arg(pending_login_key(&hash_token(&token)))
+        .arg(user_id.to_string())
+        .arg("EX")
+        .arg(PENDING_LOGIN_EXPIRATION_SECONDS)
+        .query_async::<_, String>(&mut *redis_con)
+        .await
+        .map_err(|_| redis_error("Failed to save pending login"))?;
+
+    Ok(token)
+}
+
+/// Resolves a pending login token to its user, counting the attempt. The
+/// token is dropped once it has been tried too often.
+#[tracing::instrument(name = "get_pending_two_factor_login", skip(redis_store, token))]
+pub async fn get_pending_two_factor_login(
+    redis_store: &bb8_redis::bb8::Pool<bb8_redis::RedisConnectionManager>,
+    token: &str,
+) -> Result<uuid::Uuid, CustomAppError> {
+    let hashed_token = hash_token(token);
+    let mut redis_con = redis_store
+        .get()
+        .await
+        .map_err(|_| redis_error("Failed to get redis connection"))?;
+
+    let user_id: Option<String> = bb8_redis::redis::cmd("GET")
+        .arg(pending_login_key(&hashed_token))
+        .query_async(&mut *redis_con)
+        .await
+        .map_err(|_| redis_error("Failed to get pending login"))?;
+    let user_id = user_id.ok_or_else(|| {
+        CustomAppError::from((
+            "This login has expired. Please log in again".to_string(),
+            ErrorContext::UnauthorizedAccess,
+        ))
+    })?;
+
+    let (attempts,): (i64,) = bb8_redis::redis::pipe()
+        .cmd("INCR")
+        .arg(pending_login_attempts_key(&hashed_token))
+        .cmd("EXPIRE")
+        .arg(pending_login_attempts_key(&hashed_token))
+        .arg(PENDING_LOGIN_EXPIRATION_SECONDS)
+        .ignore()
+        .query_async(&mut *redis_con)
+        .await
+        .map_err(|_| redis_error("Failed to update pending login"))?;
+
+    if attempts > PENDING_LOGIN_MAX_ATTEMPTS {
+        drop(redis_con);
+        finish_pending_two_factor_login(redis_store, token).await?;
+        return Err(CustomAppError::from((
+            "Too many invalid codes. Please log in again".to_string(),
+            ErrorContext::UnauthorizedAccess,
+        )));
+    }
+
+    uuid::Uuid::parse_str(&user_id).map_err(|_| {
+        CustomAppError::from((
+            "Invalid user ID format".to_string(),
+            ErrorContext::InternalServerError,
+        ))
+    })
+}
+
+/// Drops a pending login token so it cannot be used again.
+pub async fn finish_pending_two_factor_login(
+    redis_store: &bb8_redis::bb8::Pool<bb8_redis::RedisConnectionManager>,
+    token: &str,
+) -> Result<(), CustomAppError> {
+    let hashed_token = hash_token(token);
+    let mut redis_con = redis_store
+        .get()
+        .await
+        .map_err(|_| redis_error("Failed to get redis connection"))?;
+
+    bb8_redis::redis::cmd("DEL")
+        .arg(pending_login_key(&hashed_token))
+        .arg(pending_login_attempts_key(&hashed_token))
+        .query_async::<_, i64>(&mut *redis_con)
+        .await
+        .map_err(|_| redis_error("Failed to delete pending login"))?;
+
+    Ok(())
+}
+
+/// Wrong codes or passwords a signed-in user may enter while changing their
+/// 2FA settings before having to wait out the window.
+const MAX_SETTINGS_FAILURES: i64 = 5;
+
+const SETTINGS_FAILURE_WINDOW_SECONDS: i64 = 15 * 60;
+
+fn settings_failures_key(user_id: &uuid::Uuid) -> String {
+    format!("two_factor_failures:{}", user_id)
+}
+
+/// Rejects a change to the 2FA settings of `user_id` with a 429 once too many
+/// wrong codes or passwords were entered for it.
+#[tracing::instrument(name = "check_two_factor_attempts", skip(redis_store))]
+pub async fn check_two_factor_attempts(
+    redis_store: &bb8_redis::bb8::Pool<bb8_redis::RedisConnectionManager>,
+    user_id: &uuid::Uuid,
+) -> Result<(), CustomAppError> {
+    let mut redis_con = redis_store
+        .get()
+        .await
+        .map_err(|_| redis_error("Failed to get redis connection"))?;
+
+    let (failures, ttl): (Option<i64>, i64) = bb8_redis::redis::pipe()
+        .cmd("GET")
+        .arg(settings_failures_key(user_id))
+        .cmd("TTL")
+        .arg(settings_failures_key(user_id))
+        .query_async(&mut *redis_con)
+        .await
+        .map_err(|_| redis_error("Failed to check two-factor attempts"))?;
+
+    if failures.unwrap_or(0) < MAX_SETTINGS_FAILURES {
+        return Ok(());
+    }
+
+    let retry_after = ttl.max(1) as u64;
+    Err(CustomAppError::TooManyRequests {
+        message: format!(
+            "Too many invalid codes or passwords. Please try again in {} seconds.",
+            retry_after
+        ),
+        retry_after,
+    })
+}
+
+/// Counts a wrong code or password entered while changing the 2FA settings of
+/// `user_id`.
+#[tracing::instrument(name = "record_two_factor_failure", skip(redis_store))]
+pub async fn record_two_factor_failure(
+    redis_store: &bb8_redis::bb8::Pool<bb8_redis::RedisConnectionManager>,
+    user_id: &uuid::Uuid,
+) -> Result<(), CustomAppError> {
+    let mut redis_con = redis_store
+        .get()
+        .await
+        .map_err(|_| redis_error("Failed to get redis connection"))?;
+
+    // The window starts with the first failure and is not extended by later ones
+    bb8_redis::redis::pipe()
+        .cmd("SET")
+        .arg(settings_failures_key(user_id))
+        .arg(0)
+        .arg("EX")
+        .arg(SETTINGS_FAILURE_WINDOW_SECONDS)
+        .arg("NX")
+        .ignore()
+        .cmd("INCR")
+        .arg(settings_failures_key(user_id))
+        .ignore()
+        .query_async::<_, ()>(&mut *redis_con)
+        .await
+        .map_err(|_| redis_error("Failed to record two-factor attempt"))?;
+
+    Ok(())
+}
+
+/// Checks a second factor of `user_id`: a TOTP code, which cannot be replayed
+/// while it is still valid, or an unused recovery code, which is burnt.
+#[tracing::instrument(name = "verify_second_factor", skip(state, secret, code))]
+pub async fn verify_second_factor(
+    state: &crate::startup::AppState,
+    user_id: uuid::Uuid,
+    account_name: &str,
+    secret: &str,
+    code: &str,
+) -> Result<bool, CustomAppError> {
+    if !is_totp_code(code) {
+        return Ok(state
+            .db_store
+            .use_recovery_code_in_db(user_id, &hash_recovery_code(code))
+            .await?);
+    }
+
+    if !verify_totp_code(secret, account_name, code)? {
+        return Ok(false);
+    }
+
+    // A code stays valid for up to three 30 second steps with the allowed skew
+    let mut redis_con = state
+        .redis_store
+        .get()
+        .await
+        .map_err(|_| redis_error("Failed to get redis connection"))?;
+    let first_use: Option<String> = bb8_redis::redis::cmd("SET")
+        .arg(format!("totp_used:{}:{}", user_id, code.trim()))
+        .arg(1)
+        .arg("EX")
+        .arg(90)
+        .arg("NX")
+        .query_async(&mut *redis_con)
+        .await
+        .map_err(|_| redis_error("Failed to record two-factor code"))?;
+
+    Ok(first_use.is_some())
+}
@@ -1,28 +1,101 @@
-use crate::utils::{CustomAppError, ErrorContext};
+use crate::{
+    startup::AppState,
+    utils::{CustomAppError, ErrorContext},
+};
+use axum::{
+    extract::FromRequestParts,
+    http::{header, request::Parts, Method},
+};
 use axum_extra::extract::PrivateCookieJar;
-use bb8_redis::bb8;
 use uuid::Uuid;
 
-#[tracing::instrument(
-    name = "get_user_id_from_session",
-    skip(cookies, redis_store, is_logout)
-)]
+/// What a request authenticates with: a personal API token sent as
+/// `Authorization: Bearer <token>`, or else the `sessionid` cookie.
+pub struct Credentials {
+    cookies: PrivateCookieJar,
+    bearer_token: Option<String>,
+    method: Method,
+}
+
+impl Credentials {
+    pub fn uses_api_token(&self) -> bool {
+        self.bearer_token.is_some()
+    }
+
+    /// The id in the `sessionid` cookie, whether or not the session is live.
+    pub fn session_id(&self) -> Option<String> {
+        self.cookies
+            .get("sessionid")
+            .map(|cookie| cookie.value().to_owned())
+    }
+
+    pub fn method(&self) -> &Method {
+        &self.method
+    }
+}
+
+#[axum::async_trait]
+impl FromRequestParts<AppState> for Credentials {
+    type Rejection = CustomAppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let cookies =
+            PrivateCookieJar::<axum_extra::extract::cookie::Key>::from_request_parts(parts, state)
+                .await
+                .map_err(|_| {
+                    CustomAppError::from((
+                        "Failed to read cookies".to_string(),
+                        ErrorContext::InternalServerError,
+                    ))
+                })?;
+
+        let bearer_token = parts
+            .headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split_once(' '))
+            .filter(|(scheme, _)| scheme.eq_ignore_ascii_case("bearer"))
+            .map(|(_, token)| token.trim().to_string());
+
+        Ok(Credentials {
+            cookies,
+            bearer_token,
+            method: parts.method.clone(),
+        })
+    }
+}
+
+fn unauthenticated() -> CustomAppError {
+    CustomAppError::from((
+        "You are not authorized since you don't seem to have been authenticated".to_string(),
+        ErrorContext::UnauthorizedAccess,
+    ))
+}
+
+/// Resolves the user behind the request. For cookie sessions, also returns
+/// the session id; for API tokens, an `api_token:<id>` placeholder that
+/// matches no session.
+#[tracing::instrument(name = "get_user_id_from_session", skip(credentials, state, is_logout))]
 pub async fn get_user_id_from_session(
-    cookies: &PrivateCookieJar,
-    redis_store: &bb8::Pool<bb8_redis::RedisConnectionManager>,
+    credentials: &Credentials,
+    state: &AppState,
     is_logout: bool,
 ) -> Result<(Uuid, String), CustomAppError> {
-    let session_id = cookies
-        .get("sessionid")
-        .map(|cookie| cookie.value().to_owned())
-        .ok_or_else(|| {
-            CustomAppError::from((
-                "Session ID not found because you are not authenticated".to_string(),
-                ErrorContext::UnauthorizedAccess,
-            ))
-        })?;
+    if let Some(token) = &credentials.bearer_token {
+        return get_user_id_from_api_token(token, &credentials.method, state, is_logout).await;
+    }
+
+    let session_id = credentials.session_id().ok_or_else(|| {
+        CustomAppError::from((
+            "Session ID not found because you are not authenticated".to_string(),
+            ErrorContext::UnauthorizedAccess,
+        ))
+    })?;
 
-    let mut redis_con = redis_store.get().await.map_err(|_| {
+    let mut redis_con = state.redis_store.get().await.map_err(|_| {
         CustomAppError::from((
             "Failed to get redis connection".to_string(),
             ErrorContext::InternalServerError,
@@ -35,13 +108,7 @@ pub async fn get_user_id_from_session(
         .arg(&session_id)
         .query_async(&mut *redis_con)
         .await
-        .map_err(|_| {
-            CustomAppError::from((
-                "You are not authorized since you don't seem to have been authenticated"
-                    .to_string(),
-                ErrorContext::UnauthorizedAccess,
-            ))
-        })?;
+        .map_err(|_| unauthenticated())?;
 
     let user_uuid = Uuid::parse_str(&user_id).map_err(|_| {
         CustomAppError::from((
@@ -50,18 +117,59 @@ pub async fn get_user_id_from_session(
         ))
     })?;
 
+    // Suspended users may still log out
+    if !is_logout {
+        crate::utils::check_user_suspension(&mut *redis_con, &state.db_store, &user_uuid).await?;
+    }
+
     if is_logout {
-        bb8_redis::redis::cmd("DEL")
-            .arg(&session_id)
-            .query_async::<_, i64>(&mut *redis_con)
-            .await
-            .map_err(|_| {
-                CustomAppError::from((
-                    "Failed to delete session ID from redis".to_string(),
-                    ErrorContext::InternalServerError,
-                ))
-            })?;
+        crate::utils::delete_session(&mut *redis_con, &user_uuid, &session_id).await?;
+    } else {
+        crate::utils::touch_session(&mut *redis_con, &session_id).await?;
     }
 
     Ok((user_uuid, session_id))
 }
+
+async fn get_user_id_from_api_token(
+    token: &str,
+    method: &Method,
+    state: &AppState,
+    is_logout: bool,
+) -> Result<(Uuid, String), CustomAppError> {
+    if is_logout {
+        return Err(CustomAppError::from((
+            "API tokens are not sessions; revoke the token instead of logging out".to_string(),
+            ErrorContext::BadRequest,
+        )));
+    }
+
+    let owner = state
+        .db_store
+        .use_api_token_in_db(&crate::utils::hash_api_token(token))
+        .await?
+        .ok_or_else(|| {
+            CustomAppError::from((
+                "Invalid or expired API token".to_string(),
+                ErrorContext::UnauthorizedAccess,
+            ))
+        })?;
+
+    let is_safe_method = matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS);
+    if owner.scope != crate::models::ApiTokenScope::Write.as_str() && !is_safe_method {
+        return Err(CustomAppError::from((
+            "This API token only grants read access".to_string(),
+            ErrorContext::Forbidden,
+        )));
+    }
+
+    let mut redis_con = state.redis_store.get().await.map_err(|_| {
+        CustomAppError::from((
+            "Failed to get redis connection".to_string(),
+            ErrorContext::InternalServerError,
+        ))
+    })?;
+    crate::utils::check_user_suspension(&mut *redis_con, &state.db_store, &owner.user_id).await?;
+
+    Ok((owner.user_id, format!("api_token:{}", owner.id)))
+}
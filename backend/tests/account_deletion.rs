@@ -0,0 +1,248 @@
+use backend::{models::DELETED_USER_ID, settings::AuthoredContentPolicy, store::Store};
+use sqlx::postgres::PgPool;
+
+async fn create_user(pool: &PgPool, email: &str) -> uuid::Uuid {
+    sqlx::query_scalar(
+        "INSERT INTO users (email, password, first_name, last_name, is_active) VALUES ($1, 'x', 'Test', 'User', TRUE) RETURNING id",
+    )
+    .bind(email)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+async fn create_question(pool: &PgPool, author: uuid::Uuid, slug: &str) -> uuid::Uuid {
+    sqlx::query_scalar(
+        "INSERT INTO questions (title, slug, content, raw_content, author) VALUES ($1, $1, 'q', 'q', $2) RETURNING id",
+    )
+    .bind(slug)
+    .bind(author)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+async fn create_answer(pool: &PgPool, author: uuid::Uuid, question: uuid::Uuid) -> uuid::Uuid {
+    sqlx::query_scalar(
+        "INSERT INTO answers (content, raw_content, author, question) VALUES ('a', 'a', $1, $2) RETURNING id",
+    )
+    .bind(author)
+    .bind(question)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+async fn create_comment(
+    pool: &PgPool,
+    author: uuid::Uuid,
+    question: uuid::Uuid,
+    parent: Option<uuid::Uuid>,
+) -> uuid::Uuid {
+    sqlx::query_scalar(
+        "INSERT INTO comments (content, raw_content, author, question, parent) VALUES ('c', 'c', $1, $2, $3) RETURNING id",
+    )
+    .bind(author)
+    .bind(question)
+    .bind(parent)
+    .fetch_one(pool)
+    .await
+    .unwrap()
+}
+
+async fn author_of(pool: &PgPool, table: &str, id: uuid::Uuid) -> Option<uuid::Uuid> {
+    sqlx::query_scalar(&format!("SELECT author FROM {table} WHERE id = $1"))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .unwrap()
+}
+
+#[sqlx::test]
+async fn anonymizing_hands_every_post_to_the_placeholder(pool: PgPool) {
+    let user = create_user(&pool, "leaving@example.com").await;
+    let question = create_question(&pool, user, "leaving-question").await;
+    let answer = create_answer(&pool, user, question).await;
+    let comment = create_comment(&pool, user, question, None).await;
+
+    let store = Store {
+        connection: pool.clone(),
+    };
+    store
+        .delete_account_in_db(user, AuthoredContentPolicy::Anonymize)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        author_of(&pool, "questions", question).await,
+        Some(DELETED_USER_ID)
+    );
+    assert_eq!(
+        author_of(&pool, "answers", answer).await,
+        Some(DELETED_USER_ID)
+    );
+    assert_eq!(
+        author_of(&pool, "comments", comment).await,
+        Some(DELETED_USER_ID)
+    );
+    let remaining: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users WHERE id = $1")
+        .bind(user)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(remaining, 0);
+}
+
+#[sqlx::test]
+async fn deleting_keeps_only_what_others_replied_to(pool: PgPool) {
+    let user = create_user(&pool, "leaving@example.com").await;
+    let other = create_user(&pool, "staying@example.com").await;
+
+    let lonely_question = create_question(&pool, user, "lonely-question").await;
+    let answered_question = create_question(&pool, user, "answered-question").await;
+    let others_answer = create_answer(&pool, other, answered_question).await;
+    let own_answer = create_answer(&pool, user, answered_question).await;
+
+    // A thread of the user's own replies goes level by level, while a
+    // comment somebody answered stays
+    let others_question = create_question(&pool, other, "others-question").await;
+    let thread = create_comment(&pool, user, others_question, None).await;
+    let reply = create_comment(&pool, user, others_question, Some(thread)).await;
+    let nested_reply = create_comment(&pool, user, others_question, Some(reply)).await;
+    let replied_to = create_comment(&pool, user, others_question, None).await;
+    let others_reply = create_comment(&pool, other, others_question, Some(replied_to)).await;
+
+    let store = Store {
+        connection: pool.clone(),
+    };
+    store
+        .delete_account_in_db(user, AuthoredContentPolicy::Delete)
+        .await
+        .unwrap();
+
+    assert_eq!(author_of(&pool, "questions", lonely_question).await, None);
+    assert_eq!(author_of(&pool, "answers", own_answer).await, None);
+    assert_eq!(
+        author_of(&pool, "questions", answered_question).await,
+        Some(DELETED_USER_ID)
+    );
+    assert_eq!(
+        author_of(&pool, "answers", others_answer).await,
+        Some(other)
+    );
+
+    for comment in [thread, reply, nested_reply] {
+        assert_eq!(author_of(&pool, "comments", comment).await, None);
+    }
+    assert_eq!(
+        author_of(&pool, "comments", replied_to).await,
+        Some(DELETED_USER_ID)
+    );
+    assert_eq!(
+        author_of(&pool, "comments", others_reply).await,
+        Some(other)
+    );
+}
+
+#[sqlx::test]
+async fn votes_are_taken_out_of_the_scores(pool: PgPool) {
+    let user = create_user(&pool, "leaving@example.com").await;
+    let other = create_user(&pool, "staying@example.com").await;
+    let question = create_question(&pool, other, "voted-question").await;
+    let answer = create_answer(&pool, other, question).await;
+
+    sqlx::query("INSERT INTO question_votes (question, voter, value) VALUES ($1, $2, 1)")
+        .bind(question)
+        .bind(user)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("INSERT INTO answer_votes (answer, voter, value) VALUES ($1, $2, -1)")
+        .bind(answer)
+        .bind(user)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE questions SET score = 1 WHERE id = $1")
+        .bind(question)
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE answers SET score = -1 WHERE id = $1")
+        .bind(answer)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let store = Store {
+        connection: pool.clone(),
+    };
+    store
+        .delete_account_in_db(user, AuthoredContentPolicy::Anonymize)
+        .await
+        .unwrap();
+
+    let question_score: i32 = sqlx::query_scalar("SELECT score FROM questions WHERE id = $1")
+        .bind(question)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    let answer_score: i32 = sqlx::query_scalar("SELECT score FROM answers WHERE id = $1")
+        .bind(answer)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!((question_score, answer_score), (0, 0));
+}
+
+#[sqlx::test]
+async fn audit_entries_lose_the_personal_data_of_the_account(pool: PgPool) {
+    let user = create_user(&pool, "leaving@example.com").await;
+    let admin = create_user(&pool, "admin@example.com").await;
+
+    let own_entry: uuid::Uuid = sqlx::query_scalar(
+        "INSERT INTO audit_log (event, actor, target_type, target_id, ip_address, user_agent, payload) VALUES ('email_changed', $1, 'user', $1, '203.0.113.7', 'curl', $2) RETURNING id",
+    )
+    .bind(user)
+    .bind(serde_json::json!({"old_email": "old@example.com", "new_email": "leaving@example.com"}))
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    let admin_entry: uuid::Uuid = sqlx::query_scalar(
+        "INSERT INTO audit_log (event, actor, target_type, target_id, ip_address, user_agent, payload) VALUES ('user_suspended', $1, 'user', $2, '198.51.100.1', 'browser', $3) RETURNING id",
+    )
+    .bind(admin)
+    .bind(user)
+    .bind(serde_json::json!({"email": "leaving@example.com", "reason": "spam"}))
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    let store = Store {
+        connection: pool.clone(),
+    };
+    store
+        .delete_account_in_db(user, AuthoredContentPolicy::Anonymize)
+        .await
+        .unwrap();
+
+    let entry = |id: uuid::Uuid| {
+        sqlx::query_as::<_, (String, Option<String>, Option<String>, serde_json::Value)>(
+            "SELECT event, ip_address, user_agent, payload FROM audit_log WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_one(&pool)
+    };
+
+    let (event, ip_address, user_agent, payload) = entry(own_entry).await.unwrap();
+    assert_eq!(event, "email_changed");
+    assert_eq!((ip_address, user_agent), (None, None));
+    assert_eq!(payload, serde_json::json!({}));
+
+    // The administrator's own address stays, the account's email does not
+    let (event, ip_address, user_agent, payload) = entry(admin_entry).await.unwrap();
+    assert_eq!(event, "user_suspended");
+    assert_eq!(ip_address.as_deref(), Some("198.51.100.1"));
+    assert_eq!(user_agent.as_deref(), Some("browser"));
+    assert_eq!(payload, serde_json::json!({"reason": "spam"}));
+}
@@ -1,6 +1,7 @@
 // @ts-nocheck
 import { quintOut } from 'svelte/easing';
 import { crossfade } from 'svelte/transition';
+import { BASE_API_URI } from '$lib/utils/constants';
 
 export const [send, receive] = crossfade({
 	duration: (d) => Math.sqrt(d * 200),
@@ -97,17 +98,44 @@ export const getRequests = async (
 	return res.ok && (await res.json());
 };
 
+/**
+ * Headers for requests that change data on behalf of the logged in user: the
+ * session cookie and the CSRF token the API expects along with it.
+ * @file lib/utils/helpers.js
+ * @param {typeof fetch} sveltekitFetch - Fetch object from sveltekit
+ * @param {string | undefined} session - The value of the `cryptoflow-sessionid` cookie.
+ * @returns {Promise<Record<string, string>>}
+ */
+export const sessionHeaders = async (sveltekitFetch, session) => {
+	/** @type {Record<string, string>} */
+	const headers = {
+		'Content-Type': 'application/json',
+		Cookie: `sessionid=${session}`
+	};
+
+	const res = await sveltekitFetch(`${BASE_API_URI}/users/csrf-token`, {
+		credentials: 'include',
+		headers: headers
+	});
+	if (res.ok) {
+		const { csrf_token } = await res.json();
+		headers['X-CSRF-Token'] = csrf_token;
+	}
+
+	return headers;
+};
+
 /**
  * Get coin prices.
  * @file lib/utils/helpers.js
  * @param {typeof fetch} sveltekitFetch - Fetch object from sveltekit
  * @param {string} tags - The tags of the coins to fetch prices for.
- * @param {string} currency - The currency to fetch prices in.
+ * @param {string} currencies - Comma-separated currencies to fetch prices in.
  */
-export const getCoinsPricesServer = async (sveltekitFetch, tags, currency) => {
+export const getCoinsPricesServer = async (sveltekitFetch, tags, currencies) => {
 	const res = await getRequests(
 		sveltekitFetch,
-		`/api/crypto/prices?tags=${tags}&currency=${currency}`
+		`/api/crypto/prices?tags=${tags}&vs_currencies=${currencies}`
 	);
 
 	return res;
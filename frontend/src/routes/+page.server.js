@@ -5,7 +5,7 @@ import { fail } from '@sveltejs/kit';
 export async function load({ fetch }) {
 	const fetchQuestions = async () => {
 		const res = await fetch(`${BASE_API_URI}/qa/questions`);
-		return res.ok && (await res.json());
+		return res.ok && (await res.json()).results;
 	};
 
 	const fetchCoins = async () => {
@@ -49,7 +49,8 @@ export const actions = {
 
 		return {
 			status: 200,
-			marketData: response
+			marketData: response.data,
+			coinErrors: response.errors
 		};
 	}
 };
@@ -1,4 +1,5 @@
 import { BASE_API_URI } from '$lib/utils/constants';
+import { sessionHeaders } from '$lib/utils/helpers';
 import { fail } from '@sveltejs/kit';
 
 /** @type {import('./$types').PageServerLoad} */
@@ -35,10 +36,7 @@ export const actions = {
 		const requestInitOptions = {
 			method: 'POST',
 			credentials: 'include',
-			headers: {
-				'Content-Type': 'application/json',
-				Cookie: `sessionid=${cookies.get('cryptoflow-sessionid')}`
-			},
+			headers: await sessionHeaders(fetch, cookies.get('cryptoflow-sessionid')),
 			body: JSON.stringify({
 				content: content
 			})
@@ -73,10 +71,7 @@ export const actions = {
 		const requestInitOptions = {
 			method: 'DELETE',
 			credentials: 'include',
-			headers: {
-				'Content-Type': 'application/json',
-				Cookie: `sessionid=${cookies.get('cryptoflow-sessionid')}`
-			}
+			headers: await sessionHeaders(fetch, cookies.get('cryptoflow-sessionid'))
 		};
 
 		const res = await fetch(`${BASE_API_URI}/qa/answers/${answerID}`, requestInitOptions);
@@ -106,10 +101,7 @@ export const actions = {
 		const requestInitOptions = {
 			method: 'PATCH',
 			credentials: 'include',
-			headers: {
-				'Content-Type': 'application/json',
-				Cookie: `sessionid=${cookies.get('cryptoflow-sessionid')}`
-			},
+			headers: await sessionHeaders(fetch, cookies.get('cryptoflow-sessionid')),
 			body: JSON.stringify({
 				content: content
 			})
@@ -1,4 +1,5 @@
 import { BASE_API_URI } from '$lib/utils/constants';
+import { sessionHeaders } from '$lib/utils/helpers';
 import { fail, redirect } from '@sveltejs/kit';
 
 /** @type {import('./$types').Actions} */
@@ -14,10 +15,7 @@ export const actions = {
 		const requestInitOptions = {
 			method: 'DELETE',
 			credentials: 'include',
-			headers: {
-				'Content-Type': 'application/json',
-				Cookie: `sessionid=${cookies.get('cryptoflow-sessionid')}`
-			}
+			headers: await sessionHeaders(fetch, cookies.get('cryptoflow-sessionid'))
 		};
 
 		const res = await fetch(`${BASE_API_URI}/qa/questions/${params.id}`, requestInitOptions);
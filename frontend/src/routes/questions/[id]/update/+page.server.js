@@ -1,4 +1,5 @@
 import { BASE_API_URI } from '$lib/utils/constants';
+import { sessionHeaders } from '$lib/utils/helpers';
 import { fail, redirect } from '@sveltejs/kit';
 
 /** @type {import('./$types').PageServerLoad} */
@@ -43,10 +44,7 @@ export const actions = {
 		const requestInitOptions = {
 			method: 'PATCH',
 			credentials: 'include',
-			headers: {
-				'Content-Type': 'application/json',
-				Cookie: `sessionid=${cookies.get('cryptoflow-sessionid')}`
-			},
+			headers: await sessionHeaders(fetch, cookies.get('cryptoflow-sessionid')),
 			body: JSON.stringify({
 				title: title,
 				content: content,
@@ -1,4 +1,5 @@
 import { BASE_API_URI } from '$lib/utils/constants';
+import { sessionHeaders } from '$lib/utils/helpers';
 import { fail, redirect } from '@sveltejs/kit';
 
 /** @type {import('./$types').PageServerLoad} */
@@ -9,6 +10,61 @@ export async function load({ locals }) {
 	}
 }
 
+/**
+ * Copies the session cookie the backend set on `res` over to the browser.
+ *
+ * @param {Response} res - The backend's response to a successful login
+ * @param {import('@sveltejs/kit').Cookies} cookies - SvelteKit's cookie object
+ */
+function setSessionCookie(res, cookies) {
+	if (res.headers.has('Set-Cookie')) {
+		const responseHeaders = Object.fromEntries(res.headers);
+		const cookieString = responseHeaders['set-cookie'];
+
+		const regexSessionId = /sessionid=([^;]*)/;
+		const regexSameSite = /SameSite=([^;]*)/;
+		const regexPath = /Path=([^;]*)/;
+		const regexMaxAge = /Max-Age=([^;]*)/;
+
+		const sessionID = (cookieString.match(regexSessionId) || [])[1];
+		const sameSite = (cookieString.match(regexSameSite) || [])[1];
+		const path = (cookieString.match(regexPath) || [])[1];
+		const maxAge = (cookieString.match(regexMaxAge) || [])[1];
+
+		/** @type {"lax" | "strict" | "none" | undefined} */
+		let sameSiteValue;
+		if (sameSite === 'Strict') {
+			sameSiteValue = 'strict';
+		} else if (sameSite === 'Lax') {
+			sameSiteValue = 'lax';
+		} else if (sameSite === 'None') {
+			sameSiteValue = 'none';
+		} else {
+			sameSiteValue = undefined;
+		}
+
+		cookies.set('cryptoflow-sessionid', sessionID, {
+			httpOnly: true,
+			sameSite: sameSiteValue,
+			path: path,
+			secure: true,
+			maxAge: maxAge !== undefined ? parseInt(maxAge) : undefined
+		});
+	}
+}
+
+/**
+ * Where to go once logged in.
+ *
+ * @param {string} next - The page the user came from, if any
+ */
+function redirectNext(next) {
+	redirect(
+		303,
+		next !== 'null' && next !== null && next !== undefined && next !== 'undefined' ? next : '/'
+	);
+}
+
 /** @type {import('./$types').Actions} */
 export const actions = {
 	/**
@@ -16,9 +72,10 @@ export const actions = {
 	 * @param request - The request object
 	 * @param fetch - Fetch object from sveltekit
 	 * @param cookies - SvelteKit's cookie object
+	 * @param getClientAddress - The browser's IP address, forwarded so sessions record it
 	 * @returns Error data or redirects user to the home page or the previous page
 	 */
-	login: async ({ request, fetch, cookies }) => {
+	login: async ({ request, fetch, cookies, getClientAddress }) => {
 		const data = await request.formData();
 		const email = String(data.get('email'));
 		const password = String(data.get('password'));
@@ -29,7 +86,9 @@ export const actions = {
 			method: 'POST',
 			credentials: 'include',
 			headers: {
-				'Content-Type': 'application/json'
+				'Content-Type': 'application/json',
+				'X-Forwarded-For': getClientAddress(),
+				'User-Agent': request.headers.get('user-agent') ?? ''
 			},
 			body: JSON.stringify({
 				email: email,
@@ -45,57 +104,68 @@ export const actions = {
 			return fail(400, { errors: errors });
 		}
 
-		if (res.headers.has('Set-Cookie')) {
-			const responseHeaders = Object.fromEntries(res.headers);
-			const cookieString = responseHeaders['set-cookie'];
-
-			const regexSessionId = /sessionid=([^;]*)/;
-			const regexSameSite = /SameSite=([^;]*)/;
-			const regexPath = /Path=([^;]*)/;
-			const regexMaxAge = /Max-Age=([^;]*)/;
-
-			const sessionID = (cookieString.match(regexSessionId) || [])[1];
-			const sameSite = (cookieString.match(regexSameSite) || [])[1];
-			const path = (cookieString.match(regexPath) || [])[1];
-			const maxAge = (cookieString.match(regexMaxAge) || [])[1];
-
-			/** @type {"lax" | "strict" | "none" | undefined} */
-			let sameSiteValue;
-			if (sameSite === 'Strict') {
-				sameSiteValue = 'strict';
-			} else if (sameSite === 'Lax') {
-				sameSiteValue = 'lax';
-			} else if (sameSite === 'None') {
-				sameSiteValue = 'none';
-			} else {
-				sameSiteValue = undefined;
-			}
+		const response = await res.json();
 
-			cookies.set('cryptoflow-sessionid', sessionID, {
-				httpOnly: true,
-				sameSite: sameSiteValue,
-				path: path,
-				secure: true,
-				maxAge: maxAge !== undefined ? parseInt(maxAge) : undefined
-			});
+		// Accounts with 2FA on need a second step before they get a session
+		if (response.two_factor_required) {
+			return { twoFactorToken: response.two_factor_token, next };
 		}
 
-		redirect(
-			303,
-			next !== 'null' && next !== null && next !== undefined && next !== 'undefined' ? next : '/'
-		);
+		setSessionCookie(res, cookies);
+		redirectNext(next);
 	},
-	logout: async ({ fetch, cookies, request }) => {
+	/**
+	 *
+	 * @param request - The request object
+	 * @param fetch - Fetch object from sveltekit
+	 * @param cookies - SvelteKit's cookie object
+	 * @param getClientAddress - The browser's IP address, forwarded so sessions record it
+	 * @returns Error data or redirects user to the home page or the previous page
+	 */
+	twoFactor: async ({ request, fetch, cookies, getClientAddress }) => {
 		const data = await request.formData();
+		const token = String(data.get('token'));
+		const code = String(data.get('code')).trim();
 		const next = String(data.get('next'));
+
 		/** @type {RequestInit} */
 		const requestInitOptions = {
 			method: 'POST',
 			credentials: 'include',
 			headers: {
 				'Content-Type': 'application/json',
-				Cookie: `sessionid=${cookies.get('cryptoflow-sessionid')}`
+				'X-Forwarded-For': getClientAddress(),
+				'User-Agent': request.headers.get('user-agent') ?? ''
+			},
+			body: JSON.stringify({
+				token: token,
+				code: code
+			})
+		};
+
+		const res = await fetch(`${BASE_API_URI}/users/login/2fa`, requestInitOptions);
+
+		if (!res.ok) {
+			const response = await res.json();
+			const errors = [{ id: 1, message: response.message }];
+			// An expired or used-up token means starting over from the password
+			if (res.status === 401) {
+				return fail(401, { errors: errors });
 			}
+			return fail(400, { errors: errors, twoFactorToken: token, next });
+		}
+
+		setSessionCookie(res, cookies);
+		redirectNext(next);
+	},
+	logout: async ({ fetch, cookies, request }) => {
+		const data = await request.formData();
+		const next = String(data.get('next'));
+		/** @type {RequestInit} */
+		const requestInitOptions = {
+			method: 'POST',
+			credentials: 'include',
+			headers: await sessionHeaders(fetch, cookies.get('cryptoflow-sessionid'))
 		};
 
 		const res = await fetch(`${BASE_API_URI}/users/logout`, requestInitOptions);